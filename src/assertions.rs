@@ -0,0 +1,86 @@
+//! Find the failed `assert_eq!`s in the output of `cargo test`, so that the values they compared
+//! can be shown as a diff rather than as two long lines to squint at.
+use crate::strip_ansi_codes;
+
+/// A failed `assert_eq!`, as reported by the standard library:
+///
+/// ```text
+/// assertion `left == right` failed: optional message
+///   left: 4
+///  right: 5
+/// ```
+pub struct FailedAssertion {
+    /// The index of the `left: ` line in the output. The `right: ` line is the next one.
+    pub line: usize,
+    /// The `Debug` representation of the left value.
+    pub left: String,
+    /// The `Debug` representation of the right value.
+    pub right: String,
+}
+
+impl FailedAssertion {
+    /// The text of both values, if they are strings spanning several lines.
+    ///
+    /// `Debug` escapes line breaks, which makes such strings hard to compare by eye.
+    pub fn multiline_strings(&self) -> Option<(String, String)> {
+        let left = unescape(&self.left)?;
+        let right = unescape(&self.right)?;
+        (left.contains('\n') || right.contains('\n')).then_some((left, right))
+    }
+}
+
+/// Find the failed `assert_eq!`s in `output`, in order.
+pub fn find(output: &str) -> Vec<FailedAssertion> {
+    let lines: Vec<_> = output.lines().map(strip_ansi_codes).collect();
+    let mut assertions = vec![];
+    let mut in_assertion = false;
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("assertion `left == right` failed") {
+            in_assertion = true;
+            continue;
+        }
+        if !in_assertion {
+            continue;
+        }
+        // The message, if any, comes first and may span several lines.
+        let left = line.strip_prefix("  left: ");
+        let right = lines.get(i + 1).and_then(|l| l.strip_prefix(" right: "));
+        if let (Some(left), Some(right)) = (left, right) {
+            assertions.push(FailedAssertion {
+                line: i,
+                left: left.to_owned(),
+                right: right.to_owned(),
+            });
+            in_assertion = false;
+        }
+    }
+    assertions
+}
+
+/// The contents of a string, from its `Debug` representation.
+///
+/// Returns `None` if `value` isn't a string.
+fn unescape(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(c @ ('"' | '\'' | '\\')) => unescaped.push(c),
+            // Keep the other escapes, e.g. `\u{1b}`, as they are.
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    Some(unescaped)
+}
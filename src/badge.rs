@@ -0,0 +1,73 @@
+//! A badge showing the progress on a collection, in the style of shields.io, for `wr badge`.
+//!
+//! Learners can embed it in the README of their fork. It's rendered locally: there is no
+//! service to call.
+use crate::dashboard::escape;
+
+/// How tall the badge is, in pixels.
+const HEIGHT: u32 = 20;
+/// The space on either side of each half's text, in pixels.
+const PADDING: u32 = 6;
+
+/// An SVG badge reading `label | percentage%`, coloured from red to green as the percentage grows.
+pub fn render(label: &str, percentage: usize) -> String {
+    let message = format!("{percentage}%");
+    let label_width = text_width(label) + 2 * PADDING;
+    let message_width = text_width(&message) + 2 * PADDING;
+    let width = label_width + message_width;
+    let color = color(percentage);
+    let title = escape(&format!("{label}: {message}"));
+    let label = escape(label);
+    // The text is drawn twice: once, offset and translucent, as its shadow.
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{HEIGHT}" role="img" aria-label="{title}">
+<title>{title}</title>
+<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+<clipPath id="r"><rect width="{width}" height="{HEIGHT}" rx="3" fill="#fff"/></clipPath>
+<g clip-path="url(#r)">
+<rect width="{label_width}" height="{HEIGHT}" fill="#555"/>
+<rect x="{label_width}" width="{message_width}" height="{HEIGHT}" fill="{color}"/>
+<rect width="{width}" height="{HEIGHT}" fill="url(#s)"/>
+</g>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" text-rendering="geometricPrecision" font-size="11">
+<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+<text x="{label_x}" y="14">{label}</text>
+<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+<text x="{message_x}" y="14">{message}</text>
+</g>
+</svg>
+"##,
+        label_x = label_width as f64 / 2.0,
+        message_x = label_width as f64 + message_width as f64 / 2.0,
+    )
+}
+
+/// The colours of shields.io, from `red` to `brightgreen`.
+fn color(percentage: usize) -> &'static str {
+    match percentage {
+        0..=24 => "#e05d44",
+        25..=49 => "#fe7d37",
+        50..=74 => "#dfb317",
+        75..=99 => "#a4a61d",
+        _ => "#4c1",
+    }
+}
+
+/// Roughly how wide `text` is in 11px Verdana, in pixels.
+///
+/// There's no font to measure it with, so it's an estimate based on the usual width of
+/// each kind of character. It errs on the side of wider: a bit of extra padding looks fine.
+fn text_width(text: &str) -> u32 {
+    let width: f64 = text
+        .chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '!' | '|' | '\'' => 3.5,
+            'f' | 'r' | 't' | ' ' | '(' | ')' | '[' | ']' | '/' | '-' | 'I' => 4.5,
+            'm' | 'w' | 'M' | 'W' => 10.0,
+            '%' => 12.0,
+            c if c.is_ascii_uppercase() || c.is_ascii_digit() => 7.5,
+            _ => 7.0,
+        })
+        .sum();
+    width.ceil() as u32
+}
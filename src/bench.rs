@@ -0,0 +1,84 @@
+//! Extract timings from the output of `cargo bench`, to hold exercises to a performance budget.
+//!
+//! Both the built-in benchmark harness (`#[bench]`) and [criterion](https://docs.rs/criterion)
+//! are supported.
+
+/// A benchmark that must run faster than a given threshold for an exercise to be solved.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct BenchmarkThreshold {
+    /// The name of the benchmark, as reported by `cargo bench`.
+    pub name: String,
+    /// The maximum time per iteration, in nanoseconds.
+    pub max_ns: f64,
+}
+
+impl BenchmarkThreshold {
+    /// Check the output of `cargo bench` against the threshold, returning an explanation
+    /// if the benchmark is too slow or can't be found.
+    pub fn check(&self, output: &str) -> Result<(), String> {
+        let Some(time) = time_per_iteration_ns(output, &self.name) else {
+            return Err(format!(
+                "Benchmark `{}` wasn't found in the output of `cargo bench`.",
+                self.name
+            ));
+        };
+        if time > self.max_ns {
+            return Err(format!(
+                "Benchmark `{}` took {time:.1} ns per iteration, more than the {:.1} ns allowed.",
+                self.name, self.max_ns
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The time per iteration, in nanoseconds, of the benchmark called `name`.
+///
+/// For criterion benchmarks, it's the estimate in the middle of the confidence interval.
+pub fn time_per_iteration_ns(output: &str, name: &str) -> Option<f64> {
+    let mut previous_line = "";
+    for line in output.lines() {
+        // The built-in harness: `test fib_20 ... bench:   1,234.56 ns/iter (+/- 12.34)`
+        if let Some(rest) = line.strip_prefix("test ") {
+            if let Some((bench_name, result)) = rest.split_once(" ... bench:") {
+                if bench_name.trim() == name {
+                    let value = result.split_whitespace().next()?.replace(',', "");
+                    return value.parse().ok();
+                }
+            }
+        }
+        // Criterion: `fib 20    time:   [26.029 µs 26.251 µs 26.505 µs]`.
+        // Long names get a line of their own, with the timings on the following one.
+        if let Some((bench_name, result)) = line.split_once("time:") {
+            let bench_name = match bench_name.trim() {
+                "" => previous_line.trim(),
+                bench_name => bench_name,
+            };
+            if bench_name == name {
+                let tokens: Vec<_> = result
+                    .trim()
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split_whitespace()
+                    .collect();
+                let [_, _, value, unit, _, _] = tokens.as_slice() else {
+                    return None;
+                };
+                return Some(value.parse::<f64>().ok()? * unit_in_ns(unit)?);
+            }
+        }
+        previous_line = line;
+    }
+    None
+}
+
+fn unit_in_ns(unit: &str) -> Option<f64> {
+    match unit {
+        "ps" => Some(0.001),
+        "ns" => Some(1.0),
+        "µs" | "us" => Some(1_000.0),
+        "ms" => Some(1_000_000.0),
+        "s" => Some(1_000_000_000.0),
+        _ => None,
+    }
+}
@@ -0,0 +1,158 @@
+//! Render the progress on a collection as an HTML page, for `wr serve`.
+use crate::{CollectionMetadata, ExerciseDefinition, ExerciseStatus};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Render the progress board.
+///
+/// `failures` maps exercises to the output of their last failed verification run, if known.
+/// Optional exercises (see [`ExerciseCollection::is_optional`](crate::ExerciseCollection::is_optional))
+/// are labelled as such, and left out of the count of solved exercises.
+pub fn render(
+    metadata: &CollectionMetadata,
+    statuses: &[(ExerciseDefinition, ExerciseStatus)],
+    is_optional: impl Fn(&ExerciseDefinition) -> bool,
+    failures: &HashMap<ExerciseDefinition, String>,
+) -> String {
+    let mandatory: Vec<_> = statuses.iter().filter(|(e, _)| !is_optional(e)).collect();
+    let n_solved = mandatory
+        .iter()
+        .filter(|(_, s)| *s == ExerciseStatus::Solved)
+        .count();
+    let title = escape(metadata.title.as_deref().unwrap_or("Workshop progress"));
+    let mut body = String::new();
+    if let Some(description) = &metadata.description {
+        writeln!(
+            body,
+            r#"<p class="description">{}</p>"#,
+            escape(description)
+        )
+        .unwrap();
+    }
+    writeln!(
+        body,
+        r#"<p class="summary">{n_solved}/{} exercises solved</p>
+<form method="post" action="/verify"><button>Check all opened exercises</button></form>"#,
+        mandatory.len()
+    )
+    .unwrap();
+    for chapter in statuses.chunk_by(|(a, _), (b, _)| a.chapter_number() == b.chapter_number()) {
+        let (first, _) = &chapter[0];
+        writeln!(
+            body,
+            "<h2>({:02}) {}</h2>\n<table>",
+            first.chapter_number(),
+            escape(first.chapter_name())
+        )
+        .unwrap();
+        for (exercise, status) in chapter {
+            let action = if *status == ExerciseStatus::Unopened {
+                String::new()
+            } else {
+                format!(
+                    r#"<form method="post" action="/verify?chapter={}&amp;exercise={}"><button>Check</button></form>"#,
+                    percent_encode(&exercise.chapter()),
+                    percent_encode(&exercise.exercise())
+                )
+            };
+            writeln!(
+                body,
+                r#"<tr class="{status}"><td>({:02}) {}{}</td><td>{status}</td><td>{action}</td></tr>"#,
+                exercise.exercise_number(),
+                escape(exercise.exercise_name()),
+                if is_optional(exercise) {
+                    r#" <span class="optional">(optional)</span>"#
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+            if let Some(output) = failures.get(exercise) {
+                writeln!(
+                    body,
+                    r#"<tr><td colspan="3"><details><summary>Last failure</summary><pre>{}</pre></details></td></tr>"#,
+                    escape(output)
+                )
+                .unwrap();
+            }
+        }
+        writeln!(body, "</table>").unwrap();
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title} - wr</title>
+<style>
+body {{ font-family: sans-serif; max-width: 60em; margin: 2em auto; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td {{ padding: 0.3em 0.6em; border-bottom: 1px solid #ddd; }}
+tr.solved td {{ color: #1a7f37; }}
+tr.failed td {{ color: #cf222e; }}
+tr.opened td {{ color: #9a6700; }}
+tr.unopened td {{ color: #888; }}
+.optional {{ font-style: italic; }}
+.description {{ color: #555; }}
+pre {{ background: #f6f8fa; padding: 1em; overflow-x: auto; }}
+form {{ margin: 0; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}
+</body>
+</html>
+"#
+    )
+}
+
+/// Escape text so that it can be safely embedded in an HTML page.
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Percent-encode text so that it can be used as a query parameter value.
+pub fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            write!(encoded, "%{byte:02X}").unwrap();
+        }
+    }
+    encoded
+}
+
+/// Decode a percent-encoded query parameter value, the inverse of [`percent_encode`].
+///
+/// `+` is decoded as a space, as browsers do when submitting forms.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let byte = s
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = byte {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
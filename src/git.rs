@@ -0,0 +1,67 @@
+//! Compare exercises with (and revert them to) their pristine state, as recorded in the `git` history of the workshop.
+use anyhow::{bail, Context};
+use std::path::Path;
+use std::process::Command;
+
+/// The changes you made to the files in `dir`, compared to the last commit, as a unified diff.
+///
+/// New files, not yet tracked by `git`, are included. Ignored files (e.g. `target`) are not.
+pub fn diff(dir: &Path, colour: bool) -> Result<String, anyhow::Error> {
+    let colour = if colour {
+        "--color=always"
+    } else {
+        "--color=never"
+    };
+    let mut diff = git(&["diff", colour, "HEAD", "--"], dir, &[0])?;
+    for file in untracked_files(dir)? {
+        // `--no-index` exits with `1` when the files differ, which is always the case here.
+        diff.push_str(&git(
+            &["diff", colour, "--no-index", "--", "/dev/null"],
+            Path::new(&file),
+            &[0, 1],
+        )?);
+    }
+    Ok(diff)
+}
+
+/// Revert the files in `dir` to their state as of the last commit.
+///
+/// Files that are not tracked by `git` are removed, while ignored files (e.g. `target`) are left alone.
+pub fn restore(dir: &Path) -> Result<(), anyhow::Error> {
+    git(&["checkout", "HEAD", "--"], dir, &[0])?;
+    for file in untracked_files(dir)? {
+        fs_err::remove_file(&file)?;
+    }
+    Ok(())
+}
+
+/// The files in `dir` that are not tracked by `git` (and not ignored either).
+fn untracked_files(dir: &Path) -> Result<Vec<String>, anyhow::Error> {
+    let output = git(
+        &["ls-files", "--others", "--exclude-standard", "--"],
+        dir,
+        &[0],
+    )?;
+    Ok(output.lines().map(ToOwned::to_owned).collect())
+}
+
+/// Run a `git` command with `path` as its last argument and return its output.
+fn git(args: &[&str], path: &Path, success_codes: &[i32]) -> Result<String, anyhow::Error> {
+    let output = Command::new("git")
+        .args(args)
+        .arg(path)
+        .output()
+        .with_context(|| format!("Failed to run `git {}`", args.join(" ")))?;
+    if !output
+        .status
+        .code()
+        .is_some_and(|code| success_codes.contains(&code))
+    {
+        bail!(
+            "`git {}` failed:\n{}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    String::from_utf8(output.stdout).context("The output of `git` is not valid UTF-8")
+}
@@ -0,0 +1,122 @@
+//! Tests that the author of a collection keeps out of sight, in the `hidden_tests` folder of an exercise.
+//!
+//! They are copied into the `tests` folder of the exercise right before it is verified, and removed
+//! right after: editing the visible tests is not enough to get an exercise to pass.
+//! If `wr` is interrupted in the meantime (e.g. with Ctrl+C), they are removed on the way out or,
+//! failing that, the next time the exercise is verified.
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The name of the folder, inside an exercise, that contains its hidden tests.
+pub const HIDDEN_TESTS_DIR: &str = "hidden_tests";
+
+/// The prefix of the copies of the hidden tests, to avoid overwriting the visible tests.
+const PREFIX: &str = "wr_hidden_";
+
+/// Whether a filesystem change at `path` may have been caused by injecting (or removing)
+/// hidden tests, rather than by the user.
+pub fn is_injected(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "tests" || name.to_string_lossy().starts_with(PREFIX))
+}
+
+/// Everything injected by the [`InjectedTests`] that are still around, in the order it was created,
+/// so that it can be removed if `wr` is interrupted.
+static INJECTED: Mutex<Vec<PathBuf>> = Mutex::new(vec![]);
+
+/// The hidden tests of an exercise, copied into its `tests` folder.
+///
+/// They are removed when this value is dropped.
+pub struct InjectedTests {
+    /// The `tests` folder, if it didn't exist before we injected the hidden tests, followed by
+    /// the copies of the hidden tests.
+    paths: Vec<PathBuf>,
+}
+
+/// Copy the hidden tests of the exercise in `exercise_dir`, if it has any, into its `tests` folder,
+/// where `cargo test` picks them up as integration tests.
+pub fn inject(exercise_dir: &Path) -> Result<Option<InjectedTests>, anyhow::Error> {
+    let hidden_dir = exercise_dir.join(HIDDEN_TESTS_DIR);
+    if !hidden_dir.is_dir() {
+        return Ok(None);
+    }
+    #[cfg(unix)]
+    clean_up_on_interrupt();
+    let tests_dir = exercise_dir.join("tests");
+    let mut injected = InjectedTests { paths: vec![] };
+    if tests_dir.exists() {
+        // Left behind by a run of `wr` that couldn't clean up after itself.
+        for entry in fs_err::read_dir(&tests_dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_injected(&path) {
+                fs_err::remove_file(&path)?;
+            }
+        }
+    } else {
+        fs_err::create_dir(&tests_dir)?;
+        injected.track(tests_dir.clone());
+    }
+    for entry in fs_err::read_dir(&hidden_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "rs") {
+            continue;
+        }
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let destination = tests_dir.join(format!("{PREFIX}{}", file_name.to_string_lossy()));
+        // Tracked first: a partial copy must be removed too.
+        injected.track(destination.clone());
+        fs_err::copy(&path, &destination)?;
+    }
+    Ok(Some(injected))
+}
+
+impl InjectedTests {
+    fn track(&mut self, path: PathBuf) {
+        INJECTED.lock().unwrap().push(path.clone());
+        self.paths.push(path);
+    }
+}
+
+impl Drop for InjectedTests {
+    fn drop(&mut self) {
+        remove(&self.paths);
+        INJECTED
+            .lock()
+            .unwrap()
+            .retain(|path| !self.paths.contains(path));
+    }
+}
+
+/// Remove files and (empty) folders, in the reverse order of their creation.
+fn remove(paths: &[PathBuf]) {
+    // Best effort: there is nothing sensible we can do if the clean-up fails.
+    for path in paths.iter().rev() {
+        if path.is_dir() {
+            let _ = fs_err::remove_dir(path);
+        } else {
+            let _ = fs_err::remove_file(path);
+        }
+    }
+}
+
+/// Remove the hidden tests that are still around when `wr` is interrupted or terminated,
+/// then exit as the signal would have made it.
+#[cfg(unix)]
+fn clean_up_on_interrupt() {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+    INSTALLED.call_once(|| {
+        let Ok(mut signals) = signal_hook::iterator::Signals::new([SIGINT, SIGTERM, SIGHUP]) else {
+            return;
+        };
+        std::thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                remove(&std::mem::take(&mut *INJECTED.lock().unwrap()));
+                std::process::exit(128 + signal);
+            }
+        });
+    });
+}
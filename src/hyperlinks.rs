@@ -0,0 +1,108 @@
+//! Terminal hyperlinks (OSC 8), so that learners can open an exercise, or the line of code that
+//! made it fail, with a click.
+//!
+//! Terminals that don't understand them would print garbage, so they are only emitted when
+//! the terminal is known to support them. `FORCE_HYPERLINK=1` (or `0`) overrides the detection.
+use regex::{Captures, Regex};
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// `true` if the terminal `wr` runs in is known to support hyperlinks.
+pub fn supported() -> bool {
+    let var = |name| std::env::var(name).unwrap_or_default();
+    if let Some(force) = std::env::var_os("FORCE_HYPERLINK") {
+        return !force.is_empty() && force != "0";
+    }
+    if ["WT_SESSION", "KONSOLE_VERSION", "DOMTERM"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some())
+    {
+        return true;
+    }
+    // GNOME Terminal, Tilix and the other terminals built on VTE, since 0.50.
+    if var("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000) {
+        return true;
+    }
+    matches!(
+        var("TERM_PROGRAM").as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper" | "WarpTerminal"
+    ) || matches!(
+        var("TERM").as_str(),
+        "xterm-kitty" | "xterm-ghostty" | "alacritty" | "foot" | "wezterm"
+    )
+}
+
+/// `text`, as a hyperlink to `url`.
+pub fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// The `file://` URL of `path`, or `None` if it doesn't exist.
+pub fn file_url(path: &Path) -> Option<String> {
+    let path = fs_err::canonicalize(path).ok()?;
+    let path = path.to_string_lossy().replace('\\', "/");
+    // Windows' canonical paths are "verbatim" ones, e.g. `\\?\C:\Users`.
+    let path = path.trim_start_matches("//?/");
+    let mut url = String::from("file://");
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                url.push(byte as char)
+            }
+            _ => write!(url, "%{byte:02X}").unwrap(),
+        }
+    }
+    Some(url)
+}
+
+/// `path:line:column` in the errors of the compiler (`--> src/lib.rs:3:5`) and in panics
+/// (`panicked at src/lib.rs:3:30:`), possibly with terminal colours in between.
+fn location_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?:-->|panicked at)(?:\s|\x1b\[[0-9;]*m)+(?<location>(?<path>[^\s:\x1b]+\.rs):\d+:\d+)")
+            .unwrap()
+    })
+}
+
+/// The first `path:line:column` that went wrong according to `output`, if any.
+pub fn first_location(output: &str) -> Option<&str> {
+    location_regex()
+        .captures(output)
+        .and_then(|c| c.name("location"))
+        .map(|m| m.as_str())
+}
+
+/// Turn the locations in `output` into hyperlinks to the files they point to, see [`link_location`].
+pub fn link_locations(output: &str, dir: &Path) -> String {
+    location_regex()
+        .replace_all(output, |captures: &Captures| {
+            let whole = captures.get(0).unwrap();
+            let location = captures.name("location").unwrap();
+            // Keep what comes before the location, e.g. `--> `, as it is.
+            format!(
+                "{}{}",
+                &whole.as_str()[..location.start() - whole.start()],
+                link_location(location.as_str(), dir)
+            )
+        })
+        .into_owned()
+}
+
+/// `location` (`path:line:column`, relative to `dir`) as a hyperlink to the file it points to.
+///
+/// It's left as it is if the file doesn't exist.
+pub fn link_location(location: &str, dir: &Path) -> String {
+    let url = location
+        .rsplitn(3, ':')
+        .nth(2)
+        .and_then(|path| file_url(&dir.join(path)));
+    match url {
+        Some(url) => link(&url, location),
+        None => location.to_owned(),
+    }
+}
@@ -0,0 +1,600 @@
+//! Translations of the messages learners see while they work through a collection: prompts,
+//! failure banners and progress.
+//!
+//! The locale is set by `locale` in `.wr.toml` or, failing that, by the environment
+//! (`LC_ALL`, `LC_MESSAGES`, then `LANG`). Unsupported locales fall back to English.
+//! Collections can still replace some of the messages in their `[messages]` table,
+//! see [`crate::Messages`].
+
+/// A language `wr` speaks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    It,
+    Fr,
+    De,
+    Es,
+}
+
+impl Locale {
+    /// The supported locales, by language code.
+    pub const ALL: &'static [(&'static str, Locale)] = &[
+        ("en", Locale::En),
+        ("it", Locale::It),
+        ("fr", Locale::Fr),
+        ("de", Locale::De),
+        ("es", Locale::Es),
+    ];
+
+    /// The locale for `tag`, e.g. `it`, `it-IT` or `it_IT.UTF-8`, or `None` if it's not supported.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::ALL
+            .iter()
+            .find(|(code, _)| *code == language)
+            .map(|(_, locale)| *locale)
+    }
+
+    /// The locale of the environment, if it's set and supported.
+    pub fn from_env() -> Option<Self> {
+        // The first variable that's set wins, as it does for every other program.
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::parse(&value))
+    }
+
+    /// The messages in this locale.
+    pub fn catalog(self) -> &'static Catalog {
+        match self {
+            Locale::En => &EN,
+            Locale::It => &IT,
+            Locale::Fr => &FR,
+            Locale::De => &DE,
+            Locale::Es => &ES,
+        }
+    }
+}
+
+/// The messages of a locale.
+///
+/// The ones that collections can replace are used through [`crate::Messages`] rather than directly.
+pub struct Catalog {
+    pub(crate) failure: &'static str,
+    pub(crate) not_finished: &'static str,
+    pub(crate) next_prompt: &'static str,
+    pub(crate) opened: &'static str,
+    pub(crate) completed: &'static str,
+    /// The possible answers to a yes/no question, e.g. `[y/n]`.
+    answers: &'static str,
+    /// The words (and their initials) that mean "yes" and "no".
+    yes: &'static [&'static str],
+    no: &'static [&'static str],
+    invalid_answer: &'static str,
+    running_tests: &'static str,
+    stage_failed: &'static str,
+    output: &'static str,
+    lines_omitted: &'static str,
+    see_everything: &'static str,
+    first_error: &'static str,
+    optional_failure: &'static str,
+    solved_in_chapter: &'static str,
+    solved: &'static str,
+    optional_solved: &'static str,
+    passed_verification: &'static str,
+    no_more_tasks: &'static str,
+    collection_completed: &'static str,
+    get_started: &'static str,
+    run_again: &'static str,
+    skipped: &'static str,
+    move_on: &'static str,
+    not_verified_skipped: &'static str,
+    not_verified_not_rechecked: &'static str,
+    not_verified_unchanged: &'static str,
+    failed_at_stage: &'static str,
+    closed: &'static str,
+    feedback: &'static str,
+    score: &'static str,
+    chapter_score: &'static str,
+    watching: &'static str,
+    no_hints: &'static str,
+    hint: &'static str,
+    no_more_hints: &'static str,
+    status: StatusLabels,
+}
+
+/// The labels of the summary printed by `wr status`, and the values that stand for nothing.
+pub struct StatusLabels {
+    pub workshop: &'static str,
+    pub authors: &'static str,
+    pub track: &'static str,
+    pub current: &'static str,
+    pub progress: &'static str,
+    pub last_failure: &'static str,
+    pub elapsed: &'static str,
+    /// Shown when no exercise has been opened yet.
+    pub no_current: &'static str,
+    pub none: &'static str,
+    pub unknown: &'static str,
+}
+
+impl Catalog {
+    /// The possible answers to a yes/no question, e.g. `[y/n]`.
+    pub fn answers(&self) -> &str {
+        self.answers
+    }
+
+    /// Interpret the answer to a yes/no question. English answers are always understood.
+    pub fn parse_answer(&self, answer: &str) -> Option<bool> {
+        let answer = answer.trim().to_lowercase();
+        let answer = answer.as_str();
+        if self.yes.contains(&answer) || EN.yes.contains(&answer) {
+            Some(true)
+        } else if self.no.contains(&answer) || EN.no.contains(&answer) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Shown when the answer to a yes/no question isn't understood.
+    pub fn invalid_answer(&self) -> &str {
+        self.invalid_answer
+    }
+
+    pub fn running_tests(&self) -> &str {
+        self.running_tests
+    }
+
+    /// The title of the output of a failed command.
+    pub fn stage_failed(&self, stage: &str) -> String {
+        self.stage_failed.replace("{stage}", stage)
+    }
+
+    /// Introduces what a failed command printed.
+    pub fn output(&self) -> &str {
+        self.output
+    }
+
+    /// Stands for the lines of output that were left out.
+    pub fn lines_omitted(&self, n: usize) -> String {
+        self.lines_omitted.replace("{n}", &n.to_string())
+    }
+
+    /// Points to `command`, to see the whole output of a failed command.
+    pub fn see_everything(&self, command: &str) -> String {
+        self.see_everything.replace("{command}", command)
+    }
+
+    /// Points to the `path:line:column` where things first went wrong.
+    pub fn first_error(&self, location: &str) -> String {
+        self.first_error.replace("{location}", location)
+    }
+
+    /// Shown when an optional exercise fails verification.
+    pub fn optional_failure(&self, exercise: &str) -> String {
+        self.optional_failure.replace("{exercise}", exercise)
+    }
+
+    pub fn solved_in_chapter(&self, n_solved: usize, total: usize) -> String {
+        self.solved_in_chapter
+            .replace("{n_solved}", &n_solved.to_string())
+            .replace("{total}", &total.to_string())
+    }
+
+    pub fn solved(&self, n_solved: usize, total: usize, percentage: usize) -> String {
+        self.solved
+            .replace("{n_solved}", &n_solved.to_string())
+            .replace("{total}", &total.to_string())
+            .replace("{percentage}", &percentage.to_string())
+    }
+
+    /// How many optional exercises have been solved, appended to [`Catalog::solved`].
+    ///
+    /// It's empty if there are no optional exercises.
+    pub fn optional_solved(&self, n_solved: usize, total: usize) -> String {
+        if total == 0 {
+            return String::new();
+        }
+        self.optional_solved
+            .replace("{n_solved}", &n_solved.to_string())
+            .replace("{total}", &total.to_string())
+    }
+
+    pub fn passed_verification(&self, n_passed: usize, total: usize) -> String {
+        self.passed_verification
+            .replace("{n_passed}", &n_passed.to_string())
+            .replace("{total}", &total.to_string())
+    }
+
+    /// Tells learners where the exercise they just opened is, e.g. `"exercises/01_intro/00_welcome"`.
+    pub fn get_started(&self, path: &str) -> String {
+        self.get_started.replace("{path}", path)
+    }
+
+    /// Follows [`Catalog::get_started`].
+    pub fn run_again(&self) -> &str {
+        self.run_again
+    }
+
+    pub fn skipped(&self, exercise: &str) -> String {
+        self.skipped.replace("{exercise}", exercise)
+    }
+
+    /// Follows [`Catalog::skipped`].
+    pub fn move_on(&self) -> &str {
+        self.move_on
+    }
+
+    /// Why an opened exercise wasn't verified: it was skipped.
+    pub fn not_verified_skipped(&self) -> &str {
+        self.not_verified_skipped
+    }
+
+    /// Why an opened exercise wasn't verified: solved exercises are only verified again on demand.
+    pub fn not_verified_not_rechecked(&self) -> &str {
+        self.not_verified_not_rechecked
+    }
+
+    /// Why an opened exercise wasn't verified: nothing changed since it was solved.
+    pub fn not_verified_unchanged(&self) -> &str {
+        self.not_verified_unchanged
+    }
+
+    /// Next to an exercise, in a list of the ones that failed verification.
+    pub fn failed_at_stage(&self, stage: &str) -> String {
+        self.failed_at_stage.replace("{stage}", stage)
+    }
+
+    pub fn closed(&self, exercise: &str) -> String {
+        self.closed.replace("{exercise}", exercise)
+    }
+
+    /// Shown once the collection is completed, if it has a repository to point to.
+    pub fn feedback(&self, repository: &str) -> String {
+        self.feedback.replace("{repository}", repository)
+    }
+
+    /// The final score computed by `wr grade`, as a percentage.
+    pub fn score(
+        &self,
+        score: f64,
+        points: f64,
+        max_points: f64,
+        n_solved: usize,
+        total: usize,
+    ) -> String {
+        self.score
+            .replace("{score}", &format!("{score:.1}"))
+            .replace("{points}", &points.to_string())
+            .replace("{max_points}", &max_points.to_string())
+            .replace("{n_solved}", &n_solved.to_string())
+            .replace("{total}", &total.to_string())
+    }
+
+    /// The score of a chapter, next to its name, in the report of `wr grade`.
+    pub fn chapter_score(
+        &self,
+        n_solved: usize,
+        total: usize,
+        points: f64,
+        max_points: f64,
+    ) -> String {
+        self.chapter_score
+            .replace("{n_solved}", &n_solved.to_string())
+            .replace("{total}", &total.to_string())
+            .replace("{points}", &points.to_string())
+            .replace("{max_points}", &max_points.to_string())
+    }
+
+    /// Shown by `wr watch` while it waits for the exercise to change.
+    pub fn watching(&self, exercise: &str) -> String {
+        self.watching.replace("{exercise}", exercise)
+    }
+
+    pub fn no_hints(&self, exercise: &str) -> String {
+        self.no_hints.replace("{exercise}", exercise)
+    }
+
+    /// The `n`-th hint out of `total`, starting from 1.
+    pub fn hint(&self, n: usize, total: usize, hint: &str) -> String {
+        self.hint
+            .replace("{n}", &n.to_string())
+            .replace("{total}", &total.to_string())
+            .replace("{hint}", hint)
+    }
+
+    pub fn no_more_hints(&self) -> &str {
+        self.no_more_hints
+    }
+
+    pub fn status(&self) -> &StatusLabels {
+        &self.status
+    }
+
+    /// Shown when the last exercise has been solved, with the name of the collection if it has one.
+    pub fn no_more_tasks(&self, title: Option<&str>) -> String {
+        match title {
+            Some(title) => self.collection_completed.replace("{title}", title),
+            None => self.no_more_tasks.to_owned(),
+        }
+    }
+}
+
+static EN: Catalog = Catalog {
+    failure: "Meditate on your approach and return. Mountains are merely mountains.",
+    not_finished: "Eternity lies ahead of us, and behind. Your path is not yet finished. 🍂",
+    next_prompt: "Do you want to open the next exercise, {exercise}?",
+    opened: "Ahead of you lies {exercise}",
+    completed: "What is the sound of one hand clapping (for you)? 🌟",
+    answers: "[y/n]",
+    yes: &["y", "yes"],
+    no: &["n", "no"],
+    invalid_answer: "Please answer either yes or no.",
+    running_tests: "Running tests...",
+    stage_failed: "Stage `{stage}` failed. Failed to run:",
+    output: "Output:",
+    lines_omitted: "[... {n} more line(s) ...]",
+    see_everything: "Run `{command}` to see everything.",
+    first_error: "The first error is at {location}.",
+    optional_failure:
+        "{exercise} is optional: you can come back to it later, or leave it unsolved.",
+    solved_in_chapter: "{n_solved}/{total} solved in this chapter",
+    solved: "{n_solved}/{total} exercises solved ({percentage}%)",
+    optional_solved: ", {n_solved}/{total} optional",
+    passed_verification: "{n_passed}/{total} exercises passed verification.",
+    no_more_tasks: "There will be no more tasks.",
+    collection_completed: "There will be no more tasks: you have completed {title}.",
+    get_started: "Open {path} in your editor and get started!",
+    run_again: "Run `wr` again to compile the exercise and execute its tests.",
+    skipped: "{exercise} has been skipped.",
+    move_on: "Run `wr` to move on to the next exercise.",
+    not_verified_skipped: "Skipped",
+    not_verified_not_rechecked: "Not rechecked",
+    not_verified_unchanged: "Unchanged since it was solved",
+    failed_at_stage: "stage `{stage}` failed",
+    closed: "{exercise} has been closed.",
+    feedback: "Feedback on the workshop is welcome, at {repository}.",
+    score: "Score: {score}% ({points}/{max_points} points, {n_solved}/{total} exercises solved)",
+    chapter_score: "{n_solved}/{total} solved, {points}/{max_points} points",
+    watching: "Watching {exercise} for changes...",
+    no_hints: "There are no hints for {exercise}.",
+    hint: "Hint {n}/{total}: {hint}",
+    no_more_hints: "There are no more hints for this exercise.",
+    status: StatusLabels {
+        workshop: "Workshop:",
+        authors: "Authors:",
+        track: "Track:",
+        current: "Current exercise:",
+        progress: "Progress:",
+        last_failure: "Last failure:",
+        elapsed: "Time elapsed:",
+        no_current: "None, run `wr` to open the first exercise",
+        none: "None",
+        unknown: "Unknown",
+    },
+};
+
+static IT: Catalog = Catalog {
+    failure: "Medita sul tuo approccio e ritorna. Le montagne sono soltanto montagne.",
+    not_finished:
+        "L'eternità ci sta davanti, e alle spalle. Il tuo cammino non è ancora finito. 🍂",
+    next_prompt: "Vuoi aprire il prossimo esercizio, {exercise}?",
+    opened: "Davanti a te c'è {exercise}",
+    completed: "Qual è il suono di una mano sola che applaude (per te)? 🌟",
+    answers: "[s/n]",
+    yes: &["s", "si", "sì"],
+    no: &["n", "no"],
+    invalid_answer: "Rispondi sì o no.",
+    running_tests: "Esecuzione dei test...",
+    stage_failed: "La fase `{stage}` è fallita. Comando non riuscito:",
+    output: "Output:",
+    lines_omitted: "[... altre {n} righe ...]",
+    see_everything: "Esegui `{command}` per vedere tutto.",
+    first_error: "Il primo errore è in {location}.",
+    optional_failure: "{exercise} è facoltativo: puoi tornarci più tardi, o lasciarlo irrisolto.",
+    solved_in_chapter: "{n_solved}/{total} risolti in questo capitolo",
+    solved: "{n_solved}/{total} esercizi risolti ({percentage}%)",
+    optional_solved: ", {n_solved}/{total} facoltativi",
+    passed_verification: "{n_passed}/{total} esercizi hanno superato la verifica.",
+    no_more_tasks: "Non ci saranno altri compiti.",
+    collection_completed: "Non ci saranno altri compiti: hai completato {title}.",
+    get_started: "Apri {path} nel tuo editor e inizia!",
+    run_again: "Esegui di nuovo `wr` per compilare l'esercizio ed eseguirne i test.",
+    skipped: "{exercise} è stato saltato.",
+    move_on: "Esegui `wr` per passare al prossimo esercizio.",
+    not_verified_skipped: "Saltato",
+    not_verified_not_rechecked: "Non ricontrollato",
+    not_verified_unchanged: "Invariato da quando è stato risolto",
+    failed_at_stage: "la fase `{stage}` è fallita",
+    closed: "{exercise} è stato chiuso.",
+    feedback: "Ogni commento sul workshop è benvenuto, su {repository}.",
+    score: "Punteggio: {score}% ({points}/{max_points} punti, {n_solved}/{total} esercizi risolti)",
+    chapter_score: "{n_solved}/{total} risolti, {points}/{max_points} punti",
+    watching: "In attesa di modifiche a {exercise}...",
+    no_hints: "Non ci sono suggerimenti per {exercise}.",
+    hint: "Suggerimento {n}/{total}: {hint}",
+    no_more_hints: "Non ci sono altri suggerimenti per questo esercizio.",
+    status: StatusLabels {
+        workshop: "Workshop:",
+        authors: "Autori:",
+        track: "Percorso:",
+        current: "Esercizio attuale:",
+        progress: "Progressi:",
+        last_failure: "Ultimo fallimento:",
+        elapsed: "Tempo trascorso:",
+        no_current: "Nessuno, esegui `wr` per aprire il primo esercizio",
+        none: "Nessuno",
+        unknown: "Sconosciuto",
+    },
+};
+
+static FR: Catalog = Catalog {
+    failure: "Méditez sur votre approche et revenez. Les montagnes ne sont que des montagnes.",
+    not_finished:
+        "L'éternité s'étend devant nous, et derrière. Votre chemin n'est pas encore terminé. 🍂",
+    next_prompt: "Voulez-vous ouvrir l'exercice suivant, {exercise} ?",
+    opened: "Devant vous se trouve {exercise}",
+    completed: "Quel est le son d'une seule main qui applaudit (pour vous) ? 🌟",
+    answers: "[o/n]",
+    yes: &["o", "oui"],
+    no: &["n", "non"],
+    invalid_answer: "Veuillez répondre oui ou non.",
+    running_tests: "Exécution des tests...",
+    stage_failed: "L'étape `{stage}` a échoué. Échec de la commande :",
+    output: "Sortie :",
+    lines_omitted: "[... {n} ligne(s) de plus ...]",
+    see_everything: "Lancez `{command}` pour tout voir.",
+    first_error: "La première erreur se trouve à {location}.",
+    optional_failure:
+        "{exercise} est facultatif : vous pouvez y revenir plus tard, ou le laisser non résolu.",
+    solved_in_chapter: "{n_solved}/{total} résolus dans ce chapitre",
+    solved: "{n_solved}/{total} exercices résolus ({percentage} %)",
+    optional_solved: ", {n_solved}/{total} facultatifs",
+    passed_verification: "{n_passed}/{total} exercices ont passé la vérification.",
+    no_more_tasks: "Il n'y aura plus d'autres tâches.",
+    collection_completed: "Il n'y aura plus d'autres tâches : vous avez terminé {title}.",
+    get_started: "Ouvrez {path} dans votre éditeur et lancez-vous !",
+    run_again: "Relancez `wr` pour compiler l'exercice et exécuter ses tests.",
+    skipped: "{exercise} a été passé.",
+    move_on: "Lancez `wr` pour passer à l'exercice suivant.",
+    not_verified_skipped: "Passé",
+    not_verified_not_rechecked: "Non revérifié",
+    not_verified_unchanged: "Inchangé depuis sa résolution",
+    failed_at_stage: "l'étape `{stage}` a échoué",
+    closed: "{exercise} a été fermé.",
+    feedback: "Vos retours sur l'atelier sont les bienvenus, sur {repository}.",
+    score: "Score : {score} % ({points}/{max_points} points, {n_solved}/{total} exercices résolus)",
+    chapter_score: "{n_solved}/{total} résolus, {points}/{max_points} points",
+    watching: "En attente de modifications de {exercise}...",
+    no_hints: "Il n'y a pas d'indices pour {exercise}.",
+    hint: "Indice {n}/{total} : {hint}",
+    no_more_hints: "Il n'y a plus d'indices pour cet exercice.",
+    status: StatusLabels {
+        workshop: "Atelier :",
+        authors: "Auteurs :",
+        track: "Parcours :",
+        current: "Exercice en cours :",
+        progress: "Progression :",
+        last_failure: "Dernier échec :",
+        elapsed: "Temps écoulé :",
+        no_current: "Aucun, lancez `wr` pour ouvrir le premier exercice",
+        none: "Aucun",
+        unknown: "Inconnu",
+    },
+};
+
+static DE: Catalog = Catalog {
+    failure: "Denke über deinen Ansatz nach und kehre zurück. Berge sind nur Berge.",
+    not_finished: "Die Ewigkeit liegt vor uns und hinter uns. Dein Weg ist noch nicht zu Ende. 🍂",
+    next_prompt: "Möchtest du die nächste Übung öffnen, {exercise}?",
+    opened: "Vor dir liegt {exercise}",
+    completed: "Wie klingt das Klatschen einer Hand (für dich)? 🌟",
+    answers: "[j/n]",
+    yes: &["j", "ja"],
+    no: &["n", "nein"],
+    invalid_answer: "Bitte antworte mit ja oder nein.",
+    running_tests: "Tests werden ausgeführt...",
+    stage_failed: "Die Phase `{stage}` ist fehlgeschlagen. Fehlgeschlagener Befehl:",
+    output: "Ausgabe:",
+    lines_omitted: "[... {n} weitere Zeile(n) ...]",
+    see_everything: "Führe `{command}` aus, um alles zu sehen.",
+    first_error: "Der erste Fehler liegt bei {location}.",
+    optional_failure:
+        "{exercise} ist optional: Du kannst später darauf zurückkommen oder sie ungelöst lassen.",
+    solved_in_chapter: "{n_solved}/{total} in diesem Kapitel gelöst",
+    solved: "{n_solved}/{total} Übungen gelöst ({percentage} %)",
+    optional_solved: ", {n_solved}/{total} optional",
+    passed_verification: "{n_passed}/{total} Übungen haben die Überprüfung bestanden.",
+    no_more_tasks: "Es wird keine weiteren Aufgaben geben.",
+    collection_completed: "Es wird keine weiteren Aufgaben geben: Du hast {title} abgeschlossen.",
+    get_started: "Öffne {path} in deinem Editor und leg los!",
+    run_again: "Führe `wr` erneut aus, um die Übung zu kompilieren und ihre Tests auszuführen.",
+    skipped: "{exercise} wurde übersprungen.",
+    move_on: "Führe `wr` aus, um zur nächsten Übung weiterzugehen.",
+    not_verified_skipped: "Übersprungen",
+    not_verified_not_rechecked: "Nicht erneut geprüft",
+    not_verified_unchanged: "Unverändert, seit sie gelöst wurde",
+    failed_at_stage: "Phase `{stage}` fehlgeschlagen",
+    closed: "{exercise} wurde geschlossen.",
+    feedback: "Feedback zum Workshop ist willkommen, unter {repository}.",
+    score: "Punktzahl: {score} % ({points}/{max_points} Punkte, {n_solved}/{total} Übungen gelöst)",
+    chapter_score: "{n_solved}/{total} gelöst, {points}/{max_points} Punkte",
+    watching: "Warte auf Änderungen an {exercise}...",
+    no_hints: "Es gibt keine Hinweise für {exercise}.",
+    hint: "Hinweis {n}/{total}: {hint}",
+    no_more_hints: "Es gibt keine weiteren Hinweise für diese Übung.",
+    status: StatusLabels {
+        workshop: "Workshop:",
+        authors: "Autoren:",
+        track: "Lernpfad:",
+        current: "Aktuelle Übung:",
+        progress: "Fortschritt:",
+        last_failure: "Letzter Fehlschlag:",
+        elapsed: "Verstrichene Zeit:",
+        no_current: "Keine, führe `wr` aus, um die erste Übung zu öffnen",
+        none: "Keine",
+        unknown: "Unbekannt",
+    },
+};
+
+static ES: Catalog = Catalog {
+    failure: "Medita sobre tu enfoque y vuelve. Las montañas son solo montañas.",
+    not_finished:
+        "La eternidad se extiende ante nosotros, y detrás. Tu camino aún no ha terminado. 🍂",
+    next_prompt: "¿Quieres abrir el siguiente ejercicio, {exercise}?",
+    opened: "Ante ti se encuentra {exercise}",
+    completed: "¿Cuál es el sonido de una sola mano aplaudiendo (por ti)? 🌟",
+    answers: "[s/n]",
+    yes: &["s", "si", "sí"],
+    no: &["n", "no"],
+    invalid_answer: "Por favor, responde sí o no.",
+    running_tests: "Ejecutando las pruebas...",
+    stage_failed: "La etapa `{stage}` ha fallado. No se pudo ejecutar:",
+    output: "Salida:",
+    lines_omitted: "[... {n} línea(s) más ...]",
+    see_everything: "Ejecuta `{command}` para verlo todo.",
+    first_error: "El primer error está en {location}.",
+    optional_failure:
+        "{exercise} es opcional: puedes volver a él más tarde o dejarlo sin resolver.",
+    solved_in_chapter: "{n_solved}/{total} resueltos en este capítulo",
+    solved: "{n_solved}/{total} ejercicios resueltos ({percentage} %)",
+    optional_solved: ", {n_solved}/{total} opcionales",
+    passed_verification: "{n_passed}/{total} ejercicios han superado la verificación.",
+    no_more_tasks: "No habrá más tareas.",
+    collection_completed: "No habrá más tareas: has completado {title}.",
+    get_started: "¡Abre {path} en tu editor y empieza!",
+    run_again: "Ejecuta `wr` de nuevo para compilar el ejercicio y ejecutar sus pruebas.",
+    skipped: "{exercise} se ha saltado.",
+    move_on: "Ejecuta `wr` para pasar al siguiente ejercicio.",
+    not_verified_skipped: "Saltado",
+    not_verified_not_rechecked: "No se ha vuelto a comprobar",
+    not_verified_unchanged: "Sin cambios desde que se resolvió",
+    failed_at_stage: "la etapa `{stage}` ha fallado",
+    closed: "{exercise} se ha cerrado.",
+    feedback: "Cualquier comentario sobre el taller es bienvenido, en {repository}.",
+    score: "Puntuación: {score} % ({points}/{max_points} puntos, {n_solved}/{total} ejercicios resueltos)",
+    chapter_score: "{n_solved}/{total} resueltos, {points}/{max_points} puntos",
+    watching: "Esperando cambios en {exercise}...",
+    no_hints: "No hay pistas para {exercise}.",
+    hint: "Pista {n}/{total}: {hint}",
+    no_more_hints: "No hay más pistas para este ejercicio.",
+    status: StatusLabels {
+        workshop: "Taller:",
+        authors: "Autores:",
+        track: "Itinerario:",
+        current: "Ejercicio actual:",
+        progress: "Progreso:",
+        last_failure: "Último fallo:",
+        elapsed: "Tiempo transcurrido:",
+        no_current: "Ninguno, ejecuta `wr` para abrir el primer ejercicio",
+        none: "Ninguno",
+        unknown: "Desconocido",
+    },
+};
@@ -0,0 +1,103 @@
+//! Report the outcome of verifications as JUnit XML, a format that CI systems
+//! (and GitHub Classroom) understand natively.
+//!
+//! Each chapter becomes a `<testsuite>` and each verified exercise a `<testcase>`.
+//! The output of the failing command, if any, is the body of its `<failure>`.
+use crate::{strip_ansi_codes, ExerciseDefinition, LastFailure};
+use anyhow::Context;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// The outcome of verifying an exercise.
+#[derive(Clone)]
+pub struct TestCase {
+    pub definition: ExerciseDefinition,
+    /// How long the verification took.
+    pub duration: Duration,
+    /// Why the verification failed, or `None` if it succeeded.
+    pub failure: Option<LastFailure>,
+}
+
+/// Render `cases` as a JUnit XML document.
+///
+/// Consecutive exercises from the same chapter are grouped in the same test suite.
+pub fn render(cases: &[TestCase]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"wr\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+        cases.len(),
+        n_failures(cases),
+        total_time(cases)
+    );
+    for suite in cases.chunk_by(|a, b| a.definition.chapter() == b.definition.chapter()) {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+            escape(&suite[0].definition.chapter()),
+            suite.len(),
+            n_failures(suite),
+            total_time(suite)
+        );
+        for case in suite {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.definition.exercise()),
+                escape(&case.definition.chapter()),
+                case.duration.as_secs_f64()
+            );
+            match &case.failure {
+                None => xml.push_str("/>\n"),
+                Some(failure) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>",
+                        escape(&format!("`{}` failed", failure.command)),
+                        escape(&failure.stage),
+                        escape(&failure.output)
+                    );
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Write the report for `cases` to `path`, replacing it if it already exists.
+pub fn write(path: &Path, cases: &[TestCase]) -> Result<(), anyhow::Error> {
+    fs_err::write(path, render(cases))
+        .with_context(|| format!("Failed to write the JUnit report to {path:?}"))
+}
+
+fn n_failures(cases: &[TestCase]) -> usize {
+    cases.iter().filter(|c| c.failure.is_some()).count()
+}
+
+fn total_time(cases: &[TestCase]) -> f64 {
+    cases.iter().map(|c| c.duration.as_secs_f64()).sum()
+}
+
+/// Escape `s` for use in XML text and attributes.
+///
+/// Terminal escape codes (e.g. colours in `cargo`'s output) are dropped, as are the other
+/// control characters: XML 1.0 doesn't allow them, even escaped.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in strip_ansi_codes(s).chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\t' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,24 +1,385 @@
 use anyhow::{anyhow, bail, Context};
 use fs_err::read_dir;
+use progress::{
+    AttemptRecord, EventKind, EventRecord, NoteRecord, ProgressStore, ProgressStoreKind,
+};
 use regex::Regex;
-use rusqlite::{params, Connection};
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsStr;
 use std::fmt::Formatter;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+
+pub mod assertions;
+pub mod badge;
+pub mod bench;
+pub mod dashboard;
+pub mod git;
+pub mod hidden_tests;
+pub mod hyperlinks;
+pub mod i18n;
+pub mod junit;
+pub mod log;
+mod migrations;
+pub mod process;
+pub mod progress;
+pub mod report;
+pub mod rustlings;
+pub mod sandbox;
+pub mod scaffold;
+pub mod sync;
+pub mod tui;
+pub mod validate;
 
 #[derive(serde::Deserialize, Debug)]
 /// The configuration for the current collection of exercises.
 pub struct ExercisesConfig {
+    /// Other configuration files this one builds upon, relative to it, e.g. a base configuration
+    /// shared by several workshops. See [`ExercisesConfig::load`] for how they are merged.
+    #[serde(default)]
+    include: Vec<PathBuf>,
+    /// The name of the workshop, see [`CollectionMetadata::title`].
+    #[serde(default)]
+    title: Option<String>,
+    /// A short description of the workshop, see [`CollectionMetadata::description`].
+    #[serde(default)]
+    description: Option<String>,
+    /// The authors of the workshop.
+    #[serde(default)]
+    authors: Vec<String>,
+    /// The version of the workshop, see [`CollectionMetadata::version`].
+    #[serde(default)]
+    version: Option<String>,
+    /// Where the workshop is developed, see [`CollectionMetadata::repository`].
+    #[serde(default)]
+    repository: Option<String>,
     /// The path to the directory containing the exercises, relative
     /// to the root of the repository.
-    #[serde(default = "default_exercise_dir")]
-    exercises_dir: PathBuf,
+    /// Courses split across several folders can list them all, or use glob patterns:
+    /// see [`ExercisesConfig::exercises_dirs`].
+    #[serde(default)]
+    exercises_dir: ExercisesDirs,
+    /// The directories `exercises_dir` resolves to, see [`ExercisesConfig::exercises_dirs`].
+    #[serde(skip)]
+    exercises_dirs: Vec<PathBuf>,
     /// The command that should be run to verify that the workshop-runner is working as expected.
     #[serde(default)]
     verification: Vec<Verification>,
+    /// The path to the directory containing the reference solutions, relative
+    /// to the root of the repository.
+    /// It must mirror the structure of the exercises directory.
+    #[serde(default)]
+    solutions_dir: Option<PathBuf>,
+    /// How long, in seconds, each verification command is allowed to run before being killed.
+    /// It can be overridden for a specific command via [`Verification::timeout_secs`].
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    /// Run `cargo clippy --all-targets -- -D warnings` as part of the verification of every exercise.
+    /// It can be overridden for a specific exercise via [`ExerciseConfig::clippy`].
+    #[serde(default)]
+    clippy: bool,
+    /// Run `cargo fmt --check` as part of the verification of every exercise.
+    /// It can be overridden for a specific exercise via [`ExerciseConfig::fmt_check`].
+    #[serde(default)]
+    fmt_check: bool,
+    /// The tool used to run the tests of exercises that don't specify their own verification commands.
+    #[serde(default)]
+    test_runner: TestRunner,
+    /// Reject exercises that compile with warnings.
+    /// It can be overridden for a specific exercise via [`ExerciseConfig::deny_warnings`].
+    #[serde(default)]
+    deny_warnings: bool,
+    /// The maximum amount of memory, in megabytes, the tests and binaries of an exercise can use
+    /// (the compiler excluded).
+    #[serde(default)]
+    memory_limit_mb: Option<u64>,
+    /// The maximum amount of CPU time, in seconds, the tests and binaries of an exercise can use
+    /// (the compiler excluded).
+    #[serde(default)]
+    cpu_time_limit_secs: Option<u64>,
+    /// Don't build exercises before verifying them, e.g. because the verification commands build
+    /// them anyway. It can be overridden for a specific exercise via [`ExerciseConfig::skip_build`].
+    #[serde(default)]
+    skip_build: bool,
+    /// The command used to build exercises, instead of `cargo build --all-targets`.
+    /// It can be overridden for a specific exercise via [`ExerciseConfig::build`].
+    #[serde(default)]
+    build: Option<Verification>,
+    /// Run the build and verification commands in a sandbox, rather than directly on the machine.
+    #[serde(default)]
+    sandbox: Option<sandbox::Sandbox>,
+    /// The container image used for sandboxed verification.
+    /// It defaults to [`sandbox::DEFAULT_IMAGE`].
+    #[serde(default)]
+    sandbox_image: Option<String>,
+    /// Extra arguments passed to `docker run` for sandboxed verification, e.g. `--network=none`.
+    #[serde(default)]
+    sandbox_args: Vec<String>,
+    /// Where progress is tracked: a SQLite database (the default) or a JSON file.
+    #[serde(default)]
+    progress_store: ProgressStoreKind,
+    /// The directory where progress is tracked, relative to the root of the repository.
+    /// It defaults to the exercises directory.
+    #[serde(default)]
+    progress_path: Option<PathBuf>,
+    /// Track progress in the user's data directory (e.g. `~/.local/share/wr/<collection>` on Linux),
+    /// rather than in the repository, where it may get committed or wiped by `git clean`.
+    #[serde(default)]
+    progress_in_data_dir: bool,
+    /// The HTTP endpoint `wr sync` pushes progress to and pulls it from, see [`sync`].
+    #[serde(default)]
+    sync_url: Option<String>,
+    /// Learning paths through the collection, e.g. `beginner` or `async-deep-dive`, as ordered lists
+    /// of chapters and exercises, see [`ExerciseCollection::select_track`].
+    #[serde(default)]
+    tracks: BTreeMap<String, Vec<String>>,
+    /// How chapter and exercise folders are named, see [`NamingPattern`].
+    /// It defaults to [`DEFAULT_NAMING_PATTERN`].
+    #[serde(default)]
+    naming_pattern: Option<String>,
+    /// `naming_pattern`, parsed, see [`ExercisesConfig::naming_pattern`].
+    #[serde(skip)]
+    naming: NamingPattern,
+    /// Ask learners before opening the next exercise. If `false`, it's opened right away.
+    #[serde(default = "default_prompt")]
+    prompt: bool,
+    /// How many lines of the output of a failed command are shown, counting from the end.
+    /// `0` shows everything. The full output is kept either way, for `wr last-failure --full`.
+    #[serde(default = "default_failure_lines")]
+    failure_lines: usize,
+    /// A file, relative to the root of the repository, where every run of `wr` is logged,
+    /// see [`log`]. It can be overridden with `--log`.
+    #[serde(default)]
+    log_file: Option<PathBuf>,
+    /// The language learners are addressed in, e.g. `it`, rather than the one of their
+    /// environment. See [`i18n`].
+    #[serde(default)]
+    locale: Option<String>,
+    /// The messages shown to learners, to replace the default ones, e.g. with translations.
+    #[serde(default)]
+    messages: Messages,
+    /// The values of `wr`'s command-line flags when learners don't pass them explicitly.
+    #[serde(default)]
+    defaults: FlagDefaults,
+    /// What to do the first time learners run `wr`, before they open any exercise.
+    #[serde(default)]
+    on_start: Hook,
+    /// What to do when learners solve the last exercise of the collection.
+    #[serde(default)]
+    on_complete: Hook,
+}
+
+/// What to do at a milestone of the workshop, e.g. when it's completed.
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct Hook {
+    /// A message shown to learners, e.g. to welcome them to the workshop.
+    #[serde(default)]
+    pub message: Option<String>,
+    /// A command to run from the root of the repository, e.g. to generate a certificate
+    /// of completion.
+    #[serde(default)]
+    pub run: Option<Verification>,
+}
+
+/// Defaults for `wr`'s command-line flags, from the `[defaults]` table of `.wr.toml`, e.g. for
+/// workshops that are best experienced with `--keep-going`.
+///
+/// Flags passed on the command line always win, e.g. `--keep-going=false`.
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct FlagDefaults {
+    /// See `wr --keep-going`.
+    #[serde(default)]
+    pub keep_going: Option<bool>,
+    /// How many times `wr --verbose` is passed, e.g. `2` for `-vv`.
+    /// `true` and `false` stand for `1` and `0`.
+    #[serde(default, deserialize_with = "deserialize_verbosity")]
+    pub verbose: Option<u8>,
+    /// See `wr --recheck`.
+    #[serde(default)]
+    pub recheck: Option<bool>,
+    /// See `wr --jobs`.
+    #[serde(default)]
+    pub jobs: Option<u32>,
+}
+
+/// Accept a verbosity level, or a boolean for `1` and `0`, see [`FlagDefaults::verbose`].
+fn deserialize_verbosity<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Verbosity {
+        Flag(bool),
+        Level(u8),
+    }
+
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        Verbosity::Flag(verbose) => Some(u8::from(verbose)),
+        Verbosity::Level(level) => Some(level),
+    })
+}
+
+fn default_prompt() -> bool {
+    true
+}
+
+fn default_failure_lines() -> usize {
+    40
+}
+
+/// Messages shown to learners, from the `[messages]` table of `.wr.toml`.
+///
+/// Each of them falls back to the default one, in the collection's locale, if it's not specified.
+/// `{exercise}`, where supported, is replaced with the name of the exercise.
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct Messages {
+    /// Shown when an exercise fails verification.
+    #[serde(default)]
+    failure: Option<String>,
+    /// Shown when all the opened exercises pass verification, but there are more to go.
+    #[serde(default)]
+    not_finished: Option<String>,
+    /// The question asked before opening the next exercise, with `{exercise}`.
+    #[serde(default)]
+    next_prompt: Option<String>,
+    /// Shown when an exercise is opened, with `{exercise}`.
+    #[serde(default)]
+    opened: Option<String>,
+    /// Shown when every exercise has been solved.
+    #[serde(default)]
+    completed: Option<String>,
+    /// The language of the default messages, see [`i18n`].
+    #[serde(skip)]
+    locale: i18n::Locale,
+}
+
+impl Messages {
+    /// The other messages shown to learners, in the same locale.
+    pub fn catalog(&self) -> &'static i18n::Catalog {
+        self.locale.catalog()
+    }
+
+    pub fn failure(&self) -> &str {
+        self.failure.as_deref().unwrap_or(self.catalog().failure)
+    }
+
+    pub fn not_finished(&self) -> &str {
+        self.not_finished
+            .as_deref()
+            .unwrap_or(self.catalog().not_finished)
+    }
+
+    pub fn next_prompt(&self, exercise: &ExerciseDefinition) -> String {
+        self.next_prompt
+            .as_deref()
+            .unwrap_or(self.catalog().next_prompt)
+            .replace("{exercise}", &exercise.to_string())
+    }
+
+    pub fn opened(&self, exercise: &ExerciseDefinition) -> String {
+        self.opened
+            .as_deref()
+            .unwrap_or(self.catalog().opened)
+            .replace("{exercise}", &exercise.to_string())
+    }
+
+    pub fn completed(&self) -> &str {
+        self.completed
+            .as_deref()
+            .unwrap_or(self.catalog().completed)
+    }
+}
+
+/// Information about a collection of exercises, from the top-level fields of its `.wr.toml`.
+#[derive(serde::Serialize, Debug, Clone, Default)]
+pub struct CollectionMetadata {
+    /// The name of the workshop, e.g. `100 Exercises To Learn Rust`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// A short description of what the workshop covers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Who wrote the workshop.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    /// The version of the workshop (not of `wr`), e.g. `1.2.0`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Where the workshop is developed, e.g. to report problems with the exercises.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+}
+
+/// The value of `exercises_dir` in `.wr.toml`: a single directory or a list of them, each of which
+/// can be a glob pattern (e.g. `parts/*/exercises`).
+#[derive(serde::Deserialize, Debug)]
+#[serde(untagged)]
+enum ExercisesDirs {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+impl Default for ExercisesDirs {
+    fn default() -> Self {
+        Self::One(default_exercise_dir())
+    }
+}
+
+impl ExercisesDirs {
+    /// The directories matching these patterns, relative to `root_path`, in the order they are listed.
+    /// The matches of a glob pattern are sorted by path.
+    fn resolve(&self, root_path: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+        let patterns = match self {
+            Self::One(pattern) => std::slice::from_ref(pattern),
+            Self::Many(patterns) => patterns.as_slice(),
+        };
+        if patterns.is_empty() {
+            bail!("`exercises_dir` must list at least one directory");
+        }
+        let mut dirs: Vec<PathBuf> = vec![];
+        for pattern in patterns {
+            let path = root_path.join(pattern);
+            let is_glob = pattern.to_string_lossy().contains(['*', '?', '[']);
+            let matches = if is_glob {
+                let mut matches = glob::glob(&path.to_string_lossy())
+                    .with_context(|| {
+                        format!("`{}` is not a valid glob pattern", pattern.display())
+                    })?
+                    .filter_map(Result::ok)
+                    .filter(|p| p.is_dir())
+                    .collect::<Vec<_>>();
+                if matches.is_empty() {
+                    bail!("`{}` doesn't match any directory", pattern.display());
+                }
+                matches.sort();
+                matches
+            } else {
+                vec![path]
+            };
+            for dir in matches {
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+        Ok(dirs)
+    }
+}
+
+/// The tool used to run the tests of an exercise.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunner {
+    /// `cargo test`.
+    #[default]
+    Cargo,
+    /// `cargo nextest run`, see <https://nexte.st>.
+    Nextest,
+    /// `cargo nextest run` if `cargo-nextest` is installed, `cargo test` otherwise.
+    Auto,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -28,44 +389,687 @@ pub struct ExerciseConfig {
     /// It overrides the verification command specified in the collection configuration, if any.
     #[serde(default)]
     pub verification: Vec<Verification>,
+    /// Don't build this exercise before verifying it.
+    /// It overrides the collection-level `skip_build` setting, if specified.
+    #[serde(default)]
+    pub skip_build: Option<bool>,
+    /// The command used to build this exercise, instead of `cargo build --all-targets`.
+    /// It overrides the collection-level `build` command, if specified.
+    #[serde(default)]
+    pub build: Option<Verification>,
+    /// Run `cargo clippy` as part of the verification of this exercise.
+    /// It overrides the collection-level `clippy` setting, if specified.
+    #[serde(default)]
+    pub clippy: Option<bool>,
+    /// Run `cargo fmt --check` as part of the verification of this exercise.
+    /// It overrides the collection-level `fmt_check` setting, if specified.
+    #[serde(default)]
+    pub fmt_check: Option<bool>,
+    /// Reject solutions that compile with warnings.
+    /// It overrides the collection-level `deny_warnings` setting, if specified.
+    #[serde(default)]
+    pub deny_warnings: Option<bool>,
+    /// Verify this exercise with `cargo test --doc`, i.e. only run the tests in its doc comments,
+    /// instead of `cargo test`.
+    /// It has no effect if the exercise specifies its own verification commands.
+    #[serde(default)]
+    pub doc_tests: bool,
+    /// Only run the tests whose name contains this string, e.g. `part_one`, so that
+    /// successive exercises can share a crate while unlocking its tests progressively.
+    /// It has no effect if the exercise specifies its own verification commands.
+    #[serde(default)]
+    pub test_filter: Option<String>,
+    /// Run the tests of this exercise under Miri (`cargo +nightly miri test`) as well, to detect
+    /// undefined behaviour.
+    #[serde(default)]
+    pub miri: bool,
+    /// The target to build this exercise for, e.g. `wasm32-unknown-unknown`, instead of the host.
+    /// Unless a `target_runner` is specified, the exercise is only built: its tests (and binary)
+    /// can't be run.
+    #[serde(default)]
+    pub target: Option<String>,
+    /// The command used to run the binaries built for `target`, e.g. `wasm-bindgen-test-runner`.
+    /// It's passed to Cargo as the runner for that target.
+    #[serde(default)]
+    pub target_runner: Option<String>,
+    /// What it takes for this exercise to be solved: passing the verification (the default) or,
+    /// for exercises about compiler errors, failing to compile.
+    #[serde(default)]
+    pub expect: Expect,
+    /// The error code, e.g. `E0502`, that the compiler must emit for a `compile-fail` exercise.
+    /// Any compilation error will do if it's not specified.
+    #[serde(default)]
+    pub expected_error: Option<String>,
+    /// Benchmarks that must run faster than a threshold: if any is specified, `cargo bench`
+    /// becomes part of the verification.
+    #[serde(default)]
+    pub benchmarks: Vec<bench::BenchmarkThreshold>,
+    /// The file containing the output that the exercise binary is expected to print,
+    /// relative to the exercise folder.
+    /// It defaults to `expected_output.txt`, if it exists.
+    #[serde(default)]
+    pub expected_output: Option<PathBuf>,
+    /// The input piped into the verification commands (and the exercise binary, when comparing
+    /// its output with the expected one).
+    #[serde(default)]
+    pub stdin: Option<String>,
+    /// The file whose contents are piped into the verification commands, relative to the exercise folder.
+    /// It's ignored if `stdin` is specified.
+    #[serde(default)]
+    pub stdin_file: Option<PathBuf>,
+    /// Ignore trailing whitespace and leading/trailing empty lines when comparing
+    /// the output of the exercise binary with the expected one.
+    #[serde(default)]
+    pub normalize_output: bool,
+    /// The Rust toolchain to verify this exercise with, e.g. `nightly` or `nightly-2024-05-01`,
+    /// instead of the default one. `wr` invokes `cargo +<toolchain>`.
+    #[serde(default)]
+    pub toolchain: Option<String>,
+    /// The Cargo features to enable when building, linting and testing this exercise.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Enable all the Cargo features of this exercise (`--all-features`).
+    #[serde(default)]
+    pub all_features: bool,
+    /// Don't enable the default Cargo features of this exercise (`--no-default-features`).
+    #[serde(default)]
+    pub no_default_features: bool,
+    /// Hints to help learners who are stuck on this exercise, in the order they should be revealed.
+    #[serde(default)]
+    pub hints: Vec<String>,
+    /// How much this exercise counts towards the final score computed by `wr grade`.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// A stable identifier for the exercise, e.g. `ownership-borrowing`.
+    /// Progress on the exercise is carried over if it's renamed or renumbered, as long as
+    /// its `id` stays the same.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// A bonus exercise: it doesn't have to be solved to move on, and it doesn't count
+    /// towards the completion percentage.
+    #[serde(default)]
+    pub optional: bool,
+    /// The exercises that must be solved before this one, as `<chapter folder>/<exercise folder>`,
+    /// e.g. `02_basics/05_loops`.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// The topics this exercise covers, e.g. `traits` or `async`, to find exercises by topic
+    /// (`wr list --tag traits`) or to check them together (`wr check --tag traits`).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// How hard this exercise is expected to be.
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+impl ExerciseConfig {
+    /// The settings that can change the outcome of verifying this exercise, serialised.
+    /// Hints, tags and the like are left out: editing them doesn't call for verifying it again.
+    pub fn verification_settings(&self) -> serde_json::Value {
+        serde_json::json!({
+            "verification": self.verification,
+            "skip_build": self.skip_build,
+            "build": self.build,
+            "clippy": self.clippy,
+            "fmt_check": self.fmt_check,
+            "deny_warnings": self.deny_warnings,
+            "doc_tests": self.doc_tests,
+            "test_filter": self.test_filter,
+            "miri": self.miri,
+            "target": self.target,
+            "target_runner": self.target_runner,
+            "expect": self.expect,
+            "expected_error": self.expected_error,
+            "benchmarks": self.benchmarks,
+            "expected_output": self.expected_output,
+            "stdin": self.stdin,
+            "stdin_file": self.stdin_file,
+            "normalize_output": self.normalize_output,
+            "toolchain": self.toolchain,
+            "features": self.features,
+            "all_features": self.all_features,
+            "no_default_features": self.no_default_features,
+        })
+    }
+
+    /// The flags that select the Cargo features to enable, to be passed to `cargo build`,
+    /// `cargo test`, etc.
+    pub fn feature_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if !self.features.is_empty() {
+            args.push("--features".into());
+            args.push(self.features.join(","));
+        }
+        if self.all_features {
+            args.push("--all-features".into());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".into());
+        }
+        args
+    }
+
+    /// The flags that select the target to build for, if it isn't the host.
+    pub fn target_args(&self) -> Vec<String> {
+        match &self.target {
+            Some(target) => vec!["--target".into(), target.clone()],
+            None => vec![],
+        }
+    }
+
+    /// Whether the tests and binaries of this exercise can be run, rather than just built.
+    pub fn runnable(&self) -> bool {
+        self.target.is_none() || self.target_runner.is_some()
+    }
+
+    /// The environment variable that tells Cargo how to run the binaries built for `target`,
+    /// alongside its value, if a runner was specified.
+    pub fn target_runner_env(&self) -> Option<(String, &str)> {
+        let target = self.target.as_ref()?;
+        let runner = self.target_runner.as_ref()?;
+        Some((runner_env_var(target), runner))
+    }
+}
+
+/// The environment variable that tells Cargo how to run the binaries built for `target`,
+/// e.g. `CARGO_TARGET_X86_64_UNKNOWN_LINUX_GNU_RUNNER`.
+pub fn runner_env_var(target: &str) -> String {
+    let target = target.to_uppercase().replace(['-', '.'], "_");
+    format!("CARGO_TARGET_{target}_RUNNER")
+}
+
+/// What it takes for an exercise to be solved.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Expect {
+    /// The exercise must build and pass all verification stages.
+    #[default]
+    Pass,
+    /// The exercise must fail to compile, e.g. because it violates the borrowing rules.
+    CompileFail,
+}
+
+/// How hard an exercise is expected to be, see [`ExerciseConfig::difficulty`].
+#[derive(
+    serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{s}")
+    }
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct Verification {
+    /// A name for this verification stage, e.g. `lint` or `tests`, used when reporting progress
+    /// and failures. It defaults to the command itself.
+    #[serde(default)]
+    pub name: Option<String>,
     /// The command that should be run to verify that the workshop-runner is working as expected.
     pub command: String,
     /// The arguments that should be passed to the verification command.
+    ///
+    /// `${VAR}` is replaced with the value of the `VAR` environment variable,
+    /// see [`Verification::expanded_args`].
     #[serde(default)]
     pub args: Vec<String>,
+    /// Environment variables that should be set when running the verification command,
+    /// e.g. `RUST_LOG`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// How long, in seconds, the command is allowed to run before being killed.
+    /// It takes precedence over the collection-level `timeout_secs`.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Run the command through the shell (`sh -c` on Unix, `cmd /C` on Windows), so that it can
+    /// use pipes, `&&` chains and the like. The arguments, if any, are quoted and appended to the
+    /// command line.
+    #[serde(default)]
+    pub shell: bool,
+    /// A regular expression that the standard output of the command must match for the stage to pass,
+    /// on top of the command exiting successfully.
+    #[serde(default)]
+    pub expect_stdout_regex: Option<String>,
+    /// A string that the standard error of the command must contain for the stage to pass,
+    /// on top of the command exiting successfully.
+    #[serde(default)]
+    pub expect_stderr_contains: Option<String>,
+    /// How many times the command is run again if it fails, before reporting a failure.
+    /// Useful for commands that may fail for reasons outside of the learner's control,
+    /// e.g. a flaky network.
+    #[serde(default)]
+    pub retries: u32,
+    /// Only run the command on this platform, e.g. to provide both a shell script for Unix
+    /// and a batch file for Windows.
+    #[serde(default)]
+    pub platform: Option<Platform>,
+}
+
+/// An operating system (family) a verification command is meant for, see [`Verification::platform`].
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Windows,
+    /// Linux, macOS and the other Unix-like systems.
+    Unix,
+    Macos,
+}
+
+impl Platform {
+    /// `true` if `wr` is running on this platform.
+    pub fn is_current(self) -> bool {
+        match self {
+            Platform::Windows => cfg!(windows),
+            Platform::Unix => cfg!(unix),
+            Platform::Macos => cfg!(target_os = "macos"),
+        }
+    }
+}
+
+impl Verification {
+    /// `true` if the command is meant to run on the current platform.
+    pub fn runs_here(&self) -> bool {
+        self.platform.is_none_or(Platform::is_current)
+    }
+
+    /// The name of this verification stage: either the one specified in the configuration,
+    /// or the command itself.
+    pub fn stage_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            std::iter::once(self.command.as_str())
+                .chain(self.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+    }
+
+    /// The arguments for the verification command, with every `${VAR}` replaced with the value
+    /// of the `VAR` environment variable.
+    ///
+    /// Variables set in [`Verification::env`] take precedence over the ones inherited from
+    /// the environment `wr` runs in. Undefined variables are replaced with an empty string.
+    pub fn expanded_args(&self) -> Vec<String> {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        let re = REGEX.get_or_init(|| Regex::new(r"\$\{(\w+)\}").unwrap());
+        self.args
+            .iter()
+            .map(|arg| {
+                re.replace_all(arg, |captures: &regex::Captures| {
+                    let name = &captures[1];
+                    self.env
+                        .get(name)
+                        .cloned()
+                        .or_else(|| std::env::var(name).ok())
+                        .unwrap_or_default()
+                })
+                .into_owned()
+            })
+            .collect()
+    }
+
+    /// The command to run for this verification stage, with its arguments and environment.
+    pub fn command(&self) -> Command {
+        let mut cmd = if self.shell {
+            // The command is a script, as is: only the arguments are quoted, so that e.g.
+            // paths with spaces reach it as a single argument.
+            let script = std::iter::once(self.command.clone())
+                .chain(self.expanded_args().iter().map(|arg| shell_quote(arg)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut cmd = if cfg!(windows) {
+                let mut cmd = Command::new("cmd");
+                cmd.arg("/C");
+                cmd
+            } else {
+                let mut cmd = Command::new("sh");
+                cmd.arg("-c");
+                cmd
+            };
+            cmd.arg(script);
+            cmd
+        } else {
+            let mut cmd = Command::new(&self.command);
+            cmd.args(self.expanded_args());
+            cmd
+        };
+        cmd.envs(&self.env);
+        cmd
+    }
+}
+
+/// `arg`, quoted for the shell that runs [`Verification::shell`] commands if needed.
+pub fn shell_quote(arg: &str) -> String {
+    if !cfg!(windows) {
+        return shell_words::quote(arg).into_owned();
+    }
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || "\"&|<>^()%!".contains(c)) {
+        return arg.to_owned();
+    }
+    format!("\"{}\"", arg.replace('"', "\"\""))
+}
+
+/// A hash of every file in `dir` (build artifacts and `Cargo.lock`, which the build itself may
+/// create, excluded), to detect changes to the sources it contains.
+///
+/// It's stable across versions of `wr` and platforms: it can be persisted.
+pub fn hash_sources(dir: &Path) -> Result<String, anyhow::Error> {
+    use sha2::{Digest, Sha256};
+
+    fn visit(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), anyhow::Error> {
+        for entry in read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if path
+                    .file_name()
+                    .is_some_and(|name| name == "target" || name == ".git")
+                {
+                    continue;
+                }
+                visit(&path, files)?;
+            } else if path.file_name().is_none_or(|name| name != "Cargo.lock") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = vec![];
+    visit(dir, &mut files)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative = file.strip_prefix(dir).unwrap_or(&file);
+        // The same on every platform, and unambiguous.
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        hasher.update((relative.len() as u64).to_le_bytes());
+        hasher.update(relative);
+        let contents = fs_err::read(&file)?;
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The folders of the path dependencies (`path = "..."`) of the crate whose manifest is at
+/// `manifest_path`, and of their own path dependencies, sorted.
+pub fn path_dependencies(manifest_path: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    fn visit(manifest_path: &Path, found: &mut BTreeSet<PathBuf>) -> Result<(), anyhow::Error> {
+        let manifest: toml::Value = toml::from_str(&fs_err::read_to_string(manifest_path)?)
+            .with_context(|| format!("Failed to parse {manifest_path:?}"))?;
+        let dir = manifest_path.parent().unwrap_or(Path::new(""));
+        let kinds = ["dependencies", "dev-dependencies", "build-dependencies"];
+        // Dependencies can be declared for every target, or for specific ones.
+        let tables = std::iter::once(&manifest)
+            .chain(
+                manifest
+                    .get("target")
+                    .and_then(toml::Value::as_table)
+                    .into_iter()
+                    .flat_map(|targets| targets.values()),
+            )
+            .flat_map(|table| kinds.iter().filter_map(|kind| table.get(kind)))
+            .filter_map(toml::Value::as_table);
+        for dependencies in tables {
+            for dependency in dependencies.values() {
+                let Some(path) = dependency.get("path").and_then(toml::Value::as_str) else {
+                    continue;
+                };
+                let Ok(path) = fs_err::canonicalize(dir.join(path)) else {
+                    // Cargo will complain about it, and it'll fail verification.
+                    continue;
+                };
+                if found.insert(path.clone()) {
+                    visit(&path.join("Cargo.toml"), found)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    let mut found = BTreeSet::new();
+    visit(manifest_path, &mut found)?;
+    Ok(found.into_iter().collect())
 }
 
 fn default_exercise_dir() -> PathBuf {
     PathBuf::from("exercises")
 }
 
+/// A directory, in the user's data directory, dedicated to the collection rooted at `root_path`.
+///
+/// It's named after the repository, with a hash of its location to tell apart different
+/// checkouts of the same workshop. The hash is stable across versions of `wr`, so that the
+/// progress stored there isn't lost on upgrades.
+fn collection_data_dir(root_path: &Path) -> Result<PathBuf, anyhow::Error> {
+    use sha2::{Digest, Sha256};
+
+    let data_dir = dirs::data_dir().context("Failed to determine your data directory")?;
+    let current_dir =
+        std::env::current_dir().context("Failed to determine the current directory")?;
+    let root_path = fs_err::canonicalize(current_dir.join(root_path))?;
+    let name = root_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let hash = Sha256::digest(root_path.as_os_str().as_encoded_bytes());
+    let hash: String = hash[..8].iter().map(|byte| format!("{byte:02x}")).collect();
+    Ok(data_dir.join("wr").join(format!("{name}-{hash}")))
+}
+
+/// The settings of `.wr.toml` that can be overridden with an environment variable, named after
+/// the setting: `WR_EXERCISES_DIR` overrides `exercises_dir`, and so on.
+const ENV_OVERRIDES: &[(&str, EnvValue)] = &[
+    ("exercises_dir", EnvValue::TextOrList),
+    ("solutions_dir", EnvValue::Text),
+    ("timeout_secs", EnvValue::Toml),
+    ("clippy", EnvValue::Toml),
+    ("fmt_check", EnvValue::Toml),
+    ("test_runner", EnvValue::Text),
+    ("deny_warnings", EnvValue::Toml),
+    ("memory_limit_mb", EnvValue::Toml),
+    ("cpu_time_limit_secs", EnvValue::Toml),
+    ("skip_build", EnvValue::Toml),
+    ("sandbox", EnvValue::Text),
+    ("sandbox_image", EnvValue::Text),
+    ("progress_store", EnvValue::Text),
+    ("progress_path", EnvValue::Text),
+    ("progress_in_data_dir", EnvValue::Toml),
+    ("sync_url", EnvValue::Text),
+    ("prompt", EnvValue::Toml),
+    ("failure_lines", EnvValue::Toml),
+    ("log_file", EnvValue::Text),
+    ("locale", EnvValue::Text),
+];
+
+/// How the value of an environment variable in [`ENV_OVERRIDES`] is read, according to the type
+/// of the setting it overrides.
+#[derive(Clone, Copy)]
+enum EnvValue {
+    /// As is, e.g. a path.
+    Text,
+    /// As a TOML value, e.g. `true` or `30`.
+    Toml,
+    /// As a TOML array if it looks like one, e.g. `["a", "b"]`, as is otherwise.
+    TextOrList,
+}
+
+/// Read the configuration file at `path`, merged on top of the files it includes.
+///
+/// Included files are merged in the order they are listed, each overriding the previous ones,
+/// and `path` overrides them all. Tables are merged key by key, any other value is replaced.
+fn read_layered_config(path: &Path) -> Result<toml::Table, anyhow::Error> {
+    fn read(path: &Path, including: &mut Vec<PathBuf>) -> Result<toml::Table, anyhow::Error> {
+        let canonical = fs_err::canonicalize(path)?;
+        if including.contains(&canonical) {
+            bail!("`{}` includes itself", path.display());
+        }
+        let source = fs_err::read_to_string(path)?;
+        let mut config: toml::Table = toml::from_str(&source)
+            .with_context(|| format!("Failed to parse `{}`", path.display()))?;
+        let includes: Vec<PathBuf> = match config.remove("include") {
+            Some(includes) => includes.try_into().with_context(|| {
+                format!("`include` in `{}` must be a list of paths", path.display())
+            })?,
+            None => return Ok(config),
+        };
+        including.push(canonical);
+        let parent = path.parent().unwrap_or(Path::new(""));
+        let mut layered = toml::Table::new();
+        for include in includes {
+            let included = read(&parent.join(&include), including).with_context(|| {
+                format!(
+                    "Failed to include `{}` from `{}`",
+                    include.display(),
+                    path.display()
+                )
+            })?;
+            merge_config(&mut layered, included);
+        }
+        including.pop();
+        merge_config(&mut layered, config);
+        Ok(layered)
+    }
+
+    read(path, &mut vec![])
+}
+
+/// Merge `overrides` into `base`: tables are merged key by key, any other value is replaced.
+fn merge_config(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge_config(base, overrides)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Override settings with the environment variables listed in [`ENV_OVERRIDES`].
+///
+/// Values are parsed as TOML (e.g. `true`, `30` or `["a", "b"]`), falling back to plain strings,
+/// so that `WR_EXERCISES_DIR=exercises` doesn't need quotes.
+fn apply_env_overrides(config: &mut toml::Table) {
+    for (key, kind) in ENV_OVERRIDES {
+        let Ok(value) = std::env::var(format!("WR_{}", key.to_uppercase())) else {
+            continue;
+        };
+        let as_toml = match kind {
+            EnvValue::Text => false,
+            EnvValue::Toml => true,
+            EnvValue::TextOrList => value.trim_start().starts_with('['),
+        };
+        // Values that aren't valid TOML are kept as they are: deserializing the configuration
+        // then reports them, along with the setting they were meant for.
+        let value = as_toml
+            .then(|| toml::from_str::<toml::Table>(&format!("value = {value}")).ok())
+            .flatten()
+            .and_then(|mut t| t.remove("value"))
+            .unwrap_or(toml::Value::String(value));
+        config.insert(key.to_string(), value);
+    }
+}
+
 impl ExercisesConfig {
+    /// Load the configuration of the collection in the current `git` repository.
+    ///
+    /// Settings are taken from, in order of precedence:
+    ///
+    /// 1. environment variables named after the setting they override, e.g. `WR_EXERCISES_DIR`
+    ///    for `exercises_dir`;
+    /// 2. `.wr.toml`, at the root of the repository;
+    /// 3. the files listed in its `include` setting, the last one first.
     pub fn load() -> Result<Self, anyhow::Error> {
         let root_path = get_git_repository_root_dir()
             .context("Failed to determine the root path of the current `git` repository")?;
         let exercises_config_path = root_path.join(".wr.toml");
-        let exercises_config = fs_err::read_to_string(&exercises_config_path).context(
+        let mut exercises_config = read_layered_config(&exercises_config_path).context(
             "Failed to read the configuration for the current collection of workshop-runner",
         )?;
-        let mut exercises_config: ExercisesConfig = toml::from_str(&exercises_config).with_context(|| {
+        apply_env_overrides(&mut exercises_config);
+        let mut exercises_config: ExercisesConfig = toml::Value::Table(exercises_config).try_into().with_context(|| {
             format!(
                 "Failed to parse the configuration at `{}` for the current collection of workshop-runner",
                 exercises_config_path.to_string_lossy()
             )
         })?;
+        if let Some(pattern) = &exercises_config.naming_pattern {
+            exercises_config.naming =
+                NamingPattern::new(pattern).context("Invalid `naming_pattern` in `.wr.toml`")?;
+        }
         // The path to the exercises directory is relative to the root of the repository.
-        exercises_config.exercises_dir = root_path.join(&exercises_config.exercises_dir);
+        exercises_config.exercises_dirs = exercises_config
+            .exercises_dir
+            .resolve(&root_path)
+            .context("Invalid `exercises_dir` in `.wr.toml`")?;
+        exercises_config.solutions_dir = exercises_config
+            .solutions_dir
+            .map(|solutions_dir| root_path.join(solutions_dir));
+        exercises_config.log_file = exercises_config
+            .log_file
+            .map(|log_file| root_path.join(log_file));
+        exercises_config.messages.locale = match &exercises_config.locale {
+            Some(locale) => i18n::Locale::parse(locale)
+                .with_context(|| format!("Unsupported `locale` in `.wr.toml`: `{locale}`"))?,
+            None => i18n::Locale::from_env().unwrap_or_default(),
+        };
+        exercises_config.progress_path = match (
+            exercises_config.progress_path,
+            exercises_config.progress_in_data_dir,
+        ) {
+            (Some(_), true) => {
+                bail!("`progress_path` and `progress_in_data_dir` can't be used together in `.wr.toml`")
+            }
+            (Some(progress_path), false) => Some(root_path.join(progress_path)),
+            (None, true) => Some(collection_data_dir(&root_path)?),
+            (None, false) => None,
+        };
         Ok(exercises_config)
     }
 
     /// The path to the directory containing the exercises
     /// for the current collection of workshop-runner.
+    ///
+    /// If the exercises are spread across several directories, it's the first one: it's where
+    /// progress is tracked, by default, and where new chapters are created.
     pub fn exercises_dir(&self) -> &Path {
-        &self.exercises_dir
+        &self.exercises_dirs[0]
+    }
+
+    /// All the directories containing exercises, in order.
+    ///
+    /// Chapters are numbered across directories, e.g. `part1/exercises` may contain chapters
+    /// `01` to `05` and `part2/exercises` chapters `06` to `10`.
+    pub fn exercises_dirs(&self) -> &[PathBuf] {
+        &self.exercises_dirs
+    }
+
+    /// How chapter and exercise folders are named: it defaults to [`DEFAULT_NAMING_PATTERN`].
+    pub fn naming_pattern(&self) -> &NamingPattern {
+        &self.naming
+    }
+
+    /// The path to the directory containing the reference solutions, if the collection provides them.
+    pub fn solutions_dir(&self) -> Option<&Path> {
+        self.solutions_dir.as_deref()
     }
 
     /// The command(s) that should be run to verify that exercises are correct.
@@ -73,6 +1077,222 @@ impl ExercisesConfig {
     pub fn verification(&self) -> &[Verification] {
         &self.verification
     }
+
+    /// The collection-level settings that can change the outcome of verifying an exercise, serialised.
+    /// Messages, paths and the like are left out: they don't call for verifying exercises again.
+    pub fn verification_settings(&self) -> serde_json::Value {
+        serde_json::json!({
+            "verification": self.verification,
+            "timeout_secs": self.timeout_secs,
+            "clippy": self.clippy,
+            "fmt_check": self.fmt_check,
+            "test_runner": self.test_runner,
+            "deny_warnings": self.deny_warnings,
+            "memory_limit_mb": self.memory_limit_mb,
+            "cpu_time_limit_secs": self.cpu_time_limit_secs,
+            "skip_build": self.skip_build,
+            "build": self.build,
+            "sandbox": self.sandbox,
+            "sandbox_image": self.sandbox_image,
+            "sandbox_args": self.sandbox_args,
+        })
+    }
+
+    /// The verification commands for an exercise on the current platform, given its configuration.
+    ///
+    /// Commands meant for other platforms (see [`Verification::platform`]) are ignored, as if
+    /// they weren't there. The exercise's own commands take precedence over the collection's,
+    /// unless none of them is meant for this platform.
+    pub fn verification_for<'a>(
+        &'a self,
+        exercise_config: Option<&'a ExerciseConfig>,
+    ) -> Vec<&'a Verification> {
+        let for_this_platform = |commands: &'a [Verification]| -> Vec<&'a Verification> {
+            commands.iter().filter(|v| v.runs_here()).collect()
+        };
+        exercise_config
+            .filter(|c| c.verification.is_empty() || c.verification.iter().any(|v| v.runs_here()))
+            .map(|c| for_this_platform(&c.verification))
+            .unwrap_or_else(|| for_this_platform(&self.verification))
+    }
+
+    /// Whether the build step should be skipped when verifying an exercise, given its configuration.
+    pub fn skip_build(&self, exercise_config: Option<&ExerciseConfig>) -> bool {
+        exercise_config
+            .and_then(|c| c.skip_build)
+            .unwrap_or(self.skip_build)
+    }
+
+    /// The custom command used to build an exercise, given its configuration.
+    /// `None` if it should be built with `cargo build`.
+    pub fn build<'a>(
+        &'a self,
+        exercise_config: Option<&'a ExerciseConfig>,
+    ) -> Option<&'a Verification> {
+        exercise_config
+            .and_then(|c| c.build.as_ref())
+            .filter(|b| b.runs_here())
+            .or(self.build.as_ref().filter(|b| b.runs_here()))
+    }
+
+    /// Whether `cargo clippy` should be run to verify an exercise, given its configuration.
+    pub fn clippy(&self, exercise_config: Option<&ExerciseConfig>) -> bool {
+        exercise_config
+            .and_then(|c| c.clippy)
+            .unwrap_or(self.clippy)
+    }
+
+    /// Whether `cargo fmt --check` should be run to verify an exercise, given its configuration.
+    pub fn fmt_check(&self, exercise_config: Option<&ExerciseConfig>) -> bool {
+        exercise_config
+            .and_then(|c| c.fmt_check)
+            .unwrap_or(self.fmt_check)
+    }
+
+    /// Whether warnings should be treated as errors when building an exercise, given its configuration.
+    pub fn deny_warnings(&self, exercise_config: Option<&ExerciseConfig>) -> bool {
+        exercise_config
+            .and_then(|c| c.deny_warnings)
+            .unwrap_or(self.deny_warnings)
+    }
+
+    /// The resources that the processes verifying an exercise are allowed to use.
+    pub fn resource_limits(&self) -> process::ResourceLimits {
+        process::ResourceLimits {
+            memory_bytes: self.memory_limit_mb.map(|mb| mb * 1024 * 1024),
+            cpu_time: self.cpu_time_limit_secs.map(std::time::Duration::from_secs),
+        }
+    }
+
+    /// Where the build and verification commands run, if not directly on the machine.
+    pub fn sandbox(&self) -> Option<sandbox::Sandbox> {
+        self.sandbox
+    }
+
+    /// The container image used for sandboxed verification.
+    pub fn sandbox_image(&self) -> &str {
+        self.sandbox_image
+            .as_deref()
+            .unwrap_or(sandbox::DEFAULT_IMAGE)
+    }
+
+    /// Extra arguments passed to `docker run` for sandboxed verification.
+    pub fn sandbox_args(&self) -> &[String] {
+        &self.sandbox_args
+    }
+
+    /// What the workshop is about and who wrote it.
+    pub fn metadata(&self) -> CollectionMetadata {
+        CollectionMetadata {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            authors: self.authors.clone(),
+            version: self.version.clone(),
+            repository: self.repository.clone(),
+        }
+    }
+
+    /// `false` if the next exercise should be opened without asking first.
+    pub fn prompt(&self) -> bool {
+        self.prompt
+    }
+
+    /// How many lines of the output of a failed command are shown, or `0` to show everything.
+    pub fn failure_lines(&self) -> usize {
+        self.failure_lines
+    }
+
+    /// Where every run of `wr` should be logged, if anywhere.
+    pub fn log_file(&self) -> Option<&Path> {
+        self.log_file.as_deref()
+    }
+
+    /// What to do the first time learners run `wr`, see [`Hook`].
+    pub fn on_start(&self) -> &Hook {
+        &self.on_start
+    }
+
+    /// What to do when learners complete the collection, see [`Hook`].
+    pub fn on_complete(&self) -> &Hook {
+        &self.on_complete
+    }
+
+    /// The defaults for the command-line flags learners don't pass, see [`FlagDefaults`].
+    pub fn defaults(&self) -> &FlagDefaults {
+        &self.defaults
+    }
+
+    /// The messages shown to learners, see [`Messages`].
+    pub fn messages(&self) -> &Messages {
+        &self.messages
+    }
+
+    /// The HTTP endpoint `wr sync` pushes progress to and pulls it from, if any.
+    pub fn sync_url(&self) -> Option<&str> {
+        self.sync_url.as_deref()
+    }
+
+    /// The chapters and exercises that make up the track named `name`, in order.
+    pub fn track(&self, name: &str) -> Result<&[String], anyhow::Error> {
+        if let Some(track) = self.tracks.get(name) {
+            return Ok(track);
+        }
+        if self.tracks.is_empty() {
+            bail!("There is no track named `{name}`: this collection doesn't define any track");
+        }
+        let tracks: Vec<_> = self.tracks.keys().map(|t| format!("`{t}`")).collect();
+        bail!(
+            "There is no track named `{name}`. The tracks of this collection are: {}",
+            tracks.join(", ")
+        )
+    }
+
+    /// The directory where progress on the collection is tracked.
+    pub fn progress_dir(&self) -> &Path {
+        self.progress_path
+            .as_deref()
+            .unwrap_or_else(|| self.exercises_dir())
+    }
+
+    /// Open the store that tracks progress on the collection, for the given profile.
+    pub fn open_progress_store(
+        &self,
+        profile: Option<&str>,
+    ) -> Result<Box<dyn ProgressStore>, anyhow::Error> {
+        let progress_dir = self.progress_dir();
+        fs_err::create_dir_all(progress_dir)
+            .context("Failed to create the directory where your progress is tracked")?;
+        self.progress_store.open(progress_dir, profile)
+    }
+
+    /// The tool used to run the tests of exercises that don't specify their own verification commands.
+    ///
+    /// It's never [`TestRunner::Auto`]: we check whether `cargo-nextest` is installed instead.
+    pub fn test_runner(&self) -> TestRunner {
+        static NEXTEST_INSTALLED: OnceLock<bool> = OnceLock::new();
+        match self.test_runner {
+            TestRunner::Auto => {
+                let installed = *NEXTEST_INSTALLED.get_or_init(|| {
+                    Command::new("cargo")
+                        .args(["nextest", "--version"])
+                        .output()
+                        .is_ok_and(|output| output.status.success())
+                });
+                if installed {
+                    TestRunner::Nextest
+                } else {
+                    TestRunner::Cargo
+                }
+            }
+            runner => runner,
+        }
+    }
+
+    /// How long each verification command is allowed to run, unless specified otherwise
+    /// for that command. `None` if there is no time limit.
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.timeout_secs.map(std::time::Duration::from_secs)
+    }
 }
 
 /// Retrieve the path to the root directory of the current `git` repository.
@@ -94,143 +1314,733 @@ pub fn get_git_repository_root_dir() -> Result<PathBuf, anyhow::Error> {
 
 pub struct ExerciseCollection {
     exercises_dir: PathBuf,
-    connection: Connection,
-    exercises: BTreeSet<ExerciseDefinition>,
+    store: Box<dyn ProgressStore>,
+    /// The exercises on disk, with the exercises directory each of them was found in,
+    /// see [`ExercisesConfig::exercises_dirs`].
+    exercises: BTreeMap<ExerciseDefinition, PathBuf>,
+    /// How chapter and exercise folders are named, see [`ExercisesConfig::naming_pattern`].
+    naming: NamingPattern,
+    /// Exercises that have been opened in the past, but can no longer be found on disk.
+    /// E.g. they may have been removed or renamed by an update to the workshop.
+    stale: BTreeSet<ExerciseDefinition>,
+    renamed: BTreeMap<ExerciseDefinition, ExerciseDefinition>,
+    /// Exercises marked as `optional` in their configuration.
+    optional: BTreeSet<ExerciseDefinition>,
+    /// The exercises that must be solved before each exercise, see [`ExerciseConfig::requires`].
+    prerequisites: BTreeMap<ExerciseDefinition, Vec<ExerciseDefinition>>,
+    /// The tags of each exercise, see [`ExerciseConfig::tags`].
+    tags: BTreeMap<ExerciseDefinition, Vec<String>>,
+    /// The difficulty of each exercise that specifies one, see [`ExerciseConfig::difficulty`].
+    difficulty: BTreeMap<ExerciseDefinition, Difficulty>,
+    /// The track the learner is following, if any.
+    track: Option<Track>,
+}
+
+/// A named, ordered subset of the exercises in a collection, see [`ExercisesConfig::track`].
+struct Track {
+    name: String,
+    exercises: Vec<ExerciseDefinition>,
 }
 
 impl ExerciseCollection {
+    /// Load the collection in `exercises_dir`, tracking progress in a SQLite database.
     pub fn new(exercises_dir: PathBuf) -> Result<Self, anyhow::Error> {
-        let chapters = read_dir(&exercises_dir)
-            .context("Failed to read the exercises directory")?
-            .filter_map(|entry| {
-                let Ok(entry) = entry else {
+        let store = ProgressStoreKind::Sqlite.open(&exercises_dir, None)?;
+        Self::with_store(vec![exercises_dir], NamingPattern::default(), store)
+    }
+
+    /// Load the collection spread across `exercises_dirs` (usually just one, see
+    /// [`ExercisesConfig::exercises_dirs`]), whose folders are named according to `naming`,
+    /// tracking progress in `store`.
+    pub fn with_store(
+        exercises_dirs: Vec<PathBuf>,
+        naming: NamingPattern,
+        store: Box<dyn ProgressStore>,
+    ) -> Result<Self, anyhow::Error> {
+        let exercises = discover_exercises(&exercises_dirs, &naming)?;
+        let exercises_dir = exercises_dirs
+            .into_iter()
+            .next()
+            .context("There must be at least one exercises directory")?;
+
+        // Reconcile the progress database with the exercises on disk.
+        let mut stale: BTreeSet<_> = opened_exercises(store.as_ref(), &naming)?
+            .into_iter()
+            .map(|e| e.definition)
+            .filter(|e| !exercises.get(e).is_some_and(|root| e.exists(root)))
+            .collect();
+        let renamed = if stale.is_empty() {
+            BTreeMap::new()
+        } else {
+            carry_over_renamed(store.as_ref(), &exercises, &stale)?
+        };
+        stale.retain(|e| !renamed.contains_key(e));
+
+        // Invalid configuration files are reported when the exercise is verified.
+        let configs: Vec<_> = exercises
+            .iter()
+            .filter_map(|(e, root)| Some((e, e.config(root).ok().flatten()?)))
+            .collect();
+        let optional = configs
+            .iter()
+            .filter(|(_, c)| c.optional)
+            .map(|(e, _)| (*e).clone())
+            .collect();
+        // Prerequisites that don't exist are reported by `wr config validate`.
+        let prerequisites = configs
+            .iter()
+            .filter(|(_, c)| !c.requires.is_empty())
+            .map(|(e, c)| {
+                let requires = c
+                    .requires
+                    .iter()
+                    .filter_map(|r| exercises.keys().find(|e| e.path() == r.as_str()))
+                    .cloned()
+                    .collect();
+                ((*e).clone(), requires)
+            })
+            .collect();
+        let tags = configs
+            .iter()
+            .filter(|(_, c)| !c.tags.is_empty())
+            .map(|(e, c)| ((*e).clone(), c.tags.clone()))
+            .collect();
+        let difficulty = configs
+            .iter()
+            .filter_map(|(e, c)| Some(((*e).clone(), c.difficulty?)))
+            .collect();
+
+        Ok(Self {
+            store,
+            exercises_dir,
+            exercises,
+            naming,
+            stale,
+            renamed,
+            optional,
+            prerequisites,
+            tags,
+            difficulty,
+            track: None,
+        })
+    }
+
+    /// Follow a track through the collection, i.e. an ordered subset of its exercises,
+    /// see [`ExercisesConfig::track`].
+    ///
+    /// Each entry of `track` is either a chapter folder, for all the exercises of the chapter,
+    /// or an exercise, as `<chapter folder>/<exercise folder>`.
+    /// [`ExerciseCollection::next`] and [`ExerciseCollection::statuses`] only consider the exercises
+    /// of the track, in its order.
+    pub fn select_track(&mut self, name: &str, entries: &[String]) -> Result<(), anyhow::Error> {
+        let mut exercises: Vec<ExerciseDefinition> = vec![];
+        for entry in entries {
+            let matches: Vec<_> = self
+                .exercises
+                .keys()
+                .filter(|e| e.chapter() == *entry || e.path() == *entry)
+                .collect();
+            if matches.is_empty() {
+                bail!("The track `{name}` lists `{entry}`, which is neither a chapter nor an exercise of the collection");
+            }
+            for exercise in matches {
+                if !exercises.contains(exercise) {
+                    exercises.push(exercise.clone());
+                }
+            }
+        }
+        self.track = Some(Track {
+            name: name.to_owned(),
+            exercises,
+        });
+        Ok(())
+    }
+
+    /// The name of the track the learner is following, if any.
+    pub fn track(&self) -> Option<&str> {
+        self.track.as_ref().map(|t| t.name.as_str())
+    }
+
+    /// The exercises to go through, in order: the ones in the selected track, if any,
+    /// or the whole collection.
+    fn path_through(&self) -> Vec<&ExerciseDefinition> {
+        match &self.track {
+            Some(track) => track.exercises.iter().collect(),
+            None => self.exercises.keys().collect(),
+        }
+    }
+
+    /// How chapter and exercise folders are named, see [`ExercisesConfig::naming_pattern`].
+    pub fn naming_pattern(&self) -> &NamingPattern {
+        &self.naming
+    }
+
+    /// The exercises directory that `exercise` lives in, see [`ExercisesConfig::exercises_dirs`].
+    ///
+    /// Exercises that can't be found on disk are assumed to live in the first one.
+    pub fn exercises_dir_of(&self, exercise: &ExerciseDefinition) -> &Path {
+        self.exercises
+            .get(exercise)
+            .map(PathBuf::as_path)
+            .unwrap_or(&self.exercises_dir)
+    }
+
+    /// `true` if the exercise is a bonus one, see [`ExerciseConfig::optional`].
+    ///
+    /// Learners can move on without solving it, and it doesn't count towards their completion
+    /// percentage.
+    pub fn is_optional(&self, exercise: &ExerciseDefinition) -> bool {
+        self.optional.contains(exercise)
+    }
+
+    /// `true` if every exercise (of the selected track, if any) has been opened and, unless
+    /// it's optional, solved or skipped: there is nothing left to do.
+    pub fn is_completed(&self) -> Result<bool, anyhow::Error> {
+        let opened: BTreeMap<_, _> = self
+            .opened()?
+            .into_iter()
+            .map(|e| (e.definition.clone(), e))
+            .collect();
+        Ok(self.path_through().into_iter().all(|e| {
+            opened
+                .get(e)
+                .is_some_and(|o| o.solved || o.skipped || o.optional)
+        }))
+    }
+
+    /// The tags of the exercise, see [`ExerciseConfig::tags`].
+    pub fn tags(&self, exercise: &ExerciseDefinition) -> &[String] {
+        self.tags
+            .get(exercise)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// `true` if the exercise is tagged with any of `tags`, or if `tags` is empty.
+    pub fn has_any_tag(&self, exercise: &ExerciseDefinition, tags: &[String]) -> bool {
+        tags.is_empty() || self.tags(exercise).iter().any(|t| tags.contains(t))
+    }
+
+    /// How hard the exercise is expected to be, if its configuration says so.
+    pub fn difficulty(&self, exercise: &ExerciseDefinition) -> Option<Difficulty> {
+        self.difficulty.get(exercise).copied()
+    }
+
+    /// Exercises that have been renamed or renumbered since they were opened, mapped to their
+    /// new name. The progress on them has been carried over.
+    pub fn renamed(&self) -> &BTreeMap<ExerciseDefinition, ExerciseDefinition> {
+        &self.renamed
+    }
+
+    pub fn n_opened(&self) -> Result<usize, anyhow::Error> {
+        let n_opened = self
+            .store
+            .exercises()
+            .context("Failed to determine how many workshop-runner have been opened")?
+            .len();
+        Ok(n_opened)
+    }
+
+    /// Return an iterator over all the workshop-runner that have been opened.
+    ///
+    /// Stale exercises (see [`ExerciseCollection::stale`]) are not included.
+    pub fn opened(&self) -> Result<BTreeSet<OpenedExercise>, anyhow::Error> {
+        let opened = opened_exercises(self.store.as_ref(), &self.naming)?
+            .into_iter()
+            .filter(|e| !self.stale.contains(&e.definition))
+            .map(|e| OpenedExercise {
+                optional: self.is_optional(&e.definition),
+                ..e
+            })
+            .collect();
+        Ok(opened)
+    }
+
+    /// Exercises that have been opened in the past, but can no longer be found on disk.
+    ///
+    /// It usually happens when the workshop is updated and some exercises are removed or renamed.
+    /// They are ignored by all other methods on [`ExerciseCollection`].
+    pub fn stale(&self) -> &BTreeSet<ExerciseDefinition> {
+        &self.stale
+    }
+
+    /// Remove all stale exercises (see [`ExerciseCollection::stale`]) from the progress database.
+    pub fn prune(&mut self) -> Result<BTreeSet<ExerciseDefinition>, anyhow::Error> {
+        let stale: Vec<_> = self
+            .stale
+            .iter()
+            .map(|e| (e.chapter(), e.exercise()))
+            .collect();
+        self.store
+            .remove_exercises(&stale)
+            .context("Failed to remove stale exercises from your progress")?;
+        Ok(std::mem::take(&mut self.stale))
+    }
+
+    /// Return the exercise you are currently working on, i.e. the last one that has been opened.
+    ///
+    /// It returns `None` if no exercise has been opened yet.
+    pub fn current(&self) -> Result<Option<ExerciseDefinition>, anyhow::Error> {
+        // Exercises aren't necessarily opened in order, because of prerequisites.
+        // Exercises opened by older versions of `wr` don't know when that happened: they are
+        // ordered by number instead.
+        let opened_at: BTreeMap<_, _> = self
+            .store
+            .exercises()
+            .context("Failed to determine the exercise you are currently working on")?
+            .into_iter()
+            .map(|p| ((p.chapter, p.exercise), p.opened_at))
+            .collect();
+        let current = self
+            .opened()?
+            .into_iter()
+            .map(|e| e.definition)
+            .filter(|e| e.exists(self.exercises_dir_of(e)))
+            .max_by_key(|e| {
+                let opened_at = opened_at.get(&(e.chapter(), e.exercise())).copied();
+                (opened_at.flatten(), e.clone())
+            });
+        Ok(current)
+    }
+
+    /// Return the next exercise that should be opened, if we are going through the workshop-runner
+    /// in the expected order.
+    ///
+    /// Exercises whose prerequisites (see [`ExerciseConfig::requires`]) haven't been solved yet
+    /// come after the others. If every exercise left is waiting on a prerequisite, e.g. because
+    /// they require each other, the first one is returned anyway.
+    /// If a track is selected (see [`ExerciseCollection::select_track`]), only its exercises are
+    /// considered, in the track's order.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<ExerciseDefinition>, anyhow::Error> {
+        let opened = self.opened()?;
+        let done = done_exercises(&opened);
+        let opened: BTreeSet<_> = opened.into_iter().map(|e| e.definition).collect();
+        let unsolved: Vec<_> = self
+            .path_through()
+            .into_iter()
+            .filter(|e| !opened.contains(*e))
+            .cloned()
+            .collect();
+        let mut waiting = None;
+        for next in unsolved {
+            if !next.exists(self.exercises_dir_of(&next)) {
+                self.close(&next)?;
+            } else if self.prerequisites_of(&next).all(|p| done.contains(p)) {
+                return Ok(Some(next));
+            } else {
+                waiting.get_or_insert(next);
+            }
+        }
+        Ok(waiting)
+    }
+
+    /// The exercises that must be solved before `exercise` (see [`ExerciseConfig::requires`]),
+    /// but haven't been yet.
+    ///
+    /// Skipped exercises count as solved: learners chose to move on.
+    pub fn unmet_prerequisites(
+        &self,
+        exercise: &ExerciseDefinition,
+    ) -> Result<Vec<ExerciseDefinition>, anyhow::Error> {
+        let done = done_exercises(&self.opened()?);
+        Ok(self
+            .prerequisites_of(exercise)
+            .filter(|p| !done.contains(*p))
+            .cloned()
+            .collect())
+    }
+
+    fn prerequisites_of(
+        &self,
+        exercise: &ExerciseDefinition,
+    ) -> impl Iterator<Item = &ExerciseDefinition> {
+        self.prerequisites.get(exercise).into_iter().flatten()
+    }
+
+    /// Apply a change to the progress recorded for an exercise.
+    ///
+    /// Nothing happens if the exercise hasn't been opened.
+    fn update_progress(
+        &self,
+        exercise: &ExerciseDefinition,
+        err_msg: &'static str,
+        mut f: impl FnMut(&mut ExerciseProgress),
+    ) -> Result<(), anyhow::Error> {
+        self.store
+            .update_exercise(&exercise.chapter(), &exercise.exercise(), &mut f)
+            .context(err_msg)
+    }
+
+    /// Record in the database that an exercise was solved, so that it can be skipped next time.
+    ///
+    /// The time it was first solved at is kept, even if it is solved again later on.
+    pub fn mark_as_solved(&self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
+        let mut was_solved = true;
+        self.update_progress(exercise, "Failed to mark exercise as solved", |p| {
+            was_solved = p.solved;
+            p.solved = true;
+            p.failed = false;
+            p.skipped = false;
+            p.solved_at.get_or_insert_with(unix_timestamp);
+            p.last_failure = None;
+        })?;
+        if !was_solved {
+            self.record_event(exercise, EventKind::Solved)?;
+        }
+        Ok(())
+    }
+
+    /// Append a change in the status of an exercise to its history, see [`ExerciseCollection::events`].
+    fn record_event(
+        &self,
+        exercise: &ExerciseDefinition,
+        kind: EventKind,
+    ) -> Result<(), anyhow::Error> {
+        self.store.record_event(&EventRecord {
+            chapter: exercise.chapter(),
+            exercise: exercise.exercise(),
+            occurred_at: unix_timestamp(),
+            kind,
+        })
+    }
+
+    /// Every change in the status of exercises, from the oldest to the most recent.
+    ///
+    /// If `only` is specified, only the events for that exercise are returned.
+    /// Events for exercises that can no longer be found on disk are not included.
+    pub fn events(&self, only: Option<&ExerciseDefinition>) -> Result<Vec<Event>, anyhow::Error> {
+        let events = self
+            .store
+            .events()?
+            .into_iter()
+            .filter_map(|e| {
+                let definition =
+                    ExerciseDefinition::new(e.chapter.as_ref(), e.exercise.as_ref(), &self.naming)
+                        .ok()?;
+                if !self.exercises.contains_key(&definition)
+                    || only.is_some_and(|o| o != &definition)
+                {
                     return None;
-                };
-                let Ok(file_type) = entry.file_type() else {
+                }
+                Some(Event {
+                    definition,
+                    occurred_at: e.occurred_at,
+                    kind: e.kind,
+                })
+            })
+            .collect();
+        Ok(events)
+    }
+
+    /// Remember the hash of an exercise's sources (see [`ExerciseDefinition::source_hash`])
+    /// as of the last time it was solved.
+    pub fn record_source_hash(
+        &self,
+        exercise: &ExerciseDefinition,
+        hash: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.update_progress(
+            exercise,
+            "Failed to record the hash of the exercise's sources",
+            |p| p.source_hash = Some(hash.to_owned()),
+        )
+    }
+
+    /// The hash of an exercise's sources as of the last time it was solved, if we know it.
+    pub fn source_hash(
+        &self,
+        exercise: &ExerciseDefinition,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let progress = self
+            .store
+            .exercise(&exercise.chapter(), &exercise.exercise())
+            .context("Failed to retrieve the hash of the exercise's sources")?;
+        Ok(progress.and_then(|p| p.source_hash))
+    }
+
+    /// Record in the database an attempt at verifying an exercise: how long it took and,
+    /// if it failed, which command failed.
+    ///
+    /// Nothing is recorded for exercises that haven't been opened, e.g. when verified by
+    /// `wr check --all`: they aren't part of your progress yet.
+    pub fn record_run(
+        &self,
+        exercise: &ExerciseDefinition,
+        duration: std::time::Duration,
+        failed_command: Option<&str>,
+    ) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to record how long it took to verify the exercise";
+        if self
+            .store
+            .exercise(&exercise.chapter(), &exercise.exercise())
+            .context(err_msg)?
+            .is_none()
+        {
+            return Ok(());
+        }
+        self.store
+            .record_attempt(&AttemptRecord {
+                chapter: exercise.chapter(),
+                exercise: exercise.exercise(),
+                attempted_at: unix_timestamp(),
+                duration_ms: duration.as_millis() as u64,
+                failed_command: failed_command.map(ToOwned::to_owned),
+            })
+            .context(err_msg)
+    }
+
+    /// Every attempt at verifying exercises, from the oldest to the most recent.
+    ///
+    /// If `only` is specified, only the attempts for that exercise are returned.
+    /// Attempts for stale exercises (see [`ExerciseCollection::stale`]) are not included.
+    pub fn attempts(
+        &self,
+        only: Option<&ExerciseDefinition>,
+    ) -> Result<Vec<Attempt>, anyhow::Error> {
+        let attempts = self
+            .store
+            .attempts()?
+            .into_iter()
+            .filter_map(|a| {
+                let definition =
+                    ExerciseDefinition::new(a.chapter.as_ref(), a.exercise.as_ref(), &self.naming)
+                        .ok()?;
+                if !self.exercises.contains_key(&definition)
+                    || only.is_some_and(|e| e != &definition)
+                {
                     return None;
-                };
-                if file_type.is_dir() {
-                    Some(entry)
-                } else {
-                    None
                 }
-            });
-        let exercises: BTreeSet<ExerciseDefinition> = chapters
-            .flat_map(|entry| {
-                let chapter_name = entry.file_name();
-                read_dir(entry.path()).unwrap().map(move |f| {
-                    let exercise = f.unwrap();
-                    (chapter_name.to_owned(), exercise.file_name())
+                Some(Attempt {
+                    definition,
+                    attempted_at: a.attempted_at,
+                    duration: std::time::Duration::from_millis(a.duration_ms),
+                    failed_command: a.failed_command,
                 })
             })
-            .filter_map(|(c, k)| ExerciseDefinition::new(&c, &k).ok())
             .collect();
+        Ok(attempts)
+    }
 
-        let db_path = exercises_dir.join("progress.db");
-        // Open the database (or create it, if it doesn't exist yet).
-        let connection = Connection::open(db_path)
-            .context("Failed to create a SQLite database to track your progress")?;
-        // Make sure all tables are initialised
-        connection
-            .execute(
-                "CREATE TABLE IF NOT EXISTS open_exercises (
-                chapter TEXT NOT NULL,
-                exercise TEXT NOT NULL,
-                solved INTEGER NOT NULL,
-                PRIMARY KEY (chapter, exercise)
-            )",
-                [],
-            )
-            .context("Failed to initialise our SQLite database to track your progress")?;
+    /// Timing statistics for all the exercises that have been opened, in the order we
+    /// expect them to be completed.
+    pub fn timings(&self) -> Result<Vec<ExerciseTimings>, anyhow::Error> {
+        let mut timings = self
+            .store
+            .exercises()
+            .context("Failed to retrieve timing statistics")?
+            .into_iter()
+            .filter_map(|p| {
+                let definition =
+                    ExerciseDefinition::new(p.chapter.as_ref(), p.exercise.as_ref(), &self.naming)
+                        .ok()?;
+                if self.stale.contains(&definition) {
+                    return None;
+                }
+                let time_to_solve = p.opened_at.zip(p.solved_at).map(|(o, s)| s - o);
+                Some(ExerciseTimings {
+                    definition,
+                    time_to_solve: time_to_solve
+                        .map(|s| std::time::Duration::from_secs(s.max(0) as u64)),
+                    runs: p.runs,
+                    run_time: std::time::Duration::from_millis(p.run_time_ms),
+                })
+            })
+            .collect::<Vec<_>>();
+        timings.sort_by(|a, b| a.definition.cmp(&b.definition));
+        Ok(timings)
+    }
 
-        Ok(Self {
-            connection,
-            exercises_dir,
-            exercises,
+    /// Record in the database that an exercise was not solved, so that it won't be skipped next time.
+    ///
+    /// The failure is kept until the exercise is solved, see [`ExerciseCollection::last_failure`].
+    /// It returns `true` if the exercise was solved until now, i.e. it has been broken since.
+    /// Exercises that haven't been opened are left alone.
+    pub fn mark_as_unsolved(
+        &self,
+        exercise: &ExerciseDefinition,
+        failure: &LastFailure,
+    ) -> Result<bool, anyhow::Error> {
+        let mut was_solved = false;
+        self.update_progress(exercise, "Failed to mark exercise as unsolved", |p| {
+            was_solved = p.solved;
+            p.solved = false;
+            p.failed = true;
+            p.source_hash = None;
+            p.last_failure = Some(failure.clone());
+        })?;
+        if was_solved {
+            self.record_event(exercise, EventKind::Unsolved)?;
+        }
+        Ok(was_solved)
+    }
+
+    /// Why the last verification of an exercise failed.
+    ///
+    /// It returns `None` if the exercise has never failed verification, or if it was solved since.
+    pub fn last_failure(
+        &self,
+        exercise: &ExerciseDefinition,
+    ) -> Result<Option<LastFailure>, anyhow::Error> {
+        let progress = self
+            .store
+            .exercise(&exercise.chapter(), &exercise.exercise())
+            .context("Failed to retrieve the last failure for the exercise")?;
+        Ok(progress.and_then(|p| p.last_failure))
+    }
+
+    /// Record in the database that an exercise was skipped, so that it won't stop you from
+    /// moving forward even though it hasn't been solved.
+    ///
+    /// The exercise is opened first, if it wasn't already.
+    pub fn mark_as_skipped(&mut self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
+        self.open(exercise)?;
+        let mut was_skipped = true;
+        self.update_progress(exercise, "Failed to mark exercise as skipped", |p| {
+            was_skipped = p.skipped;
+            p.skipped = true;
+        })?;
+        if !was_skipped {
+            self.record_event(exercise, EventKind::Skipped)?;
+        }
+        Ok(())
+    }
+
+    /// How many hints have been revealed so far for an exercise.
+    pub fn hints_used(&self, exercise: &ExerciseDefinition) -> Result<usize, anyhow::Error> {
+        let progress = self
+            .store
+            .exercise(&exercise.chapter(), &exercise.exercise())
+            .context("Failed to determine how many hints you have already used")?;
+        Ok(progress.map(|p| p.hints_used).unwrap_or(0))
+    }
+
+    /// Record in the database that one more hint has been revealed for an exercise.
+    pub fn use_hint(&self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
+        self.update_progress(exercise, "Failed to record that you used a hint", |p| {
+            p.hints_used += 1;
         })
     }
 
-    pub fn n_opened(&self) -> Result<usize, anyhow::Error> {
-        let err_msg = "Failed to determine how many workshop-runner have been opened";
-        let mut stmt = self
-            .connection
-            .prepare("SELECT COUNT(*) FROM open_exercises")
-            .context(err_msg)?;
-        stmt.query_row([], |row| row.get(0)).context(err_msg)
+    /// Bookmark an exercise, to come back to it later.
+    ///
+    /// It returns `false` if the exercise was already bookmarked.
+    pub fn bookmark(&self, exercise: &ExerciseDefinition) -> Result<bool, anyhow::Error> {
+        if !self.exercises.contains_key(exercise) {
+            bail!("The exercise you are trying to bookmark doesn't exist")
+        }
+        self.store
+            .add_bookmark(&exercise.chapter(), &exercise.exercise())
     }
 
-    /// Return an iterator over all the workshop-runner that have been opened.
-    pub fn opened(&self) -> Result<BTreeSet<OpenedExercise>, anyhow::Error> {
-        opened_exercises(&self.connection)
+    /// Remove the bookmark for an exercise.
+    ///
+    /// It returns `false` if the exercise wasn't bookmarked.
+    pub fn remove_bookmark(&self, exercise: &ExerciseDefinition) -> Result<bool, anyhow::Error> {
+        self.store
+            .remove_bookmark(&exercise.chapter(), &exercise.exercise())
     }
 
-    /// Return the next exercise that should be opened, if we are going through the workshop-runner
-    /// in the expected order.
-    pub fn next(&mut self) -> Result<Option<ExerciseDefinition>, anyhow::Error> {
-        let opened = opened_exercises(&self.connection)?
+    /// The exercises that have been bookmarked, in collection order.
+    ///
+    /// Bookmarks for exercises that can no longer be found on disk are not included.
+    pub fn bookmarks(&self) -> Result<BTreeSet<ExerciseDefinition>, anyhow::Error> {
+        Ok(self
+            .store
+            .bookmarks()?
             .into_iter()
-            .map(|e| e.definition)
-            .collect();
-        let unsolved = self
-            .exercises
-            .difference(&opened)
-            .cloned()
-            .collect::<BTreeSet<_>>();
-        for next in unsolved {
-            if next.exists(&self.exercises_dir) {
-                return Ok(Some(next));
-            } else {
-                self.close(&next)?;
-            }
-        }
-        Ok(None)
+            .filter_map(|(chapter, exercise)| {
+                ExerciseDefinition::new(chapter.as_ref(), exercise.as_ref(), &self.naming).ok()
+            })
+            .filter(|e| self.exercises.contains_key(e))
+            .collect())
     }
 
-    /// Record in the database that an exercise was solved, so that it can be skipped next time.
-    pub fn mark_as_solved(&self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
-        self.connection
-            .execute(
-                "UPDATE open_exercises SET solved = 1 WHERE chapter = ?1 AND exercise = ?2",
-                params![exercise.chapter(), exercise.exercise(),],
-            )
-            .context("Failed to mark exercise as solved")?;
-        Ok(())
+    /// Attach a note to an exercise.
+    pub fn add_note(&self, exercise: &ExerciseDefinition, text: &str) -> Result<(), anyhow::Error> {
+        if !self.exercises.contains_key(exercise) {
+            bail!("The exercise you are trying to take notes on doesn't exist")
+        }
+        self.store.add_note(&NoteRecord {
+            chapter: exercise.chapter(),
+            exercise: exercise.exercise(),
+            created_at: unix_timestamp(),
+            text: text.to_owned(),
+        })
     }
 
-    /// Record in the database that an exercise was not solved, so that it won't be skipped next time.
-    pub fn mark_as_unsolved(&self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
-        self.connection
-            .execute(
-                "UPDATE open_exercises SET solved = 0 WHERE chapter = ?1 AND exercise = ?2",
-                params![exercise.chapter(), exercise.exercise(),],
-            )
-            .context("Failed to mark exercise as unsolved")?;
-        Ok(())
+    /// Your notes, sorted by exercise (in collection order) and then from the oldest to the
+    /// most recent.
+    ///
+    /// If `only` is specified, only the notes on that exercise are returned.
+    /// Notes on exercises that can no longer be found on disk are not included.
+    pub fn notes(&self, only: Option<&ExerciseDefinition>) -> Result<Vec<Note>, anyhow::Error> {
+        let mut notes: Vec<_> = self
+            .store
+            .notes()?
+            .into_iter()
+            .filter_map(|n| {
+                let definition =
+                    ExerciseDefinition::new(n.chapter.as_ref(), n.exercise.as_ref(), &self.naming)
+                        .ok()?;
+                if !self.exercises.contains_key(&definition)
+                    || only.is_some_and(|e| e != &definition)
+                {
+                    return None;
+                }
+                Some(Note {
+                    definition,
+                    created_at: n.created_at,
+                    text: n.text,
+                })
+            })
+            .collect();
+        // The sort is stable: notes on the same exercise stay in chronological order.
+        notes.sort_by(|a, b| a.definition.cmp(&b.definition));
+        Ok(notes)
     }
 
     /// Open a specific exercise.
     pub fn open(&mut self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
-        if !self.exercises.contains(exercise) {
+        if !self.exercises.contains_key(exercise) {
             bail!("The exercise you are trying to open doesn't exist")
         }
-        self.connection
-            .execute(
-                "INSERT OR IGNORE INTO open_exercises (chapter, exercise, solved) VALUES (?1, ?2, 0)",
-                params![exercise.chapter(), exercise.exercise(),],
-            )
+        let fingerprint = exercise.fingerprint(self.exercises_dir_of(exercise))?;
+        let opened = self
+            .store
+            .open_exercise(&ExerciseProgress {
+                opened_at: Some(unix_timestamp()),
+                fingerprint: Some(fingerprint),
+                ..ExerciseProgress::new(exercise.chapter(), exercise.exercise())
+            })
             .context("Failed to open the next exercise")?;
+        if opened {
+            self.record_event(exercise, EventKind::Opened)?;
+        }
         Ok(())
     }
 
     /// Close a specific exercise.
     pub fn close(&mut self, exercise: &ExerciseDefinition) -> Result<(), anyhow::Error> {
-        self.connection
-            .execute(
-                "DELETE FROM open_exercises WHERE chapter = ?1 AND exercise = ?2",
-                params![exercise.chapter(), exercise.exercise(),],
-            )
-            .context("Failed to close an exercise")?;
-        Ok(())
+        self.store
+            .remove_exercises(&[(exercise.chapter(), exercise.exercise())])
+            .context("Failed to close an exercise")
+    }
+
+    /// Forget all progress for the collection, as if no exercise had ever been opened.
+    pub fn reset(&mut self) -> Result<(), anyhow::Error> {
+        self.store.reset()
+    }
+
+    /// Save a copy of everything recorded for the collection, see [`progress::backup`].
+    /// It returns where the copy has been saved.
+    pub fn backup(&self, to: Option<&Path>) -> Result<PathBuf, anyhow::Error> {
+        progress::backup(self.store.as_ref(), to)
+    }
+
+    /// Replace everything recorded for the collection with a copy saved by [`ExerciseCollection::backup`].
+    pub fn restore_backup(&mut self, from: &Path) -> Result<(), anyhow::Error> {
+        self.store.restore(from)
     }
 
     /// Open the next exercise, assuming we are going through the workshop-runner in order.
@@ -242,6 +2052,138 @@ impl ExerciseCollection {
         Ok(next)
     }
 
+    /// Take a snapshot of your progress on the collection.
+    pub fn export(&self) -> Result<ProgressSnapshot, anyhow::Error> {
+        let exercises = self
+            .store
+            .exercises()
+            .context("Failed to export your progress")?
+            .into_iter()
+            // The hash is only meaningful to the `wr` binary that computed it.
+            .map(|p| ExerciseProgress {
+                source_hash: None,
+                ..p
+            })
+            .collect();
+        Ok(ProgressSnapshot { exercises })
+    }
+
+    /// Take a snapshot of your progress to be shared with your other machines, e.g. by `wr sync`.
+    ///
+    /// Unlike [`ExerciseCollection::export`], it leaves out exercises that can no longer be
+    /// found in the collection and the output of failed verifications, which is only useful
+    /// on the machine where they ran.
+    pub fn export_for_sync(&self) -> Result<ProgressSnapshot, anyhow::Error> {
+        let mut snapshot = self.export()?;
+        snapshot.exercises.retain(|p| {
+            ExerciseDefinition::new(p.chapter.as_ref(), p.exercise.as_ref(), &self.naming)
+                .is_ok_and(|definition| self.exercises.contains_key(&definition))
+        });
+        for p in &mut snapshot.exercises {
+            p.last_failure = None;
+        }
+        Ok(snapshot)
+    }
+
+    /// Restore progress from a snapshot.
+    ///
+    /// Exercises in the snapshot overwrite the progress you have already recorded for them,
+    /// while all other exercises are left untouched.
+    pub fn import(&mut self, snapshot: &ProgressSnapshot) -> Result<(), anyhow::Error> {
+        for e in &snapshot.exercises {
+            ExerciseDefinition::new(e.chapter.as_ref(), e.exercise.as_ref(), &self.naming)
+                .with_context(|| {
+                    format!(
+                        "The snapshot contains an invalid exercise: `{}/{}`",
+                        e.chapter, e.exercise
+                    )
+                })?;
+        }
+        self.store
+            .save_exercises(&snapshot.exercises)
+            .context("Failed to import your progress")
+    }
+
+    /// Merge progress from a snapshot recorded on another machine, e.g. by `wr sync`.
+    ///
+    /// Unlike [`ExerciseCollection::import`], nothing is overwritten blindly: for every exercise,
+    /// we keep the record with the most verification runs (i.e. the most recent one) while preserving
+    /// when the exercise was first opened and solved, and how many hints were used.
+    /// Exercises that can't be found in the collection are ignored.
+    /// It returns the number of exercises whose progress changed.
+    pub fn merge(&mut self, remote: &ProgressSnapshot) -> Result<usize, anyhow::Error> {
+        let err_msg = "Failed to merge your progress";
+        let local = self.store.exercises().context(err_msg)?;
+        let mut changed = Vec::new();
+        for theirs in &remote.exercises {
+            let definition = ExerciseDefinition::new(
+                theirs.chapter.as_ref(),
+                theirs.exercise.as_ref(),
+                &self.naming,
+            )
+            .with_context(|| {
+                format!(
+                    "The snapshot contains an invalid exercise: `{}/{}`",
+                    theirs.chapter, theirs.exercise
+                )
+            })?;
+            // E.g. it was removed or renamed by an update to the workshop that the other machine
+            // hasn't pulled yet: it'd only show up as stale here.
+            if !self.exercises.contains_key(&definition) {
+                continue;
+            }
+            let ours = local
+                .iter()
+                .find(|p| p.chapter == theirs.chapter && p.exercise == theirs.exercise);
+            let merged = match ours {
+                None => theirs.clone(),
+                Some(ours) => ours.merge(theirs),
+            };
+            if ours != Some(&merged) {
+                changed.push(merged);
+            }
+        }
+        self.store.save_exercises(&changed).context(err_msg)?;
+        Ok(changed.len())
+    }
+
+    /// When the first exercise in the collection was opened, as a Unix timestamp (in seconds).
+    ///
+    /// It returns `None` if no exercise has been opened yet, or if the exercises were
+    /// opened by an older version of `wr` that didn't keep track of it.
+    pub fn started_at(&self) -> Result<Option<i64>, anyhow::Error> {
+        let started_at = self
+            .store
+            .exercises()
+            .context("Failed to determine when you started working on the workshop")?
+            .into_iter()
+            .filter_map(|p| p.opened_at)
+            .min();
+        Ok(started_at)
+    }
+
+    /// Determine the status of every exercise in the collection, in the order we expect
+    /// them to be completed.
+    /// If a track is selected, only the exercises of the track are included.
+    pub fn statuses(&self) -> Result<Vec<(ExerciseDefinition, ExerciseStatus)>, anyhow::Error> {
+        let opened = self.opened()?;
+        let statuses = self
+            .path_through()
+            .into_iter()
+            .map(|definition| {
+                let status = match opened.iter().find(|o| &o.definition == definition) {
+                    None => ExerciseStatus::Unopened,
+                    Some(o) if o.solved => ExerciseStatus::Solved,
+                    Some(o) if o.skipped => ExerciseStatus::Skipped,
+                    Some(o) if o.failed => ExerciseStatus::Failed,
+                    Some(_) => ExerciseStatus::Opened,
+                };
+                (definition.to_owned(), status)
+            })
+            .collect();
+        Ok(statuses)
+    }
+
     /// The directory containing all the workshop chapters and workshop-runner.
     pub fn exercises_dir(&self) -> &Path {
         &self.exercises_dir
@@ -250,48 +2192,469 @@ impl ExerciseCollection {
     /// Iterate over the workshop-runner in the collection, in the order we expect them to be completed.
     /// It returns both opened and unopened workshop-runner.
     pub fn iter(&self) -> impl Iterator<Item = &ExerciseDefinition> {
-        self.exercises.iter()
+        self.exercises.keys()
+    }
+}
+
+/// Find all the exercises in the exercises directories, without touching the progress database,
+/// with the exercises directory each of them was found in.
+///
+/// Chapters are numbered across directories: it's an error for two directories to contain
+/// a chapter with the same number.
+pub fn discover_exercises(
+    exercises_dirs: &[PathBuf],
+    naming: &NamingPattern,
+) -> Result<BTreeMap<ExerciseDefinition, PathBuf>, anyhow::Error> {
+    let mut exercises = BTreeMap::new();
+    let mut chapters: BTreeMap<u16, (String, &Path)> = BTreeMap::new();
+    for exercises_dir in exercises_dirs {
+        for exercise in discover_exercises_in(exercises_dir, naming)? {
+            let (chapter, chapter_root) = chapters
+                .entry(exercise.chapter_number)
+                .or_insert_with(|| (exercise.chapter(), exercises_dir));
+            if *chapter_root != exercises_dir {
+                bail!(
+                    "Both {:?} and {:?} contain chapter {:02}: chapters must be numbered across exercises directories",
+                    chapter_root.join(chapter.as_str()),
+                    exercises_dir.join(exercise.chapter()),
+                    exercise.chapter_number
+                );
+            }
+            exercises.insert(exercise, exercises_dir.clone());
+        }
+    }
+    Ok(exercises)
+}
+
+fn discover_exercises_in(
+    exercises_dir: &Path,
+    naming: &NamingPattern,
+) -> Result<BTreeSet<ExerciseDefinition>, anyhow::Error> {
+    let chapters = read_dir(exercises_dir)
+        .context("Failed to read the exercises directory")?
+        .filter_map(|entry| {
+            let Ok(entry) = entry else {
+                return None;
+            };
+            let Ok(file_type) = entry.file_type() else {
+                return None;
+            };
+            if file_type.is_dir() {
+                Some(entry)
+            } else {
+                None
+            }
+        });
+    let exercises = chapters
+        .flat_map(|entry| {
+            let chapter_name = entry.file_name();
+            read_dir(entry.path()).unwrap().map(move |f| {
+                let exercise = f.unwrap();
+                (chapter_name.to_owned(), exercise.file_name())
+            })
+        })
+        .filter_map(|(c, k)| ExerciseDefinition::new(&c, &k, naming).ok())
+        .collect();
+    Ok(exercises)
+}
+
+/// Move the progress on `stale` exercises to the exercises on disk that have the same
+/// fingerprint (see [`ExerciseDefinition::fingerprint`]), if there is exactly one of them and it
+/// hasn't been opened yet.
+fn carry_over_renamed(
+    store: &dyn ProgressStore,
+    exercises: &BTreeMap<ExerciseDefinition, PathBuf>,
+    stale: &BTreeSet<ExerciseDefinition>,
+) -> Result<BTreeMap<ExerciseDefinition, ExerciseDefinition>, anyhow::Error> {
+    let progress = store.exercises()?;
+    let opened: BTreeSet<_> = progress
+        .iter()
+        .map(|p| (p.chapter.as_str(), p.exercise.as_str()))
+        .collect();
+    let mut candidates: BTreeMap<String, Vec<&ExerciseDefinition>> = BTreeMap::new();
+    for (exercise, exercises_dir) in exercises {
+        if opened.contains(&(exercise.chapter().as_str(), exercise.exercise().as_str()))
+            || !exercise.exists(exercises_dir)
+        {
+            continue;
+        }
+        // An invalid configuration will be reported when the exercise is verified.
+        if let Ok(fingerprint) = exercise.fingerprint(exercises_dir) {
+            candidates.entry(fingerprint).or_default().push(exercise);
+        }
     }
+
+    let mut renamed = BTreeMap::new();
+    for old in stale {
+        let fingerprint = progress
+            .iter()
+            .find(|p| p.chapter == old.chapter() && p.exercise == old.exercise())
+            .and_then(|p| p.fingerprint.clone())
+            .unwrap_or_else(|| old.name_fingerprint());
+        let Some([new]) = candidates.get_mut(&fingerprint).map(|c| c.as_slice()) else {
+            continue;
+        };
+        let new = (*new).clone();
+        store.rename_exercise(
+            (&old.chapter(), &old.exercise()),
+            (&new.chapter(), &new.exercise()),
+        )?;
+        // It's now opened: it can't be the new name of another exercise.
+        candidates.remove(&fingerprint);
+        renamed.insert(old.clone(), new);
+    }
+    Ok(renamed)
+}
+
+/// The exercises that have been solved or skipped, i.e. that don't hold back the ones requiring them.
+fn done_exercises(opened: &BTreeSet<OpenedExercise>) -> BTreeSet<ExerciseDefinition> {
+    opened
+        .iter()
+        .filter(|e| e.solved || e.skipped)
+        .map(|e| e.definition.clone())
+        .collect()
 }
 
 /// Return the set of all workshop-runner that have been opened.
-fn opened_exercises(connection: &Connection) -> Result<BTreeSet<OpenedExercise>, anyhow::Error> {
-    let err_msg = "Failed to retrieve the list of exercises that you have already started";
-    let mut stmt = connection
-        .prepare("SELECT chapter, exercise, solved FROM open_exercises")
-        .context(err_msg)?;
-    let opened_exercises = stmt
-        .query_map([], |row| {
-            let chapter = row.get_ref_unwrap(0).as_str().unwrap();
-            let exercise = row.get_ref_unwrap(1).as_str().unwrap();
-            let solved = row.get_ref_unwrap(2).as_i64().unwrap();
-            let solved = if solved == 0 { false } else { true };
-            let definition = ExerciseDefinition::new(chapter.as_ref(), exercise.as_ref())
-                .expect("An invalid exercise has been stored in the database");
-            Ok(OpenedExercise { definition, solved })
+fn opened_exercises(
+    store: &dyn ProgressStore,
+    naming: &NamingPattern,
+) -> Result<BTreeSet<OpenedExercise>, anyhow::Error> {
+    store
+        .exercises()
+        .context("Failed to retrieve the list of exercises that you have already started")?
+        .into_iter()
+        .map(|p| {
+            let definition =
+                ExerciseDefinition::new(p.chapter.as_ref(), p.exercise.as_ref(), naming)
+                    .with_context(|| {
+                        format!(
+                            "An invalid exercise has been stored in your progress: `{}/{}`.\n\
+                        Run `wr db check --fix` to remove it.",
+                            p.chapter, p.exercise
+                        )
+                    })?;
+            Ok(OpenedExercise {
+                definition,
+                solved: p.solved,
+                failed: p.failed,
+                skipped: p.skipped,
+                // It's not recorded in the progress: see `ExerciseCollection::opened`.
+                optional: false,
+            })
         })
-        .context(err_msg)?
-        .collect::<Result<BTreeSet<_>, _>>()?;
-    Ok(opened_exercises)
+        .collect()
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// The current time, as a Unix timestamp (in seconds).
+pub fn unix_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Format the time spent verifying an exercise, which is often below a minute.
+pub fn format_run_time(run_time: std::time::Duration) -> String {
+    if run_time.as_secs() < 60 {
+        format!("{:.1}s", run_time.as_secs_f64())
+    } else {
+        format_duration(run_time.as_secs() as i64)
+    }
+}
+
+/// Format a duration, expressed in seconds, in a human-friendly way (e.g. `2h 05m`).
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours:02}h {minutes:02}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {:02}s", seconds % 60)
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// Normalize the output of a program before comparing it with the expected one:
+/// trailing whitespace is removed from each line, as well as leading and trailing empty lines.
+pub fn normalize_output(s: &str) -> String {
+    let lines: Vec<_> = s.lines().map(str::trim_end).collect();
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end].join("\n")
+}
+
+/// Remove ANSI escape codes (e.g. colors) from the output of a command.
+pub fn strip_ansi_codes(s: &str) -> String {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    let re = REGEX.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+    re.replace_all(s, "").into_owned()
+}
+
+/// List all the files in a directory, recursively, as paths relative to that directory.
+///
+/// Build artifacts (i.e. `target` directories) are skipped.
+pub fn exercise_files(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    fn visit(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), anyhow::Error> {
+        for entry in read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                if entry.file_name() != "target" {
+                    visit(root, &path, files)?;
+                }
+            } else {
+                files.push(path.strip_prefix(root)?.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = vec![];
+    visit(dir, dir, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct ExerciseDefinition {
     chapter_name: String,
     chapter_number: u16,
     name: String,
     number: u16,
+    /// The name of the chapter folder, e.g. `01_intro`.
+    chapter_dir_name: String,
+    /// The name of the exercise folder, e.g. `00_welcome`.
+    dir_name: String,
+}
+
+/// The naming scheme of chapter and exercise folders when the collection doesn't specify one:
+/// a two or three digit number, an underscore, then the name, e.g. `01_intro`.
+pub const DEFAULT_NAMING_PATTERN: &str = r"^(?P<number>\d{2,3})_(?P<name>\w+)$";
+
+/// The naming scheme of chapter and exercise folders, see [`ExercisesConfig::naming_pattern`]:
+/// a regular expression with a `number` and a `name` capture group.
+/// It tells chapters and exercises apart from other folders and extracts their number and name.
+#[derive(Debug, Clone)]
+pub struct NamingPattern(Regex);
+
+impl NamingPattern {
+    pub fn new(pattern: &str) -> Result<Self, anyhow::Error> {
+        let re =
+            Regex::new(pattern).context("`naming_pattern` must be a valid regular expression")?;
+        for group in ["number", "name"] {
+            if !re.capture_names().flatten().any(|name| name == group) {
+                bail!("`naming_pattern` must have a capture group named `{group}`, e.g. `(?P<{group}>...)`");
+            }
+        }
+        Ok(Self(re))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Default for NamingPattern {
+    fn default() -> Self {
+        Self(Regex::new(DEFAULT_NAMING_PATTERN).unwrap())
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct OpenedExercise {
     pub definition: ExerciseDefinition,
     pub solved: bool,
+    /// `true` if the last verification run for this exercise failed.
+    pub failed: bool,
+    /// `true` if the exercise was skipped without being solved.
+    pub skipped: bool,
+    /// `true` if the exercise is a bonus one, see [`ExerciseCollection::is_optional`].
+    pub optional: bool,
+}
+
+/// How much time was spent on an exercise.
+#[derive(Clone)]
+pub struct ExerciseTimings {
+    pub definition: ExerciseDefinition,
+    /// How long it took to solve the exercise, from the moment it was opened.
+    /// `None` if it hasn't been solved yet (or if we don't know when it was opened).
+    pub time_to_solve: Option<std::time::Duration>,
+    /// How many times the exercise has been verified.
+    pub runs: usize,
+    /// The total time spent verifying the exercise.
+    pub run_time: std::time::Duration,
+}
+
+/// An attempt at verifying an exercise.
+#[derive(Clone)]
+pub struct Attempt {
+    pub definition: ExerciseDefinition,
+    /// When the attempt was made, as a Unix timestamp (in seconds).
+    pub attempted_at: i64,
+    /// How long the verification took.
+    pub duration: std::time::Duration,
+    /// The command that failed, or `None` if the verification succeeded.
+    pub failed_command: Option<String>,
+}
+
+/// A change in the status of an exercise, see [`ExerciseCollection::events`].
+#[derive(Clone)]
+pub struct Event {
+    pub definition: ExerciseDefinition,
+    /// When the change happened, as a Unix timestamp (in seconds).
+    pub occurred_at: i64,
+    pub kind: EventKind,
+}
+
+/// A free-form note attached to an exercise.
+#[derive(Clone)]
+pub struct Note {
+    pub definition: ExerciseDefinition,
+    /// When the note was written, as a Unix timestamp (in seconds).
+    pub created_at: i64,
+    pub text: String,
+}
+
+/// A portable snapshot of your progress on a collection, e.g. to move it to another machine.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProgressSnapshot {
+    pub exercises: Vec<ExerciseProgress>,
+}
+
+/// The progress on a single exercise, as stored in a [`ProgressSnapshot`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExerciseProgress {
+    /// The number+name of the chapter, e.g. `01_intro`.
+    pub chapter: String,
+    /// The number+name of the exercise, e.g. `00_welcome`.
+    pub exercise: String,
+    pub solved: bool,
+    #[serde(default)]
+    pub failed: bool,
+    #[serde(default)]
+    pub skipped: bool,
+    #[serde(default)]
+    pub hints_used: usize,
+    /// When the exercise was opened, as a Unix timestamp (in seconds).
+    #[serde(default)]
+    pub opened_at: Option<i64>,
+    /// When the exercise was first solved, as a Unix timestamp (in seconds).
+    #[serde(default)]
+    pub solved_at: Option<i64>,
+    /// How many times the exercise has been verified.
+    #[serde(default)]
+    pub runs: usize,
+    /// The total time spent verifying the exercise, in milliseconds.
+    #[serde(default)]
+    pub run_time_ms: u64,
+    /// A hash of the exercise's sources, as of the last time it was solved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+    /// Why the last verification failed, unless it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<LastFailure>,
+    /// See [`ExerciseDefinition::fingerprint`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+impl ExerciseProgress {
+    /// Combine two records of the progress on the same exercise, see [`ExerciseCollection::merge`].
+    fn merge(&self, other: &ExerciseProgress) -> ExerciseProgress {
+        // On a tie, a solved exercise wins over an unsolved one.
+        let (latest, oldest) = if (other.runs, other.solved) > (self.runs, self.solved) {
+            (other, self)
+        } else {
+            (self, other)
+        };
+        let earliest = |a: Option<i64>, b: Option<i64>| a.into_iter().chain(b).min();
+        ExerciseProgress {
+            opened_at: earliest(latest.opened_at, oldest.opened_at),
+            solved_at: earliest(latest.solved_at, oldest.solved_at),
+            hints_used: latest.hints_used.max(oldest.hints_used),
+            run_time_ms: latest.run_time_ms.max(oldest.run_time_ms),
+            // Snapshots don't carry hashes: fall back on the local one.
+            source_hash: latest.source_hash.clone().or(oldest.source_hash.clone()),
+            fingerprint: latest.fingerprint.clone().or(oldest.fingerprint.clone()),
+            ..latest.clone()
+        }
+    }
+}
+
+/// A failed verification, kept around to be looked at again later.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LastFailure {
+    /// The name of the verification stage that failed.
+    pub stage: String,
+    /// The command that failed.
+    pub command: String,
+    /// What the command printed.
+    pub output: String,
+}
+
+impl ExerciseProgress {
+    /// The progress on an exercise that has just been opened.
+    pub fn new(chapter: String, exercise: String) -> Self {
+        Self {
+            chapter,
+            exercise,
+            solved: false,
+            failed: false,
+            skipped: false,
+            hints_used: 0,
+            opened_at: None,
+            solved_at: None,
+            runs: 0,
+            run_time_ms: 0,
+            source_hash: None,
+            last_failure: None,
+            fingerprint: None,
+        }
+    }
+}
+
+/// Where a learner stands with respect to a specific exercise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExerciseStatus {
+    /// The exercise hasn't been opened yet.
+    Unopened,
+    /// The exercise has been opened, but it hasn't been verified yet.
+    Opened,
+    /// The exercise has been opened and it passed verification.
+    Solved,
+    /// The exercise has been opened, but the last verification run failed.
+    Failed,
+    /// The exercise has been skipped without being solved.
+    Skipped,
+}
+
+impl std::fmt::Display for ExerciseStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ExerciseStatus::Unopened => "unopened",
+            ExerciseStatus::Opened => "opened",
+            ExerciseStatus::Solved => "solved",
+            ExerciseStatus::Failed => "failed",
+            ExerciseStatus::Skipped => "skipped",
+        };
+        write!(f, "{s}")
+    }
 }
 
 impl PartialOrd for OpenedExercise {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.definition.partial_cmp(&other.definition)
+        Some(self.cmp(other))
     }
 }
 
@@ -303,17 +2666,19 @@ impl Ord for OpenedExercise {
 
 impl PartialOrd for ExerciseDefinition {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let ord = self
-            .chapter_number
-            .cmp(&other.chapter_number)
-            .then(self.number.cmp(&other.number));
-        Some(ord)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for ExerciseDefinition {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        self.chapter_number
+            .cmp(&other.chapter_number)
+            .then(self.number.cmp(&other.number))
+            // Folders with the same numbers, e.g. `01_intro` and `001_intro`, are still different
+            // exercises. The names are derived from the folder names, so they don't need comparing.
+            .then_with(|| self.chapter_dir_name.cmp(&other.chapter_dir_name))
+            .then_with(|| self.dir_name.cmp(&other.dir_name))
     }
 }
 
@@ -330,38 +2695,61 @@ impl PartialOrd<OpenedExercise> for ExerciseDefinition {
 }
 
 impl ExerciseDefinition {
-    pub fn new(chapter_dir_name: &OsStr, exercise_dir_name: &OsStr) -> Result<Self, anyhow::Error> {
-        fn parse(dir_name: &OsStr, type_: &str) -> Result<(String, u16), anyhow::Error> {
-            // TODO: compile the regex only once.
-            let re = Regex::new(r"(?P<number>\d{2})_(?P<name>\w+)").unwrap();
-
-            let dir_name = dir_name.to_str().ok_or_else(|| {
-                anyhow!(
-                    "The name of a {type_} must be valid UTF-8 text, but {:?} isn't",
-                    dir_name
+    pub fn new(
+        chapter_dir_name: &OsStr,
+        exercise_dir_name: &OsStr,
+        naming: &NamingPattern,
+    ) -> Result<Self, anyhow::Error> {
+        let parse =
+            |dir_name: &OsStr, type_: &str| -> Result<(String, u16, String), anyhow::Error> {
+                let re = &naming.0;
+
+                let dir_name = dir_name.to_str().ok_or_else(|| {
+                    anyhow!(
+                        "The name of a {type_} must be valid UTF-8 text, but {:?} isn't",
+                        dir_name
+                    )
+                })?;
+                let Some(s) = re.captures(dir_name) else {
+                    bail!(
+                        "Failed to parse `{dir_name:?}` as a {type_}: it doesn't match `{}`.",
+                        re.as_str()
+                    )
+                };
+                let name = s["name"].into();
+                let number = s["number"].parse().with_context(|| {
+                    format!(
+                    "The number of the {type_} `{dir_name}` must be an integer between 0 and 65535"
                 )
-            })?;
-            match re.captures(&dir_name) {
-                None => bail!("Failed to parse `{dir_name:?}` as a {type_} (<NN>_<name>).",),
-                Some(s) => {
-                    let name = s["name"].into();
-                    let number = s["number"].parse().unwrap();
-                    Ok((name, number))
-                }
-            }
-        }
+                })?;
+                Ok((name, number, dir_name.to_owned()))
+            };
 
-        let (name, number) = parse(exercise_dir_name, "exercise")?;
-        let (chapter_name, chapter_number) = parse(chapter_dir_name, "chapter")?;
+        let (name, number, dir_name) = parse(exercise_dir_name, "exercise")?;
+        let (chapter_name, chapter_number, chapter_dir_name) = parse(chapter_dir_name, "chapter")?;
 
         Ok(ExerciseDefinition {
             chapter_name,
             chapter_number,
             name,
             number,
+            chapter_dir_name,
+            dir_name,
         })
     }
 
+    /// An identifier for the exercise that survives renumbering: its `id`, if its configuration
+    /// specifies one, or the names of its chapter and exercise, without their numbers.
+    pub fn fingerprint(&self, exercises_dir: &Path) -> Result<String, anyhow::Error> {
+        let id = self.config(exercises_dir)?.and_then(|c| c.id);
+        Ok(id.unwrap_or_else(|| self.name_fingerprint()))
+    }
+
+    /// The fingerprint of an exercise that doesn't specify an `id`.
+    fn name_fingerprint(&self) -> String {
+        format!("{}/{}", self.chapter_name, self.name)
+    }
+
     /// The path to the `Cargo.toml` file of the current exercise.
     pub fn manifest_path(&self, exercises_dir: &Path) -> PathBuf {
         self.manifest_folder_path(exercises_dir).join("Cargo.toml")
@@ -369,37 +2757,149 @@ impl ExerciseDefinition {
 
     /// The path to the folder containing the `Cargo.toml` file for the current exercise.
     pub fn manifest_folder_path(&self, exercises_dir: &Path) -> PathBuf {
-        exercises_dir.join(self.chapter()).join(self.exercise())
+        self.chapter_folder_path(exercises_dir)
+            .join(self.exercise())
+    }
+
+    /// The path to the folder of the chapter that contains the current exercise.
+    pub fn chapter_folder_path(&self, exercises_dir: &Path) -> PathBuf {
+        exercises_dir.join(self.chapter())
+    }
+
+    /// A hash of every file in the exercise folder, to detect changes to its sources,
+    /// see [`hash_sources`].
+    pub fn source_hash(&self, exercises_dir: &Path) -> Result<String, anyhow::Error> {
+        hash_sources(&self.manifest_folder_path(exercises_dir))
+    }
+
+    /// The path to the folder containing the reference solution for the current exercise.
+    pub fn solution_folder_path(&self, solutions_dir: &Path) -> PathBuf {
+        solutions_dir.join(self.chapter()).join(self.exercise())
+    }
+
+    /// The path to the README of the current exercise, if it has one.
+    pub fn readme_path(&self, exercises_dir: &Path) -> Option<PathBuf> {
+        let folder = self.manifest_folder_path(exercises_dir);
+        read_dir(&folder)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .find(|entry| {
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                name == "readme.md" || name == "readme"
+            })
+            .map(|entry| entry.path())
     }
 
     /// The configuration for the current exercise, if any.
+    ///
+    /// It combines the configuration of the chapter (a `.wr.toml` file in the chapter folder),
+    /// which applies to all its exercises, with the configuration of the exercise itself:
+    /// settings specified for the exercise take precedence.
     pub fn config(&self, exercises_dir: &Path) -> Result<Option<ExerciseConfig>, anyhow::Error> {
+        let chapter_config = self.chapter_config(exercises_dir)?;
         let exercise_config = self.manifest_folder_path(exercises_dir).join(".wr.toml");
-        if !exercise_config.exists() {
+        if chapter_config.is_none() && !exercise_config.exists() {
             return Ok(None);
         }
-        let exercise_config = fs_err::read_to_string(&exercise_config).context(format!(
-            "Failed to read the configuration for the exercise `{}`",
-            self.exercise()
-        ))?;
-        let exercise_config: ExerciseConfig =
-            toml::from_str(&exercise_config).with_context(|| {
-                format!(
-                    "Failed to parse the configuration for the exercise `{}`",
-                    self.exercise()
-                )
-            })?;
+        let mut config = chapter_config.unwrap_or_default();
+        if exercise_config.exists() {
+            let exercise_config = fs_err::read_to_string(&exercise_config).context(format!(
+                "Failed to read the configuration for the exercise `{}`",
+                self.exercise()
+            ))?;
+            let exercise_config: toml::Table =
+                toml::from_str(&exercise_config).with_context(|| {
+                    format!(
+                        "Failed to parse the configuration for the exercise `{}`",
+                        self.exercise()
+                    )
+                })?;
+            config.extend(exercise_config);
+        }
+        let exercise_config: ExerciseConfig = config.try_into().with_context(|| {
+            format!(
+                "Failed to parse the configuration for the exercise `{}`",
+                self.exercise()
+            )
+        })?;
         Ok(Some(exercise_config))
     }
 
-    /// The number+name of the chapter that contains this exercise.
+    /// The settings shared by all the exercises in the chapter, if any.
+    fn chapter_config(&self, exercises_dir: &Path) -> Result<Option<toml::Table>, anyhow::Error> {
+        let chapter_config = self.chapter_folder_path(exercises_dir).join(".wr.toml");
+        if !chapter_config.exists() {
+            return Ok(None);
+        }
+        let chapter_config = fs_err::read_to_string(&chapter_config).context(format!(
+            "Failed to read the configuration for the chapter `{}`",
+            self.chapter()
+        ))?;
+        let chapter_config: toml::Table = toml::from_str(&chapter_config).with_context(|| {
+            format!(
+                "Failed to parse the configuration for the chapter `{}`",
+                self.chapter()
+            )
+        })?;
+        if chapter_config.contains_key("id") {
+            bail!(
+                "The configuration for the chapter `{}` can't specify an `id`: \
+                it must be unique to each exercise",
+                self.chapter()
+            );
+        }
+        Ok(Some(chapter_config))
+    }
+
+    /// The number+name of the chapter that contains this exercise, i.e. the name of its folder.
     pub fn chapter(&self) -> String {
-        format!("{:02}_{}", self.chapter_number, self.chapter_name)
+        self.chapter_dir_name.clone()
     }
 
-    /// The number+name of this exercise.
+    /// The number+name of this exercise, i.e. the name of its folder.
     pub fn exercise(&self) -> String {
-        format!("{:02}_{}", self.number, self.name)
+        self.dir_name.clone()
+    }
+
+    /// The name of the folder for another exercise of the same chapter, following the naming
+    /// scheme of this one: e.g. `03_traits`, for number 3 and name `traits`, next to `02_generics`.
+    ///
+    /// `naming` must be the naming scheme the exercise was parsed with.
+    pub fn sibling_dir_name(&self, number: u16, name: &str, naming: &NamingPattern) -> String {
+        let captures = naming
+            .0
+            .captures(&self.dir_name)
+            .expect("The exercise folder matched the naming pattern when it was parsed");
+        let number_match = captures.name("number").unwrap();
+        let name_match = captures.name("name").unwrap();
+        let width = number_match.as_str().len();
+        let mut dir_name = self.dir_name.clone();
+        // Replace the later match first, so that the position of the earlier one doesn't change.
+        let mut replacements = [
+            (number_match.range(), format!("{number:0width$}")),
+            (name_match.range(), name.to_owned()),
+        ];
+        replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, replacement) in replacements {
+            dir_name.replace_range(range, &replacement);
+        }
+        dir_name
+    }
+
+    /// The location of this exercise within the exercises directory, e.g. `01_intro/00_welcome`,
+    /// as used to refer to other exercises in [`ExerciseConfig::requires`].
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.chapter_dir_name, self.dir_name)
+    }
+
+    /// The name of this exercise, without its number.
+    pub fn exercise_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name of the chapter that contains this exercise, without its number.
+    pub fn chapter_name(&self) -> &str {
+        &self.chapter_name
     }
 
     /// The number of this exercise.
@@ -428,3 +2928,193 @@ impl std::fmt::Display for ExerciseDefinition {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `files`, as `(relative path, contents)` pairs, in a temporary directory.
+    fn write_files(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            fs_err::create_dir_all(path.parent().unwrap()).unwrap();
+            fs_err::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn later_layers_take_precedence() {
+        let dir = write_files(&[
+            (
+                "base.toml",
+                "timeout_secs = 10\nclippy = true\n[defaults]\nkeep_going = true\njobs = 2",
+            ),
+            ("extra.toml", "timeout_secs = 20\n[defaults]\njobs = 4"),
+            (
+                ".wr.toml",
+                r#"include = ["base.toml", "extra.toml"]
+clippy = false"#,
+            ),
+        ]);
+        let config = read_layered_config(&dir.path().join(".wr.toml")).unwrap();
+        let expected: toml::Table = toml::from_str(
+            "timeout_secs = 20\nclippy = false\n[defaults]\nkeep_going = true\njobs = 4",
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn includes_are_relative_to_the_including_file() {
+        let dir = write_files(&[
+            (".wr.toml", r#"include = ["shared/base.toml"]"#),
+            ("shared/base.toml", r#"include = ["nested.toml"]"#),
+            ("shared/nested.toml", "prompt = false"),
+        ]);
+        let config = read_layered_config(&dir.path().join(".wr.toml")).unwrap();
+        assert_eq!(config.get("prompt"), Some(&toml::Value::Boolean(false)));
+        assert!(!config.contains_key("include"));
+    }
+
+    #[test]
+    fn a_file_can_be_included_twice_without_a_cycle() {
+        let dir = write_files(&[
+            (".wr.toml", r#"include = ["a.toml", "b.toml"]"#),
+            ("a.toml", r#"include = ["common.toml"]"#),
+            ("b.toml", r#"include = ["common.toml"]"#),
+            ("common.toml", "clippy = true"),
+        ]);
+        let config = read_layered_config(&dir.path().join(".wr.toml")).unwrap();
+        assert_eq!(config.get("clippy"), Some(&toml::Value::Boolean(true)));
+    }
+
+    #[test]
+    fn self_includes_are_rejected() {
+        let dir = write_files(&[(".wr.toml", r#"include = [".wr.toml"]"#)]);
+        let error = read_layered_config(&dir.path().join(".wr.toml")).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("includes itself"),
+            "{error:#}"
+        );
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let dir = write_files(&[
+            (".wr.toml", r#"include = ["a.toml"]"#),
+            ("a.toml", r#"include = ["b.toml"]"#),
+            ("b.toml", r#"include = ["a.toml"]"#),
+        ]);
+        let error = read_layered_config(&dir.path().join(".wr.toml")).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("includes itself"),
+            "{error:#}"
+        );
+    }
+
+    #[test]
+    fn merging_replaces_everything_but_tables() {
+        let mut base: toml::Table =
+            toml::from_str("sandbox_args = [\"a\"]\n[messages]\nfailure = \"x\"\nopened = \"y\"")
+                .unwrap();
+        let overrides: toml::Table =
+            toml::from_str("sandbox_args = [\"b\"]\n[messages]\nfailure = \"z\"").unwrap();
+        merge_config(&mut base, overrides);
+        let expected: toml::Table =
+            toml::from_str("sandbox_args = [\"b\"]\n[messages]\nfailure = \"z\"\nopened = \"y\"")
+                .unwrap();
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn env_overrides_are_parsed_according_to_the_setting() {
+        // The only test that sets these variables, so it can't race with the others.
+        let vars = [
+            ("WR_TIMEOUT_SECS", "30"),
+            ("WR_CLIPPY", "not toml"),
+            ("WR_SOLUTIONS_DIR", "true"),
+            ("WR_EXERCISES_DIR", r#"["a", "b"]"#),
+            ("WR_SANDBOX_IMAGE", "[not a list"),
+        ];
+        for (name, value) in vars {
+            std::env::set_var(name, value);
+        }
+        let mut config: toml::Table = toml::from_str("timeout_secs = 10\nprompt = false").unwrap();
+        apply_env_overrides(&mut config);
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+
+        assert_eq!(config["timeout_secs"], toml::Value::Integer(30));
+        // Left for deserialization to report.
+        assert_eq!(config["clippy"], toml::Value::String("not toml".into()));
+        // Paths are never parsed, even when they look like TOML.
+        assert_eq!(config["solutions_dir"], toml::Value::String("true".into()));
+        assert_eq!(
+            config["exercises_dir"],
+            toml::Value::Array(vec!["a".into(), "b".into()])
+        );
+        assert_eq!(
+            config["sandbox_image"],
+            toml::Value::String("[not a list".into())
+        );
+        // Settings without a variable are left untouched.
+        assert_eq!(config["prompt"], toml::Value::Boolean(false));
+    }
+
+    fn progress(runs: usize, solved: bool) -> ExerciseProgress {
+        ExerciseProgress {
+            runs,
+            solved,
+            ..ExerciseProgress::new("01_intro".into(), "00_welcome".into())
+        }
+    }
+
+    #[test]
+    fn merging_progress_keeps_the_most_recent_record() {
+        let ours = ExerciseProgress {
+            failed: true,
+            opened_at: Some(100),
+            hints_used: 2,
+            run_time_ms: 500,
+            source_hash: Some("local".into()),
+            last_failure: Some(LastFailure {
+                stage: "tests".into(),
+                command: "cargo test".into(),
+                output: "boom".into(),
+            }),
+            ..progress(3, false)
+        };
+        let theirs = ExerciseProgress {
+            opened_at: Some(200),
+            solved_at: Some(300),
+            hints_used: 1,
+            run_time_ms: 800,
+            ..progress(5, true)
+        };
+        let expected = ExerciseProgress {
+            opened_at: Some(100),
+            solved_at: Some(300),
+            hints_used: 2,
+            run_time_ms: 800,
+            source_hash: Some("local".into()),
+            ..progress(5, true)
+        };
+        assert_eq!(ours.merge(&theirs), expected);
+        assert_eq!(theirs.merge(&ours), expected);
+    }
+
+    #[test]
+    fn merging_progress_prefers_solved_on_a_tie() {
+        let solved = progress(2, true);
+        let unsolved = ExerciseProgress {
+            failed: true,
+            ..progress(2, false)
+        };
+        assert!(solved.merge(&unsolved).solved);
+        assert!(unsolved.merge(&solved).solved);
+        assert!(!unsolved.merge(&solved).failed);
+    }
+}
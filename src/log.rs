@@ -0,0 +1,105 @@
+//! A log of every run of `wr`, for instructors to retrace what happened on a learner's machine
+//! when the report is "it worked yesterday". See `log_file` in `.wr.toml` and `--log`.
+//!
+//! The log is only ever appended to. Each line is a JSON object, with the time of the event
+//! (as a Unix timestamp, in milliseconds) and its kind, e.g.
+//!
+//! ```json
+//! {"timestamp_ms":1760000000000,"event":"verification","exercise":"01_intro/00_welcome","passed":true,"duration_ms":1234}
+//! ```
+use anyhow::Context;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Something `wr` did.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// `wr` was started.
+    Invocation {
+        /// The arguments `wr` was run with, without the name of the binary.
+        args: Vec<String>,
+        cwd: Option<&'a Path>,
+        version: &'static str,
+    },
+    /// A verification command was run.
+    Command {
+        /// The exercise being verified, as `<chapter>/<exercise>`.
+        exercise: &'a str,
+        stage: &'a str,
+        command: &'a str,
+        passed: bool,
+        duration_ms: u128,
+    },
+    /// An exercise was verified.
+    Verification {
+        exercise: &'a str,
+        passed: bool,
+        /// The stage that failed, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        failed_stage: Option<&'a str>,
+        duration_ms: u128,
+    },
+    /// An exercise was opened.
+    Opened { exercise: &'a str },
+    /// An exercise was skipped.
+    Skipped { exercise: &'a str },
+    /// `wr` exited, with `code` as its exit status.
+    Exit {
+        code: i32,
+        /// Why `wr` failed, if it did because of an error.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    },
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    timestamp_ms: u128,
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+}
+
+/// A log file, opened for appending.
+pub struct Log {
+    file: Mutex<fs_err::File>,
+}
+
+impl Log {
+    /// Open the log at `path`, creating it (and its parent directories) if it doesn't exist.
+    pub fn open(path: &Path) -> Result<Self, anyhow::Error> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs_err::create_dir_all(parent)
+                .with_context(|| format!("Failed to open the log file at {path:?}"))?;
+        }
+        let file = fs_err::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open the log file at {path:?}"))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append `event` to the log, as a single line.
+    pub fn record(&self, event: &Event) -> Result<(), anyhow::Error> {
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let mut line = serde_json::to_string(&Entry {
+            timestamp_ms,
+            event,
+        })?;
+        line.push('\n');
+        // A single write, so that concurrent runs of `wr` don't interleave their lines.
+        self.file
+            .lock()
+            .unwrap()
+            .write_all(line.as_bytes())
+            .context("Failed to write to the log file")
+    }
+}
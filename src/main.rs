@@ -1,11 +1,320 @@
-use clap::{Parser, Subcommand};
+use anyhow::Context;
+use clap::builder::BoolishValueParser;
+use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use clap_complete::env::Shells;
+use clap_complete::CompleteEnv;
 use fs_err::PathExt;
 use read_input::prelude::*;
 use std::ffi::OsString;
+use std::io::IsTerminal;
 use std::path::Path;
-use wr::{ExerciseCollection, ExerciseDefinition, ExercisesConfig, OpenedExercise, Verification};
+use std::sync::{Mutex, OnceLock};
+use wr::{
+    format_duration, format_run_time, ExerciseCollection, ExerciseConfig, ExerciseDefinition,
+    ExerciseStatus, ExercisesConfig, Expect, LastFailure, Messages, OpenedExercise, TestRunner,
+    Verification,
+};
 use yansi::Paint;
 
+/// How `wr` reports on what it did, see `--output`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Text meant for humans, with colours.
+    Text,
+    /// Structured JSON, meant for other programs.
+    Json,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// `true` if `wr` should print JSON, rather than text, on stdout, see `--output`.
+fn json_output() -> bool {
+    OUTPUT_FORMAT.get() == Some(&OutputFormat::Json)
+}
+
+/// How much `wr` prints, see `--quiet` and `--verbose`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    /// Build logs are shown.
+    Verbose,
+    /// The commands being run are shown too.
+    Trace,
+}
+
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// When `wr` (and `cargo`) should colour their output, see `--color`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Colour the output if it goes to a terminal, unless `NO_COLOR` is set.
+    /// `CLICOLOR_FORCE` forces colours even if it doesn't.
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR: OnceLock<ColorChoice> = OnceLock::new();
+
+static STREAM_OUTPUT: OnceLock<bool> = OnceLock::new();
+
+static ACCESSIBLE: OnceLock<bool> = OnceLock::new();
+
+/// `true` if the output should be friendly to screen readers, see `--accessible`.
+fn accessible() -> bool {
+    ACCESSIBLE.get().copied().unwrap_or(false)
+}
+
+/// How outcomes and statuses are marked: with an emoji or, with `--accessible`, with a word
+/// that screen readers can read out.
+#[derive(Clone, Copy)]
+enum Marker {
+    Pass,
+    Fail,
+    Skipped,
+    /// A solved exercise that wasn't verified again.
+    Unchanged,
+    Opened,
+    Locked,
+    Warning,
+    Bookmark,
+    /// Something that was deleted, e.g. by `wr clean`.
+    Removed,
+    Note,
+    Retry,
+    Ok,
+    /// The stages of a verification.
+    StagePassed,
+    StageFailed,
+    StageSkipped,
+}
+
+impl std::fmt::Display for Marker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (emoji, word) = match self {
+            Marker::Pass => ("🚀", "PASS"),
+            Marker::Fail => ("❌", "FAIL"),
+            Marker::Skipped => ("⏭ ", "SKIPPED"),
+            Marker::Unchanged => ("⏩", "UNCHANGED"),
+            Marker::Opened => ("📖", "OPENED"),
+            Marker::Locked => ("🔒", "LOCKED"),
+            Marker::Warning => ("⚠️ ", "WARNING:"),
+            Marker::Bookmark => ("🔖", "BOOKMARKED"),
+            Marker::Removed => ("🗑 ", "REMOVED"),
+            Marker::Note => ("📝", "NOTE:"),
+            Marker::Retry => ("↻", "RETRY"),
+            Marker::Ok => ("✅", "OK"),
+            Marker::StagePassed => ("✔", "PASS"),
+            Marker::StageFailed => ("✘", "FAIL"),
+            Marker::StageSkipped => ("-", "SKIPPED"),
+        };
+        f.write_str(if accessible() { word } else { emoji })
+    }
+}
+
+impl From<ExerciseStatus> for Marker {
+    fn from(status: ExerciseStatus) -> Self {
+        match status {
+            ExerciseStatus::Solved => Marker::Pass,
+            ExerciseStatus::Failed => Marker::Fail,
+            ExerciseStatus::Skipped => Marker::Skipped,
+            ExerciseStatus::Opened => Marker::Opened,
+            ExerciseStatus::Unopened => Marker::Locked,
+        }
+    }
+}
+
+/// `message` without its emoji, with `--accessible`: screen readers would spell them out.
+fn without_emoji(message: &str) -> String {
+    if !accessible() {
+        return message.to_owned();
+    }
+    message
+        .chars()
+        .filter(|c| !matches!(*c as u32, 0x2600..=0x27BF | 0x1F000..=0x1FAFF | 0xFE0F))
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+/// `true` if the output of verification commands should be shown as it comes, see `--stream`.
+fn stream_output() -> bool {
+    STREAM_OUTPUT.get().copied().unwrap_or(false)
+}
+
+fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or(Verbosity::Normal)
+}
+
+/// `true` if `wr` should print what it's doing, on top of the outcome:
+/// not with `--quiet`, nor with `--output json`.
+fn show_progress() -> bool {
+    !json_output() && verbosity() > Verbosity::Quiet
+}
+
+/// `true` if the command can report on what it did as JSON, see `--output`.
+fn supports_json(command: &Command) -> bool {
+    !command.dry_run
+        && matches!(
+            command.command,
+            None | Some(
+                Commands::Check { .. }
+                    | Commands::Grade { .. }
+                    | Commands::List { .. }
+                    | Commands::Status
+                    | Commands::Stats
+                    | Commands::History { .. }
+                    | Commands::Config { .. }
+            )
+        )
+}
+
+/// How many times `--verbose` was passed according to `WR_VERBOSE`, if it's set: a number,
+/// or a boolean (e.g. `true` or `yes`) for once or never.
+///
+/// It can't be read by `clap`, which only takes booleans for flags that can be repeated.
+fn verbose_from_env() -> Option<u8> {
+    let value = std::env::var("WR_VERBOSE").ok()?;
+    let value = value.trim().to_lowercase();
+    if let Ok(level) = value.parse() {
+        return Some(level);
+    }
+    match value.as_str() {
+        "y" | "yes" | "t" | "true" | "on" => Some(1),
+        "" | "n" | "no" | "f" | "false" | "off" => Some(0),
+        _ => None,
+    }
+}
+
+/// Print `value` on stdout, as JSON.
+fn print_json(value: &impl serde::Serialize) -> Result<(), anyhow::Error> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// A report on the verifications run by `wr`, see `--report`.
+#[derive(Clone)]
+pub struct Report {
+    pub format: ReportFormat,
+    pub path: std::path::PathBuf,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// JUnit XML, with a test case for each exercise.
+    Junit,
+}
+
+/// Parse `--report`, e.g. `junit=report.xml`.
+fn parse_report(value: &str) -> Result<Report, String> {
+    let Some((format, path)) = value.split_once('=') else {
+        return Err("expected `<format>=<path>`, e.g. `junit=report.xml`".into());
+    };
+    if path.is_empty() {
+        return Err("the path of the report can't be empty".into());
+    }
+    Ok(Report {
+        format: ReportFormat::from_str(format, true)?,
+        path: path.into(),
+    })
+}
+
+/// `true` if the command verifies exercises, and can therefore report on them, see `--report`.
+fn supports_report(command: &Command) -> bool {
+    !command.dry_run
+        && matches!(
+            command.command,
+            None | Some(Commands::Check { .. } | Commands::Grade { .. })
+        )
+}
+
+/// Where the JUnit report should be written, see `--report`.
+static JUNIT_REPORT: OnceLock<std::path::PathBuf> = OnceLock::new();
+/// The verifications included in the JUnit report so far.
+static JUNIT_CASES: Mutex<Vec<wr::junit::TestCase>> = Mutex::new(Vec::new());
+
+/// Add a verification to the JUnit report, if one was asked for.
+fn record_in_report(
+    definition: &ExerciseDefinition,
+    duration: std::time::Duration,
+    outcome: &TestOutcome,
+) -> Result<(), anyhow::Error> {
+    let Some(path) = JUNIT_REPORT.get() else {
+        return Ok(());
+    };
+    let mut cases = JUNIT_CASES.lock().unwrap();
+    cases.push(wr::junit::TestCase {
+        definition: definition.clone(),
+        duration,
+        failure: outcome.failure(),
+    });
+    // The report is written again after every verification: `wr` exits as soon as
+    // an exercise fails, without going back to `main`.
+    wr::junit::write(path, &cases)
+}
+
+/// Where every run of `wr` is logged, see `--log`.
+static LOG: OnceLock<wr::log::Log> = OnceLock::new();
+
+/// Start logging to `path`, beginning with how `wr` was invoked.
+///
+/// If the log can't be opened, `wr` carries on without it, after a warning.
+fn open_log(path: &Path) {
+    match wr::log::Log::open(path) {
+        Ok(log) => {
+            let _ = LOG.set(log);
+        }
+        Err(e) => {
+            eprintln!(
+                "{}",
+                failure_style().paint(format!("\n\t{e:#}. `wr` will carry on without a log."))
+            );
+            return;
+        }
+    }
+    let cwd = std::env::current_dir().ok();
+    log(&wr::log::Event::Invocation {
+        args: std::env::args().skip(1).collect(),
+        cwd: cwd.as_deref(),
+        version: env!("CARGO_PKG_VERSION"),
+    });
+}
+
+/// Record in the log that `exercise` has been opened.
+fn log_opened(exercise: &ExerciseDefinition) {
+    log(&wr::log::Event::Opened {
+        exercise: &exercise.path(),
+    });
+}
+
+/// Record `event` in the log, if there is one.
+///
+/// Logging is best effort: a log that can't be written to shouldn't get in the way of learners.
+fn log(event: &wr::log::Event) {
+    if let Some(log) = LOG.get() {
+        let _ = log.record(event);
+    }
+}
+
+/// How many lines of the output of a failed command are shown, see [`print_failure_message`].
+static FAILURE_LINES: OnceLock<usize> = OnceLock::new();
+
+/// The messages of the current collection, see [`messages`].
+static MESSAGES: OnceLock<Messages> = OnceLock::new();
+
+/// The messages shown to learners: the collection's own, once its configuration has been loaded,
+/// or the default ones.
+fn messages() -> &'static Messages {
+    MESSAGES.get_or_init(Messages::default)
+}
+
+/// The other messages shown to learners, in the collection's locale, see [`wr::i18n`].
+fn catalog() -> &'static wr::i18n::Catalog {
+    messages().catalog()
+}
+
 /// A small CLI to manage test-driven workshops and tutorials in Rust.
 ///
 /// Each exercise comes with a set of associated tests.
@@ -17,24 +326,102 @@ use yansi::Paint;
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Command {
-    #[arg(long)]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new())]
     /// Compile and run tests for all opened exercises, even if they have already succeeded
     /// in a past run.
-    pub recheck: bool,
+    pub recheck: Option<bool>,
 
     #[arg(long)]
+    /// With `--recheck`, `wr` doesn't verify again the exercises whose sources haven't changed
+    /// since they were solved.
+    /// With this flag, they are verified regardless.
+    pub force: bool,
+
+    #[arg(long, short, global = true, action = ArgAction::Count)]
     /// By default, `wr` will run `cargo build` in quiet mode and it won't show you the logs
     /// coming from the build process.
     /// With this flag, those logs (and the progress bar) will be displayed.
-    pub verbose: bool,
+    /// Pass it twice (`-vv`) to also see the exact commands `wr` runs, with their environment.
+    /// `WR_VERBOSE=1` (or `true`) is the same as `-v`, `WR_VERBOSE=2` as `-vv`.
+    pub verbose: u8,
 
-    #[arg(long)]
+    #[arg(long, global = true, env = "WR_STREAM", value_parser = BoolishValueParser::new())]
+    /// Show the output of the verification commands as it's produced, e.g. for long-running
+    /// exercises, rather than only when they fail.
+    /// It's captured all the same, to be shown again if verification fails.
+    pub stream: bool,
+
+    #[arg(long, short, global = true)]
+    /// Only print the outcome, e.g. for scripts: which exercises failed, if any, and what
+    /// comes next.
+    /// The exercises being verified, the build logs and the output of failed commands
+    /// are left out. It takes precedence over `--verbose`.
+    pub quiet: bool,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new())]
     /// By default, `wr` will prompt you to open the next exercise if all the currently opened
     /// exercises passed their tests.  
     /// With this flag, `wr` will automatically open the next exercise if all the currently opened
     /// exercises passed their tests. It'll then run the tests for the newly opened exercise.
     /// If they pass, it'll open the next one, and so on.
-    pub keep_going: bool,
+    pub keep_going: Option<bool>,
+
+    #[arg(long, short)]
+    /// Open the next exercise, if all the currently opened exercises passed their tests,
+    /// without asking first.
+    pub yes: bool,
+
+    #[arg(long)]
+    /// By default, `wr` stops at the first exercise that fails verification.
+    /// With this flag, `wr` verifies every opened exercise and then prints a summary of all
+    /// the failures.
+    pub no_fail_fast: bool,
+
+    #[arg(long, short, global = true)]
+    /// The number of parallel jobs `cargo` uses to build exercises, instead of the number
+    /// of CPUs.
+    pub jobs: Option<u32>,
+
+    #[arg(long, global = true, env = "WR_PROFILE")]
+    /// Track progress separately for each profile, e.g. when several people share the same
+    /// checkout of the workshop.
+    pub profile: Option<String>,
+
+    #[arg(long, global = true, env = "WR_TRACK")]
+    /// Follow one of the tracks defined by the collection, e.g. `beginner`: only its exercises
+    /// are opened, listed and counted towards your progress.
+    pub track: Option<String>,
+
+    #[arg(long, global = true)]
+    /// Print which exercises would be verified, in which order, and which commands would be run
+    /// to verify them, without actually running anything.
+    pub dry_run: bool,
+
+    #[arg(long, global = true, env = "WR_ACCESSIBLE", value_parser = BoolishValueParser::new())]
+    /// Print plain words (PASS, FAIL, SKIPPED, ...) instead of emoji and progress bars,
+    /// one result per line, for screen readers.
+    pub accessible: bool,
+
+    #[arg(long, global = true, value_name = "PATH")]
+    /// Append a log of what `wr` does (the commands it runs and their outcome) to this file,
+    /// instead of the one set by `log_file` in `.wr.toml`, if any.
+    pub log: Option<std::path::PathBuf>,
+
+    #[arg(long, global = true, value_enum, env = "WR_COLOR", default_value_t = ColorChoice::Auto)]
+    /// When to colour the output of `wr` and of the commands it runs.
+    pub color: ColorChoice,
+
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    /// Print structured JSON on stdout rather than text, e.g. for editor plugins and CI pipelines.
+    /// It's supported by `wr` itself, `wr check`, `wr grade`, `wr list`, `wr status`, `wr stats`,
+    /// `wr history` and `wr config validate`.
+    pub output: OutputFormat,
+
+    #[arg(long, global = true, value_name = "FORMAT=PATH", value_parser = parse_report)]
+    /// Write the outcome of every verification to a file, e.g. `--report junit=report.xml`
+    /// for a JUnit XML report that CI systems can display.
+    /// It's supported by `wr` itself, `wr check` and `wr grade`.
+    pub report: Option<Report>,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -42,357 +429,4856 @@ pub struct Command {
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Create a new collection of exercises in the current directory.
+    ///
+    /// It creates a `.wr.toml` configuration file and an `exercises` directory with
+    /// a first exercise to get you started.
+    Init,
     /// Open a specific exercise.
     ///
     /// You can either provide the full name of the chapter and exercise, or only their number.
     ///
     /// E.g. `wr open --chapter 01_structured_logging --exercise 00_intro` will open
     /// the exercise located at `01_structured_logging/00_intro`.
-    /// The same exercise can be opened with `wr open --chapter 1 --exercise 0`.
+    /// The same exercise can be opened with `wr open --chapter 1 --exercise 0`,
+    /// `wr open 1/0` or `wr open 1 0`.
+    ///
+    /// Names can be abbreviated, as long as they are unambiguous: e.g. `wr open logging/intro`.
+    /// Without arguments, you can pick the exercise from a searchable list.
     Open {
-        /// The name of the chapter containing the exercise, or its number.
-        ///
-        /// E.g. `--chapter 01_structured_logging` and `--chapter 1` are equivalent.
+        #[command(flatten)]
+        exercise: ExerciseArgs,
+        /// Open the exercise even if the exercises it requires haven't been solved yet.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Close an exercise you opened, e.g. by mistake.
+    ///
+    /// `wr` forgets about your progress on the exercise: it'll be opened again once you get to it.
+    /// The exercise can be specified in the same way as for `wr open`.
+    Close {
+        #[command(flatten)]
+        exercise: ExerciseArgs,
+    },
+    /// Verify every exercise in the collection and compute a score.
+    ///
+    /// The score is the percentage of points earned over the total, where each exercise
+    /// is worth as many points as its `weight` (1, unless specified in its configuration).
+    /// A machine-readable report is written to disk, in JSON format.
+    Grade {
+        /// Where the grade report should be written.
+        #[arg(long, value_name = "PATH", default_value = "grade.json")]
+        report_path: std::path::PathBuf,
+        /// Exit with a non-zero status code if the score is below this percentage.
+        #[arg(long)]
+        fail_under: Option<f64>,
+    },
+    /// Print the script to enable shell completions for `wr`.
+    ///
+    /// Completions are dynamic: chapter and exercise names are read from the collection
+    /// in the current directory.
+    /// E.g. for `bash`, add `source <(wr completions bash)` to your `~/.bashrc`.
+    Completions {
+        /// The shell you want completions for.
+        #[arg(value_parser = ["bash", "elvish", "fish", "powershell", "zsh"])]
+        shell: String,
+    },
+    /// Download and compile the dependencies of every exercise ahead of time, e.g. before
+    /// a workshop in a venue with unreliable Wi-Fi.
+    ///
+    /// Exercises that haven't been solved yet may not compile: their dependencies are ready all
+    /// the same.
+    Warm {
+        /// Only download the dependencies, without compiling anything.
         #[arg(long)]
+        fetch_only: bool,
+    },
+    /// Check that your environment is set up correctly to work on the collection.
+    ///
+    /// It checks that the required tools are installed, that the configuration files
+    /// are valid, and that your progress is consistent with the exercises on disk.
+    Doctor,
+    /// Inspect the configuration of the collection.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Inspect the database where your progress is tracked.
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Create a new exercise at the end of a chapter.
+    ///
+    /// E.g. `wr new-exercise --chapter 3 --name lifetimes` will create `03_<chapter name>/<NN>_lifetimes`,
+    /// where `NN` is the number following the last exercise in the chapter.
+    /// To create a new chapter, pass its full name (e.g. `--chapter 04_async`).
+    NewExercise {
+        /// The name of the chapter that should contain the exercise, or its number.
+        #[arg(long, add = ArgValueCandidates::new(chapter_candidates))]
         chapter: String,
+        /// The name of the exercise, without its number.
+        #[arg(long)]
+        name: String,
+    },
+    /// Run the tests for an exercise, by default the one in the current directory.
+    /// It errors if the current directory is not an exercise.
+    Check {
+        /// Run the tests for this exercise instead, specified either as a path
+        /// (e.g. `exercises/02_basics/10_casting`) or as `<chapter>/<exercise>` (e.g. `2/10`).
+        #[arg(conflicts_with = "all", add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+        /// Run the tests for every exercise in the collection, whether you opened it or not.
+        ///
+        /// It exits with a non-zero status code if any exercise fails verification:
+        /// use it to check that a workshop (e.g. its reference solutions) is healthy.
+        #[arg(long)]
+        all: bool,
+        /// Run the tests for every exercise with this tag (e.g. `async`), whether you opened it or not.
+        /// It can be repeated to check the exercises that have any of the tags.
+        #[arg(long, conflicts_with_all = ["exercise", "all"])]
+        tag: Vec<String>,
+    },
+    /// List all the exercises in the collection, grouped by chapter, alongside their status.
+    ///
+    /// It doesn't compile or test anything: it only shows what `wr` already knows about
+    /// your progress.
+    List {
+        /// Only list the exercises with this tag, e.g. `traits`.
+        /// It can be repeated to list the exercises that have any of the tags.
+        #[arg(long)]
+        tag: Vec<String>,
+    },
+    /// Browse the collection in an interactive terminal UI.
+    ///
+    /// Exercises are listed on the left, grouped by chapter. The README and the last failure
+    /// of the selected exercise are shown on the right. Press `o` to open it, `c` to check it
+    /// and `s` to skip it.
+    Tui,
+    /// Start a local web server showing your progress on the collection.
+    ///
+    /// The page lets you re-run the tests for the exercises you have opened and shows
+    /// the output of the ones that failed.
+    Serve {
+        /// The port the server should listen on.
+        #[arg(long, default_value_t = 7878)]
+        port: u16,
+    },
+    /// Show how much time you spent on each exercise and chapter.
+    ///
+    /// For each exercise, it shows how long it took to solve it (from the moment it was opened)
+    /// and how much time was spent compiling and running its tests.
+    Stats,
+    /// Write a report on your progress, in Markdown: a table for each chapter with the exercises
+    /// you opened, their status, how long they took and how many attempts you made.
+    ///
+    /// Attach it when asking for help, or hand it in at the end of the workshop.
+    Report {
+        /// Write an HTML page rather than Markdown.
+        #[arg(long)]
+        html: bool,
+        /// Where the report should be written. It's printed on stdout if omitted.
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Draw a badge with the percentage of exercises you solved, as an SVG image,
+    /// e.g. to show off your progress in the README of your fork.
+    Badge {
+        /// Where the badge should be written.
+        #[arg(long, default_value = "progress.svg")]
+        out: std::path::PathBuf,
+        /// The text on the left-hand side of the badge.
+        #[arg(long, default_value = "progress")]
+        label: String,
+    },
+    /// Print a short summary of your progress.
+    ///
+    /// It doesn't compile or test anything: it only shows what `wr` already knows about
+    /// your progress.
+    Status,
+    /// Watch the exercise you are currently working on and re-run its tests every time
+    /// one of its files changes.
+    ///
+    /// If you open another exercise while `wr watch` is running, it'll start watching
+    /// the newly opened exercise.
+    Watch,
+    /// Skip an exercise, without solving it.
+    ///
+    /// Skipped exercises won't be verified when you run `wr`, so you can move on to the
+    /// next exercise. You can still verify a skipped exercise with `wr check`: it'll be
+    /// marked as solved if its tests pass.
+    ///
+    /// By default, it skips the exercise you are currently working on.
+    Skip {
+        /// The name of the chapter containing the exercise, or its number.
+        #[arg(long, requires = "exercise", add = ArgValueCandidates::new(chapter_candidates))]
+        chapter: Option<String>,
         /// The name of the exercise, or its number within the chapter it belongs to.
+        #[arg(long, requires = "chapter", add = ArgValueCandidates::new(exercise_candidates))]
+        exercise: Option<String>,
+    },
+    /// Describe the exercise you are currently working on.
+    Info {
+        /// How many lines of the exercise README should be shown.
+        #[arg(long, default_value_t = 10)]
+        lines: usize,
+    },
+    /// Save your progress to a JSON file, e.g. to move it to another machine with `wr import`.
+    Export {
+        /// Where your progress should be saved.
+        #[arg(default_value = "progress.json")]
+        path: std::path::PathBuf,
+    },
+    /// Restore your progress from a JSON file created by `wr export`.
+    ///
+    /// The progress recorded in the file overwrites the one you have recorded locally for the
+    /// same exercises. All other exercises are left untouched.
+    ///
+    /// With `--format rustlings`, the exercises completed in rustlings are marked as solved instead,
+    /// matching them by name.
+    Import {
+        /// The file created by `wr export`, or the `.rustlings-state.txt` file of a rustlings checkout.
+        path: std::path::PathBuf,
+        /// The format of the file.
+        #[arg(long, value_enum, default_value_t = ImportFormat::Wr)]
+        format: ImportFormat,
+    },
+    /// Sync your progress with a remote endpoint, to pick up where you left off on another machine.
+    ///
+    /// Progress is pulled from the endpoint, merged with the one recorded locally, then pushed back.
+    /// Bookmarks, notes and the history of your attempts are not synced.
+    Sync {
+        /// The endpoint to sync with. It defaults to the `sync_url` of the workshop configuration.
+        #[arg(long, env = "WR_SYNC_URL")]
+        url: Option<String>,
+        /// The token used to authenticate with the endpoint.
+        #[arg(long, env = "WR_SYNC_TOKEN", hide_env_values = true)]
+        token: String,
+    },
+    /// Save a copy of your progress, attempts, bookmarks and notes, to restore them later
+    /// with `wr restore-backup`.
+    ///
+    /// Backups are also taken automatically before `wr reset`, `wr prune`, `wr import`
+    /// and `wr restore-backup`.
+    Backup {
+        /// Where the copy should be saved.
+        /// It defaults to a timestamped file in the `progress-backups` folder, next to your progress.
+        path: Option<std::path::PathBuf>,
+    },
+    /// Replace your progress, attempts, bookmarks and notes with a copy saved by `wr backup`.
+    ///
+    /// What you have recorded so far is backed up first, in case you change your mind.
+    RestoreBackup {
+        /// The copy saved by `wr backup`.
+        path: std::path::PathBuf,
+    },
+    /// Forget about exercises that you opened in the past, but can no longer be found.
+    ///
+    /// It usually happens when the workshop is updated and some exercises are removed
+    /// or renamed.
+    Prune,
+    /// Show the changes you made to an exercise, compared to its starting state in `git`.
+    ///
+    /// It defaults to the exercise you are currently working on.
+    Diff {
+        /// The exercise, specified as `<chapter>/<exercise>`, by name or number.
+        #[arg(add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// Revert an exercise to its starting state in `git`, discarding your changes.
+    ///
+    /// It defaults to the exercise you are currently working on.
+    /// You'll be asked for confirmation first.
+    Restore {
+        /// The exercise, specified as `<chapter>/<exercise>`, by name or number.
+        #[arg(add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// Bookmark exercises that you want to come back to later.
+    ///
+    /// Without a subcommand, it lists your bookmarks.
+    #[command(visible_alias = "bookmarks")]
+    Bookmark {
+        #[command(subcommand)]
+        command: Option<BookmarkCommand>,
+    },
+    /// Take notes on exercises, e.g. to build a personal study summary by the end of the workshop.
+    ///
+    /// Without a subcommand, it lists your notes.
+    #[command(visible_alias = "notes")]
+    Note {
+        #[command(subcommand)]
+        command: Option<NoteCommand>,
+    },
+    /// Run the binary of the exercise you are currently working on, via `cargo run`.
+    ///
+    /// Arguments after `--` are forwarded to the binary, e.g. `wr run -- --name Ferris`.
+    Run {
+        /// The arguments to pass to the binary.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Read the README of the exercise you are currently working on, rendered in the terminal.
+    Readme {
+        /// Read the README of this exercise instead, specified as `<chapter>/<exercise>`.
+        #[arg(add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// Show again why the last verification of an exercise failed, e.g. after closing the
+    /// terminal where it ran.
+    LastFailure {
+        /// Show the last failure of this exercise instead of the one you are currently working on,
+        /// specified as `<chapter>/<exercise>`.
+        #[arg(add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+        /// Show the whole output of the failed command, rather than only its last lines.
+        #[arg(long)]
+        full: bool,
+    },
+    /// Show every attempt you made at verifying exercises: when, how long it took
+    /// and, if it failed, which command failed.
+    History {
+        /// Only show the attempts for this exercise, specified as `<chapter>/<exercise>`.
+        ///
+        /// Both components can be either a name or a number, e.g. `--exercise 2/10`.
+        #[arg(long, add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// Remove the build artifacts of all exercises, to reclaim disk space.
+    ///
+    /// Each exercise is built in its own `target` directory, which can grow quite large
+    /// over the course of a workshop.
+    Clean {
+        /// Only clean the exercises in this chapter, specified by name or number.
+        #[arg(long, add = ArgValueCandidates::new(chapter_candidates))]
+        chapter: Option<String>,
+    },
+    /// Reveal the next hint for the exercise you are currently working on.
+    ///
+    /// Hints are revealed one at a time: the ones you have already seen are shown again,
+    /// alongside the new one.
+    Hint,
+    /// Look at the reference solution for the exercise you are currently working on.
+    Solution {
+        #[command(subcommand)]
+        command: SolutionCommand,
+    },
+    /// Reset your progress.
+    ///
+    /// By default, it resets your progress for the whole collection: you'll be asked for
+    /// confirmation first.
+    /// Use `--chapter` or `--exercise` to reset a subset of the collection instead.
+    Reset {
+        /// Only reset the exercises in this chapter, specified by name or number.
+        ///
+        /// E.g. `--chapter 03_traits` and `--chapter 3` are equivalent.
+        #[arg(long, conflicts_with = "exercise", add = ArgValueCandidates::new(chapter_candidates))]
+        chapter: Option<String>,
+        /// Only reset this exercise, specified as `<chapter>/<exercise>`.
         ///
-        /// E.g. `--exercise 00_intro` and `--exercise 0` are equivalent.
+        /// Both components can be either a name or a number.
+        /// E.g. `--exercise 02_basics/10_casting` and `--exercise 2/10` are equivalent.
+        #[arg(long, add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+        /// Keep the exercises open, marking them as unsolved, rather than closing them.
         #[arg(long)]
-        exercise: String,
+        keep_open: bool,
+    },
+    /// Run a command with limited resources, see `memory_limit_mb` and `cpu_time_limit_secs`.
+    ///
+    /// It's how Cargo runs test binaries on our behalf: learners have no use for it.
+    #[command(name = "__limited", hide = true)]
+    Limited {
+        #[arg(long)]
+        memory_bytes: Option<u64>,
+        #[arg(long)]
+        cpu_time_secs: Option<u64>,
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<OsString>,
     },
-    /// Run the tests for the exercise in the current directory.
-    /// It errors if the current directory is not an exercise.
-    Check,
 }
 
-fn main() -> Result<(), anyhow::Error> {
-    let command = Command::parse();
-    // Enable ANSI colour support on Windows, if it's supported.
-    // Disable it entirely otherwise.
-    if !use_ansi_colours() {
-        Paint::disable();
+#[derive(Subcommand)]
+pub enum SolutionCommand {
+    /// Print the reference solution.
+    Show,
+    /// Copy the reference solution into the exercise folder, overwriting your changes.
+    ///
+    /// You'll be asked for confirmation first.
+    Apply,
+}
+
+#[derive(Subcommand)]
+pub enum BookmarkCommand {
+    /// Bookmark an exercise. It defaults to the exercise you are currently working on.
+    Add {
+        /// The exercise, specified as `<chapter>/<exercise>`, by name or number.
+        #[arg(add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// Remove the bookmark for an exercise. It defaults to the exercise you are currently working on.
+    Remove {
+        /// The exercise, specified as `<chapter>/<exercise>`, by name or number.
+        #[arg(add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// List the exercises you have bookmarked.
+    List,
+}
+
+/// The formats `wr import` understands.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    /// A JSON file created by `wr export`.
+    Wr,
+    /// The state file of rustlings (`.rustlings-state.txt`), version 6 or later.
+    Rustlings,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Check the `.wr.toml` files of the collection, its chapters and its exercises,
+    /// reporting unknown keys, values of the wrong type and invalid values, with their location.
+    Validate,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Check that your progress database is not corrupted and that every exercise it refers
+    /// to has a valid name.
+    Check {
+        /// Remove everything recorded for exercises with an invalid name, after backing up
+        /// your progress.
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NoteCommand {
+    /// Attach a note to an exercise. It defaults to the exercise you are currently working on.
+    Add {
+        /// The text of the note, e.g. `wr note add "&str borrows, String owns"`.
+        text: String,
+        /// The exercise, specified as `<chapter>/<exercise>`, by name or number.
+        #[arg(long, add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// List your notes.
+    List {
+        /// Only show the notes on this exercise, specified as `<chapter>/<exercise>`.
+        #[arg(long, add = ArgValueCandidates::new(exercise_path_candidates))]
+        exercise: Option<String>,
+    },
+    /// Save your notes to a Markdown file, one section per exercise.
+    Export {
+        /// Where your notes should be saved.
+        #[arg(default_value = "notes.md")]
+        path: std::path::PathBuf,
+    },
+}
+
+/// The arguments used to specify an exercise, e.g. for `wr open`.
+///
+/// If no exercise is specified, the user is asked to pick one from an interactive list.
+#[derive(clap::Args)]
+pub struct ExerciseArgs {
+    /// The exercise, either as `<chapter>/<exercise>`, as `<chapter> <exercise>` or as the path
+    /// to its folder.
+    #[arg(num_args = 1..=2, conflicts_with_all = ["chapter", "exercise"], add = ArgValueCandidates::new(exercise_path_candidates))]
+    target: Vec<String>,
+    /// The name of the chapter containing the exercise, or its number.
+    ///
+    /// E.g. `--chapter 01_structured_logging` and `--chapter 1` are equivalent.
+    #[arg(long, requires = "exercise", add = ArgValueCandidates::new(chapter_candidates))]
+    chapter: Option<String>,
+    /// The name of the exercise, or its number within the chapter it belongs to.
+    ///
+    /// E.g. `--exercise 00_intro` and `--exercise 0` are equivalent.
+    #[arg(long, requires = "chapter", add = ArgValueCandidates::new(exercise_candidates))]
+    exercise: Option<String>,
+}
+
+impl ExerciseArgs {
+    /// It returns `None` if the user didn't pick any exercise from the interactive list.
+    fn find(
+        self,
+        exercises: &ExerciseCollection,
+    ) -> Result<Option<ExerciseDefinition>, anyhow::Error> {
+        let ExerciseArgs {
+            mut target,
+            chapter,
+            exercise,
+        } = self;
+        let (chapter, exercise) = match (target.len(), chapter, exercise) {
+            (1, _, _) => {
+                return find_exercise_by_path_or_specifier(exercises, &target[0]).map(Some)
+            }
+            (2, _, _) => {
+                let exercise = target.pop().unwrap();
+                let chapter = target.pop().unwrap();
+                (Selector::new(chapter), Selector::new(exercise))
+            }
+            (_, Some(chapter), Some(exercise)) => (Selector::new(chapter), Selector::new(exercise)),
+            _ => return pick_exercise(exercises),
+        };
+        find_exercise(exercises, &chapter, &exercise).map(Some)
     }
-    let configuration = ExercisesConfig::load()?;
-    let verbose = command.verbose;
-    let mut exercises = ExerciseCollection::new(configuration.exercises_dir().to_path_buf())?;
+}
 
-    if let Some(command) = command.command {
-        match command {
-            Commands::Open { chapter, exercise } => {
-                enum Selector {
-                    FullName(String),
-                    Number(u16),
-                }
+/// The exercise that `wr check` should verify: the one specified on the command line, if any,
+/// or the one in the current directory.
+fn exercise_to_check(
+    exercises: &ExerciseCollection,
+    exercise: Option<String>,
+) -> Result<ExerciseDefinition, anyhow::Error> {
+    match exercise {
+        Some(exercise) => find_exercise_by_path_or_specifier(exercises, &exercise),
+        None => {
+            let current_dir = std::env::current_dir()?;
+            exercise_at_path(exercises, &current_dir)?
+                .ok_or_else(|| anyhow::anyhow!("The current directory is not an exercise"))
+        }
+    }
+}
 
-                impl Selector {
-                    fn new(s: String) -> Self {
-                        match s.parse::<u16>() {
-                            Ok(number) => Selector::Number(number),
-                            Err(_) => Selector::FullName(s),
-                        }
-                    }
+/// Find an exercise given either its path (e.g. `exercises/02_basics/10_casting`)
+/// or a `<chapter>/<exercise>` specifier (e.g. `2/10`).
+fn find_exercise_by_path_or_specifier(
+    exercises: &ExerciseCollection,
+    s: &str,
+) -> Result<ExerciseDefinition, anyhow::Error> {
+    let path = Path::new(s);
+    if path.is_dir() {
+        return exercise_at_path(exercises, path)?
+            .ok_or_else(|| anyhow::anyhow!("{path:?} is not an exercise"));
+    }
+    let (chapter, exercise) = parse_exercise_specifier(s)?;
+    find_exercise(exercises, &chapter, &exercise)
+}
 
-                    fn matches(&self, name: &str, number: u16) -> bool {
-                        match self {
-                            Selector::FullName(s) => s == name,
-                            Selector::Number(n) => *n == number,
-                        }
-                    }
-                }
+/// The exercise located at `path`, if there is one.
+fn exercise_at_path(
+    exercises: &ExerciseCollection,
+    path: &Path,
+) -> Result<Option<ExerciseDefinition>, anyhow::Error> {
+    let path = path.fs_err_canonicalize()?;
+    for exercise in exercises.iter() {
+        let manifest_folder = exercise
+            .manifest_folder_path(exercises.exercises_dir_of(exercise))
+            .fs_err_canonicalize()?;
+        if manifest_folder == path {
+            return Ok(Some(exercise.clone()));
+        }
+    }
+    Ok(None)
+}
 
-                impl std::fmt::Display for Selector {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        match self {
-                            Selector::FullName(s) => write!(f, "{}", s),
-                            Selector::Number(n) => write!(f, "{}", n),
-                        }
-                    }
-                }
+/// Let the user pick an exercise from a fuzzy-searchable list of the whole collection.
+///
+/// It returns `None` if the user cancelled the selection.
+fn pick_exercise(
+    exercises: &ExerciseCollection,
+) -> Result<Option<ExerciseDefinition>, anyhow::Error> {
+    // The list is redrawn as learners type, which screen readers can't follow.
+    if !std::io::stdin().is_terminal() || accessible() {
+        anyhow::bail!("Please specify an exercise, e.g. `wr open 2/10`")
+    }
+    let statuses = exercises.statuses()?;
+    let items: Vec<_> = statuses
+        .iter()
+        .map(|(exercise, status)| format!("{} {exercise}", Marker::from(*status)))
+        .collect();
+    let current = exercises.current()?;
+    let selection = dialoguer::FuzzySelect::new()
+        .with_prompt("Pick an exercise (type to search, Esc to cancel)")
+        .items(&items)
+        .default(
+            statuses
+                .iter()
+                .position(|(e, _)| Some(e) == current.as_ref())
+                .unwrap_or(0),
+        )
+        .interact_opt()
+        .context("Failed to show the list of exercises")?;
+    Ok(selection.map(|i| statuses[i].0.clone()))
+}
+
+/// The exercises in the current collection, for shell completions.
+///
+/// It returns an empty list if there is no collection in the current directory.
+fn completion_exercises() -> Vec<ExerciseDefinition> {
+    ExercisesConfig::load()
+        .and_then(|c| wr::discover_exercises(c.exercises_dirs(), c.naming_pattern()))
+        .map(|exercises| exercises.into_keys().collect())
+        .unwrap_or_default()
+}
+
+fn chapter_candidates() -> Vec<CompletionCandidate> {
+    let mut chapters: Vec<_> = completion_exercises().iter().map(|e| e.chapter()).collect();
+    chapters.dedup();
+    chapters.into_iter().map(CompletionCandidate::new).collect()
+}
+
+fn exercise_candidates() -> Vec<CompletionCandidate> {
+    let mut exercises: Vec<_> = completion_exercises()
+        .iter()
+        .map(|e| e.exercise())
+        .collect();
+    exercises.sort();
+    exercises.dedup();
+    exercises
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+fn exercise_path_candidates() -> Vec<CompletionCandidate> {
+    completion_exercises()
+        .iter()
+        .map(|e| CompletionCandidate::new(format!("{}/{}", e.chapter(), e.exercise())))
+        .collect()
+}
+
+/// Select a chapter or an exercise, either by name or by number.
+enum Selector {
+    FullName(String),
+    Number(u16),
+}
+
+impl Selector {
+    fn new(s: String) -> Self {
+        match s.parse::<u16>() {
+            Ok(number) => Selector::Number(number),
+            Err(_) => Selector::FullName(s),
+        }
+    }
+
+    fn matches(&self, name: &str, number: u16) -> bool {
+        match self {
+            Selector::FullName(s) => s == name,
+            Selector::Number(n) => *n == number,
+        }
+    }
+
+    /// Like [`Selector::matches`], but names only need to contain the selector
+    /// (case-insensitive) to match.
+    fn fuzzy_matches(&self, name: &str, number: u16) -> bool {
+        match self {
+            Selector::FullName(s) => name.to_lowercase().contains(&s.to_lowercase()),
+            Selector::Number(n) => *n == number,
+        }
+    }
+
+    fn matches_chapter(&self, exercise: &ExerciseDefinition) -> bool {
+        self.matches(&exercise.chapter(), exercise.chapter_number())
+    }
+
+    fn matches_exercise(&self, exercise: &ExerciseDefinition) -> bool {
+        self.matches(&exercise.exercise(), exercise.exercise_number())
+    }
+}
+
+impl std::fmt::Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Selector::FullName(s) => write!(f, "{}", s),
+            Selector::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// Parse a `<chapter>/<exercise>` specifier, e.g. `02/10` or `02_basics/10_casting`.
+fn parse_exercise_specifier(s: &str) -> Result<(Selector, Selector), anyhow::Error> {
+    let Some((chapter, exercise)) = s.split_once('/') else {
+        anyhow::bail!("`{s}` is not a valid exercise specifier. Expected `<chapter>/<exercise>`, e.g. `02/10`.")
+    };
+    Ok((
+        Selector::new(chapter.to_owned()),
+        Selector::new(exercise.to_owned()),
+    ))
+}
+
+/// Find the exercise matching both selectors.
+///
+/// If there is no exact match, we fall back to fuzzy matching (see [`Selector::fuzzy_matches`]),
+/// as long as it's unambiguous.
+fn find_exercise(
+    exercises: &ExerciseCollection,
+    chapter: &Selector,
+    exercise: &Selector,
+) -> Result<ExerciseDefinition, anyhow::Error> {
+    if let Some(exact) = exercises
+        .iter()
+        .find(|k| chapter.matches_chapter(k) && exercise.matches_exercise(k))
+    {
+        return Ok(exact.to_owned());
+    }
+    let candidates: Vec<_> = exercises
+        .iter()
+        .filter(|k| {
+            chapter.fuzzy_matches(&k.chapter(), k.chapter_number())
+                && exercise.fuzzy_matches(&k.exercise(), k.exercise_number())
+        })
+        .collect();
+    match candidates.as_slice() {
+        [] => anyhow::bail!(
+            "There is no exercise matching `--chapter {chapter} --exercise {exercise}`"
+        ),
+        [candidate] => Ok((*candidate).to_owned()),
+        candidates => {
+            let candidates: Vec<_> = candidates
+                .iter()
+                .map(|c| format!("\t- {}/{}", c.chapter(), c.exercise()))
+                .collect();
+            anyhow::bail!(
+                "`--chapter {chapter} --exercise {exercise}` is ambiguous. It matches:\n{}",
+                candidates.join("\n")
+            )
+        }
+    }
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    let outcome = run();
+    let error = outcome.as_ref().err().map(|e| format!("{e:#}"));
+    log(&wr::log::Event::Exit {
+        code: if error.is_some() { 1 } else { 0 },
+        error: error.as_deref(),
+    });
+    outcome
+}
+
+/// Exit with `code`, after recording it in the log.
+fn exit(code: i32) -> ! {
+    log(&wr::log::Event::Exit { code, error: None });
+    std::process::exit(code)
+}
+
+fn run() -> Result<(), anyhow::Error> {
+    // Respond to completion requests from the shell, if this is one of them.
+    CompleteEnv::with_factory(Command::command).complete();
+    let command = Command::parse();
+    if let Some(Commands::Limited {
+        memory_bytes,
+        cpu_time_secs,
+        command,
+    }) = &command.command
+    {
+        let limits = wr::process::ResourceLimits {
+            memory_bytes: *memory_bytes,
+            cpu_time: cpu_time_secs.map(std::time::Duration::from_secs),
+        };
+        let status = wr::process::run_limited(
+            std::process::Command::new(&command[0]).args(&command[1..]),
+            limits,
+        )?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    let _ = OUTPUT_FORMAT.set(command.output);
+    let _ = STREAM_OUTPUT.set(command.stream);
+    let _ = ACCESSIBLE.set(command.accessible);
+    let _ = COLOR.set(command.color);
+    if let Some(path) = &command.log {
+        open_log(path);
+    }
+    if json_output() && !supports_json(&command) {
+        anyhow::bail!(
+            "`--output json` is only supported by `wr`, `wr check`, `wr grade`, `wr list`, `wr status`, \
+            `wr stats`, `wr history` and `wr config validate`, without `--dry-run`"
+        );
+    }
+    if let Some(report) = &command.report {
+        if !supports_report(&command) {
+            anyhow::bail!(
+                "`--report` is only supported by `wr`, `wr check` and `wr grade`, without `--dry-run`"
+            );
+        }
+        match report.format {
+            ReportFormat::Junit => {
+                let _ = JUNIT_REPORT.set(report.path.clone());
+            }
+        }
+    }
+    // Enable ANSI colour support on Windows, if it's supported.
+    // Disable it entirely otherwise.
+    if !use_ansi_colours() {
+        Paint::disable();
+    }
+    if let Some(Commands::Init) = command.command {
+        let root_dir = std::env::current_dir()?;
+        wr::scaffold::init(&root_dir)?;
+        println!(
+            "{}",
+            success_style().paint(
+                "\n\tA new collection has been created!\n\tYour first exercise is in \"exercises/01_intro/00_welcome\"."
+            )
+        );
+        if wr::get_git_repository_root_dir().is_err() {
+            println!(
+                "{}",
+                next_style().paint(
+                    "\tRun `git init`: `wr` expects collections to live in a `git` repository."
+                )
+            );
+        }
+        return Ok(());
+    }
+    if let Some(Commands::Completions { shell }) = &command.command {
+        let shells = Shells::builtins();
+        let shell = shells
+            .completer(shell)
+            .expect("clap only accepts supported shells");
+        shell.write_registration("COMPLETE", "wr", "wr", "wr", &mut std::io::stdout())?;
+        return Ok(());
+    }
+    // The point is to report the problems that would prevent the collection from loading.
+    if let Some(Commands::Config {
+        command: ConfigCommand::Validate,
+    }) = command.command
+    {
+        if !config_validate()? {
+            exit(1);
+        }
+        return Ok(());
+    }
+    // The progress database may be too damaged to load the collection.
+    if let Some(Commands::Db {
+        command: DbCommand::Check { fix },
+    }) = command.command
+    {
+        if !db_check(command.profile.as_deref(), fix)? {
+            exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(Commands::Doctor) = command.command {
+        if !doctor(command.profile.as_deref()) {
+            exit(1);
+        }
+        return Ok(());
+    }
+    let configuration = ExercisesConfig::load()?;
+    let _ = MESSAGES.set(configuration.messages().clone());
+    let _ = FAILURE_LINES.set(configuration.failure_lines());
+    if let (None, Some(path)) = (LOG.get(), configuration.log_file()) {
+        open_log(path);
+    }
+    // Flags passed explicitly take precedence over the collection's defaults.
+    let defaults = configuration.defaults();
+    let verbose = match command.verbose {
+        0 => verbose_from_env().or(defaults.verbose),
+        n => Some(n),
+    };
+    let verbosity = match verbose {
+        _ if command.quiet => Verbosity::Quiet,
+        None | Some(0) => Verbosity::Normal,
+        Some(1) => Verbosity::Verbose,
+        Some(_) => Verbosity::Trace,
+    };
+    let _ = VERBOSITY.set(verbosity);
+    // Build logs would get mixed up with the JSON output.
+    let verbose = verbosity >= Verbosity::Verbose && !json_output();
+    let recheck = command.recheck.or(defaults.recheck).unwrap_or(false);
+    let keep_going = command.keep_going.or(defaults.keep_going).unwrap_or(false);
+    if let Some(jobs) = command.jobs.or(defaults.jobs) {
+        // `cargo` picks it up, whether it's run by `wr` or by the collection's own commands.
+        std::env::set_var("CARGO_BUILD_JOBS", jobs.to_string());
+    }
+    let mut exercises = ExerciseCollection::with_store(
+        configuration.exercises_dirs().to_vec(),
+        configuration.naming_pattern().clone(),
+        configuration.open_progress_store(command.profile.as_deref())?,
+    )?;
+    if let Some(track) = &command.track {
+        exercises.select_track(track, configuration.track(track)?)?;
+    }
+    if !exercises.stale().is_empty()
+        && !matches!(command.command, Some(Commands::Prune))
+        && show_progress()
+    {
+        println!(
+            "{}",
+            next_style().paint(format!(
+                "\n\t{} {} exercise(s) you opened in the past can no longer be found and will be ignored.\n\tRun `wr prune` to forget about them.",
+                Marker::Warning,
+                exercises.stale().len()
+            ))
+        );
+    }
+    // Notices would get mixed up with the JSON output.
+    if show_progress() {
+        for (old, new) in exercises.renamed() {
+            println!(
+                "{}",
+                info_style().paint(format!(
+                    "\n\t{old} is now {new}: your progress on it has been carried over."
+                ))
+            );
+        }
+    }
+
+    if command.dry_run {
+        // With `--keep-going`, the next exercise is opened and verified too.
+        let next = match (&command.command, keep_going) {
+            (None, true) => exercises.next()?,
+            _ => None,
+        };
+        let planned = match command.command {
+            None => {
+                let mut planned = vec![];
+                for e in exercises.opened()? {
+                    if (e.skipped && !e.solved)
+                        || (e.solved
+                            && (!recheck
+                                || (!command.force && is_unchanged(&exercises, &e.definition, &configuration)?)))
+                    {
+                        continue;
+                    }
+                    planned.push(e.definition);
+                }
+                planned
+            }
+            Some(Commands::Check { all: true, .. } | Commands::Grade { .. }) => {
+                exercises.iter().cloned().collect()
+            }
+            Some(Commands::Check { tag, .. }) if !tag.is_empty() => exercises
+                .iter()
+                .filter(|e| exercises.has_any_tag(e, &tag))
+                .cloned()
+                .collect(),
+            Some(Commands::Check { exercise, .. }) => vec![exercise_to_check(&exercises, exercise)?],
+            Some(_) => anyhow::bail!(
+                "`--dry-run` is only supported when verifying exercises, i.e. with `wr`, `wr check` and `wr grade`"
+            ),
+        };
+        return print_plan(&exercises, &planned, next.as_ref(), &configuration, verbose);
+    }
+
+    if let Some(command) = command.command {
+        match command {
+            Commands::Init
+            | Commands::Doctor
+            | Commands::Config { .. }
+            | Commands::Db { .. }
+            | Commands::Completions { .. }
+            | Commands::Limited { .. } => {
+                unreachable!("This command is handled before loading the configuration")
+            }
+            Commands::Open { exercise, force } => {
+                let Some(exercise) = exercise.find(&exercises)? else {
+                    return Ok(());
+                };
+                let unmet = exercises.unmet_prerequisites(&exercise)?;
+                if !unmet.is_empty() && !force {
+                    let unmet: Vec<_> = unmet.iter().map(|e| format!("\t- {e}")).collect();
+                    anyhow::bail!(
+                        "{exercise} requires you to solve these exercises first:\n{}\nRun `wr open --force` to open it anyway.",
+                        unmet.join("\n")
+                    );
+                }
+                exercises.open(&exercise)?;
+                log_opened(&exercise);
+                print_opened_message(&exercise, exercises.exercises_dir_of(&exercise));
+            }
+            Commands::Close { exercise } => {
+                let Some(exercise) = exercise.find(&exercises)? else {
+                    return Ok(());
+                };
+                if !exercises.opened()?.iter().any(|o| o.definition == exercise) {
+                    anyhow::bail!("{exercise} is not open");
+                }
+                exercises.close(&exercise)?;
+                println!(
+                    "{}",
+                    info_style().paint(format!("\n\t{}", catalog().closed(&exercise.to_string())))
+                );
+            }
+            Commands::Grade {
+                report_path,
+                fail_under,
+            } => {
+                let report = grade(&exercises, &configuration, verbose)?;
+                fs_err::write(&report_path, serde_json::to_string_pretty(&report)?)
+                    .context("Failed to write the grade report")?;
+                if json_output() {
+                    print_json(&report)?;
+                } else {
+                    println!(
+                        "{}",
+                        cargo_style().paint(format!(
+                            "\n\tThe grade report has been written to {report_path:?}."
+                        ))
+                    );
+                }
+                if let Some(threshold) = fail_under {
+                    if report.score < threshold {
+                        if json_output() {
+                            exit(1);
+                        }
+                        println!(
+                            "{}",
+                            failure_style().paint(format!(
+                                "\tThe score ({:.1}%) is below the required threshold ({threshold:.1}%).",
+                                report.score
+                            ))
+                        );
+                        exit(1);
+                    }
+                }
+            }
+            Commands::NewExercise { chapter, name } => {
+                let exercise_dir = new_exercise(&exercises, chapter, &name)?;
+                println!(
+                    "{}",
+                    success_style().paint(format!(
+                        "\n\tA new exercise has been created in {exercise_dir:?}."
+                    ))
+                );
+            }
+            Commands::Check { all, tag, .. } if all || !tag.is_empty() => {
+                if !check_all(&exercises, &configuration, verbose, &tag)? {
+                    exit(1);
+                }
+            }
+            Commands::Check { exercise, .. } => {
+                let definition = exercise_to_check(&exercises, exercise)?;
+                let outcome = verify(&exercises, &definition, &configuration, verbose)?;
+                if json_output() {
+                    let verified = [JsonVerification::new(&exercises, &definition, &outcome)?];
+                    print_json(&serde_json::json!({ "verified": verified }))?;
+                }
+            }
+            Commands::List { tag } => {
+                print_list(&exercises, &tag)?;
+            }
+            Commands::Reset {
+                chapter,
+                exercise,
+                keep_open,
+            } => {
+                reset(&mut exercises, chapter, exercise, keep_open)?;
+            }
+            Commands::Skip { chapter, exercise } => {
+                let exercise = match (chapter, exercise) {
+                    (Some(chapter), Some(exercise)) => find_exercise(
+                        &exercises,
+                        &Selector::new(chapter),
+                        &Selector::new(exercise),
+                    )?,
+                    _ => current_exercise(&exercises)?,
+                };
+                exercises.mark_as_skipped(&exercise)?;
+                log(&wr::log::Event::Skipped {
+                    exercise: &exercise.path(),
+                });
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\t{} {}\n\t{}",
+                        Marker::Skipped,
+                        catalog().skipped(&exercise.to_string()),
+                        catalog().move_on()
+                    ))
+                );
+            }
+            Commands::Info { lines } => {
+                let exercise = current_exercise(&exercises)?;
+                print_info(&exercises, &exercise, &configuration, lines)?;
+            }
+            Commands::Export { path } => {
+                let snapshot = exercises.export()?;
+                fs_err::write(&path, serde_json::to_string_pretty(&snapshot)?)
+                    .context("Failed to save your progress")?;
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\tYour progress on {} exercise(s) has been saved to {path:?}.",
+                        snapshot.exercises.len()
+                    ))
+                );
+            }
+            Commands::Import {
+                path,
+                format: ImportFormat::Rustlings,
+            } => {
+                let done = wr::rustlings::done_exercises(&fs_err::read_to_string(&path)?)
+                    .with_context(|| format!("{path:?} is not a valid rustlings state file"))?;
+                backup_before_changes(&exercises)?;
+                let import = wr::rustlings::import(&mut exercises, &done)?;
+                for exercise in &import.solved {
+                    println!(
+                        "{}",
+                        success_style().paint(format!("\t{} {exercise}", Marker::Pass))
+                    );
+                }
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\t{} exercise(s) completed in rustlings have been marked as solved.",
+                        import.solved.len()
+                    ))
+                );
+                if !import.unmatched.is_empty() {
+                    println!(
+                        "{}",
+                        next_style().paint(format!(
+                            "\tThese exercises don't match exactly one exercise in the workshop, by name: {}.",
+                            import.unmatched.join(", ")
+                        ))
+                    );
+                }
+            }
+            Commands::Import {
+                path,
+                format: ImportFormat::Wr,
+            } => {
+                let snapshot: wr::ProgressSnapshot =
+                    serde_json::from_str(&fs_err::read_to_string(&path)?)
+                        .with_context(|| format!("{path:?} is not a valid progress file"))?;
+                backup_before_changes(&exercises)?;
+                exercises.import(&snapshot)?;
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\tYour progress on {} exercise(s) has been restored from {path:?}.",
+                        snapshot.exercises.len()
+                    ))
+                );
+            }
+            Commands::Sync { url, token } => {
+                let Some(url) = url.as_deref().or(configuration.sync_url()) else {
+                    anyhow::bail!(
+                        "There is nowhere to sync your progress to.\n\
+                        Pass `--url` or ask the workshop authors to set `sync_url` in `.wr.toml`."
+                    );
+                };
+                let pulled = match wr::sync::pull(url, &token)? {
+                    Some(remote) => exercises.merge(&remote)?,
+                    None => 0,
+                };
+                let snapshot = exercises.export_for_sync()?;
+                wr::sync::push(url, &token, &snapshot)?;
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\tYour progress is in sync with {url}: {pulled} exercise(s) updated locally, {} pushed.",
+                        snapshot.exercises.len()
+                    ))
+                );
+            }
+            Commands::Backup { path } => {
+                let path = exercises.backup(path.as_deref())?;
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\tYour progress has been backed up to {path:?}.\n\tRun `wr restore-backup {}` to restore it.",
+                        path.display()
+                    ))
+                );
+            }
+            Commands::RestoreBackup { path } => {
+                if !path.is_file() {
+                    anyhow::bail!("There is no backup at {path:?}");
+                }
+                if !confirm(&format!(
+                    "Do you really want to replace your progress with the backup in {path:?}?"
+                )) {
+                    return Ok(());
+                }
+                backup_before_changes(&exercises)?;
+                exercises.restore_backup(&path)?;
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\tYour progress has been restored from {path:?}."
+                    ))
+                );
+            }
+            Commands::Prune => {
+                if !exercises.stale().is_empty() {
+                    backup_before_changes(&exercises)?;
+                }
+                let pruned = exercises.prune()?;
+                for exercise in &pruned {
+                    println!(
+                        "{}",
+                        cargo_style().paint(format!("\t{} {exercise}", Marker::Removed))
+                    );
+                }
+                println!(
+                    "{}",
+                    info_style().paint(format!(
+                        "\n\t{} stale exercise(s) removed from your progress.",
+                        pruned.len()
+                    ))
+                );
+            }
+            Commands::Diff { exercise } => {
+                let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                let exercise_dir =
+                    exercise.manifest_folder_path(exercises.exercises_dir_of(&exercise));
+                let diff = wr::git::diff(&exercise_dir, use_ansi_colours())?;
+                if diff.is_empty() {
+                    println!(
+                        "{}",
+                        info_style().paint(format!("\n\tYou haven't changed {exercise} yet."))
+                    );
+                } else {
+                    print!("{diff}");
+                }
+            }
+            Commands::Restore { exercise } => {
+                let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                if confirm(&format!(
+                    "Your changes to {exercise} will be discarded. Do you want to continue?"
+                )) {
+                    wr::git::restore(
+                        &exercise.manifest_folder_path(exercises.exercises_dir_of(&exercise)),
+                    )?;
+                    println!(
+                        "{}",
+                        info_style().paint(format!(
+                            "\n\t{exercise} has been restored to its starting state."
+                        ))
+                    );
+                }
+            }
+            Commands::Bookmark { command } => match command {
+                Some(BookmarkCommand::Add { exercise }) => {
+                    let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                    let message = if exercises.bookmark(&exercise)? {
+                        format!("\n\t{} {exercise} has been bookmarked.", Marker::Bookmark)
+                    } else {
+                        format!("\n\t{exercise} is already bookmarked.")
+                    };
+                    println!("{}", info_style().paint(message));
+                }
+                Some(BookmarkCommand::Remove { exercise }) => {
+                    let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                    let message = if exercises.remove_bookmark(&exercise)? {
+                        format!("\n\tThe bookmark for {exercise} has been removed.")
+                    } else {
+                        format!("\n\t{exercise} isn't bookmarked.")
+                    };
+                    println!("{}", info_style().paint(message));
+                }
+                Some(BookmarkCommand::List) | None => print_bookmarks(&exercises)?,
+            },
+            Commands::Note { command } => match command {
+                Some(NoteCommand::Add { text, exercise }) => {
+                    let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                    let text = text.trim();
+                    if text.is_empty() {
+                        anyhow::bail!("Your note is empty")
+                    }
+                    exercises.add_note(&exercise, text)?;
+                    println!(
+                        "{}",
+                        info_style().paint(format!(
+                            "\n\t{} Your note on {exercise} has been saved.",
+                            Marker::Note
+                        ))
+                    );
+                }
+                Some(NoteCommand::List { exercise }) => {
+                    let exercise = exercise
+                        .map(|e| {
+                            let (chapter, exercise) = parse_exercise_specifier(&e)?;
+                            find_exercise(&exercises, &chapter, &exercise)
+                        })
+                        .transpose()?;
+                    print_notes(&exercises, exercise.as_ref())?;
+                }
+                Some(NoteCommand::Export { path }) => {
+                    let notes = exercises.notes(None)?;
+                    fs_err::write(&path, notes_to_markdown(&notes))
+                        .context("Failed to save your notes")?;
+                    println!(
+                        "{}",
+                        info_style().paint(format!(
+                            "\n\t{} note(s) have been saved to {path:?}.",
+                            notes.len()
+                        ))
+                    );
+                }
+                None => print_notes(&exercises, None)?,
+            },
+            Commands::Run { args } => {
+                let exercise = current_exercise(&exercises)?;
+                let config = exercise.config(exercises.exercises_dir_of(&exercise))?;
+                let status = cargo(config.as_ref().and_then(|c| c.toolchain.as_deref()))
+                    .arg("run")
+                    .arg("--manifest-path")
+                    .arg(exercise.manifest_path(exercises.exercises_dir_of(&exercise)))
+                    .arg("--")
+                    .args(&args)
+                    .status()
+                    .context("Failed to run `cargo run`")?;
+                if !status.success() {
+                    exit(status.code().unwrap_or(1));
+                }
+            }
+            Commands::Readme { exercise } => {
+                let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                let Some(readme) = exercise.readme_path(exercises.exercises_dir_of(&exercise))
+                else {
+                    anyhow::bail!("{exercise} doesn't have a README")
+                };
+                let readme = fs_err::read_to_string(readme)?;
+                let skin = if use_ansi_colours() {
+                    termimad::MadSkin::default()
+                } else {
+                    termimad::MadSkin::no_style()
+                };
+                skin.print_text(&readme);
+            }
+            Commands::LastFailure { exercise, full } => {
+                let exercise = specified_or_current_exercise(&exercises, exercise)?;
+                match exercises.last_failure(&exercise)? {
+                    Some(failure) => {
+                        let output = if use_ansi_colours() {
+                            failure.output
+                        } else {
+                            wr::strip_ansi_codes(&failure.output)
+                        };
+                        if full {
+                            _print_failure_message(
+                                &exercise
+                                    .manifest_folder_path(exercises.exercises_dir_of(&exercise)),
+                                &failure.stage,
+                                &failure.command,
+                                &output,
+                            );
+                        } else {
+                            print_failure_message(
+                                &exercises,
+                                &exercise,
+                                &failure.stage,
+                                &failure.command,
+                                output.as_bytes(),
+                            );
+                        }
+                    }
+                    None => println!(
+                        "{}",
+                        info_style().paint(format!(
+                            "\n\t{exercise} hasn't failed verification since it was last solved."
+                        ))
+                    ),
+                }
+            }
+            Commands::History { exercise } => {
+                let exercise = exercise
+                    .map(|e| {
+                        let (chapter, exercise) = parse_exercise_specifier(&e)?;
+                        find_exercise(&exercises, &chapter, &exercise)
+                    })
+                    .transpose()?;
+                print_history(&exercises, exercise.as_ref())?;
+            }
+            Commands::Clean { chapter } => {
+                clean(&exercises, chapter)?;
+            }
+            Commands::Hint => {
+                let exercise = current_exercise(&exercises)?;
+                hint(&exercises, &exercise)?;
+            }
+            Commands::Solution { command } => {
+                let exercise = current_exercise(&exercises)?;
+                let Some(solutions_dir) = configuration.solutions_dir() else {
+                    anyhow::bail!("This collection doesn't provide reference solutions")
+                };
+                let solution_dir = exercise.solution_folder_path(solutions_dir);
+                if !solution_dir.exists() {
+                    anyhow::bail!("There is no reference solution for {exercise}");
+                }
+                match command {
+                    SolutionCommand::Show => show_solution(&exercise, &solution_dir)?,
+                    SolutionCommand::Apply => apply_solution(
+                        &exercise,
+                        &solution_dir,
+                        exercises.exercises_dir_of(&exercise),
+                    )?,
+                }
+            }
+            Commands::Serve { port } => {
+                serve(&exercises, &configuration, verbose, port)?;
+            }
+            Commands::Tui => {
+                if !std::io::stdout().is_terminal() {
+                    anyhow::bail!("`wr tui` must be run in an interactive terminal");
+                }
+                if accessible() {
+                    anyhow::bail!(
+                        "`wr tui` redraws the screen as you go, which screen readers can't follow: \
+                        use `wr list`, `wr open` and `wr check` instead"
+                    );
+                }
+                wr::tui::run(&mut exercises, |exercises, exercise| {
+                    let outcome = verify(exercises, exercise, &configuration, verbose)?;
+                    if let TestOutcome::Failure {
+                        stage,
+                        command,
+                        details,
+                    } = &outcome
+                    {
+                        print_failure_message(exercises, exercise, stage, command, details);
+                    }
+                    Ok(outcome == TestOutcome::Success)
+                })?;
+            }
+            Commands::Stats => {
+                print_stats(&exercises)?;
+            }
+            Commands::Status => {
+                print_status(&exercises, &configuration)?;
+            }
+            Commands::Report { html, out } => {
+                let report = progress_report(&exercises, &configuration)?;
+                let report = if html {
+                    report.to_html()
+                } else {
+                    report.to_markdown()
+                };
+                match out {
+                    None => print!("{report}"),
+                    Some(out) => {
+                        fs_err::write(&out, report).context("Failed to write the report")?;
+                        println!(
+                            "{}",
+                            cargo_style()
+                                .paint(format!("\n\tThe report has been written to {out:?}."))
+                        );
+                    }
+                }
+            }
+            Commands::Warm { fetch_only } => {
+                if !warm(&exercises, &configuration, fetch_only, verbose)? {
+                    exit(1);
+                }
+            }
+            Commands::Badge { out, label } => {
+                let ((n_solved, total), _) = n_solved(&exercises, &exercises.statuses()?);
+                let badge = wr::badge::render(&label, percentage(n_solved, total));
+                fs_err::write(&out, badge).context("Failed to write the badge")?;
+                println!(
+                    "{}",
+                    cargo_style().paint(format!("\n\tThe badge has been written to {out:?}."))
+                );
+            }
+            Commands::Watch => {
+                watch(&exercises, &configuration, verbose)?;
+            }
+        }
+        return Ok(());
+    }
+
+    if exercises.opened()?.is_empty() {
+        run_hook(configuration.on_start(), "on_start")?;
+    }
+    // The completion hook only fires on the run that completes the collection.
+    let completed_before = exercises.is_completed()?;
+
+    // If no command was specified, we verify the user's progress on the workshop-runner that have already
+    // been opened.
+    let mut progress = RunProgress::default();
+    let verified = seek_the_path(
+        &mut exercises,
+        &mut progress,
+        recheck,
+        command.force,
+        !command.no_fail_fast,
+        &configuration,
+        verbose,
+    )?;
+    if json_output() {
+        let open_next = command.yes || !configuration.prompt();
+        return report_progress(
+            &mut exercises,
+            &configuration,
+            verified,
+            keep_going,
+            open_next,
+            completed_before,
+        );
+    }
+    let n_verified = verified.len();
+    let failures: Vec<_> = verified
+        .into_iter()
+        .filter(|(_, outcome)| *outcome != TestOutcome::Success)
+        .collect();
+    let stuck = failures.iter().any(|(e, _)| !exercises.is_optional(e));
+    // With `--keep-going`, more exercises may be verified before the run is over.
+    if !keep_going || stuck {
+        progress.finish(&exercises)?;
+    }
+    for (exercise, failure) in &failures {
+        if let TestOutcome::Failure {
+            stage,
+            command,
+            details,
+        } = failure
+        {
+            print_failure_message(&exercises, exercise, stage, command, details);
+        }
+    }
+    if command.no_fail_fast {
+        print_failure_summary(&failures, n_verified);
+    }
+    if stuck {
+        exit(1);
+    }
+    for (exercise, _) in &failures {
+        print_optional_failure_message(exercise);
+    }
+
+    // If all the currently opened workshop-runner passed their checks, we open the next one (if it exists).
+    while let Some(next_exercise) = exercises.next()? {
+        if keep_going {
+            let next_exercise = exercises
+                .open_next()
+                .expect("Failed to open the next exercise");
+            log_opened(&next_exercise);
+            progress.enter(&exercises, &next_exercise)?;
+            let exercise_outcome = verify(&exercises, &next_exercise, &configuration, verbose)?;
+            if let TestOutcome::Failure {
+                stage,
+                command,
+                details,
+            } = exercise_outcome
+            {
+                let optional = exercises.is_optional(&next_exercise);
+                if !optional {
+                    progress.finish(&exercises)?;
+                }
+                print_failure_message(&exercises, &next_exercise, &stage, &command, &details);
+                if !optional {
+                    exit(1);
+                }
+                print_optional_failure_message(&next_exercise);
+            };
+            continue;
+        } else {
+            println!(
+                "\t{}\n",
+                info_style().paint(without_emoji(messages().not_finished()))
+            );
+
+            let open_next = command.yes
+                || !configuration.prompt()
+                || confirm(&messages().next_prompt(&next_exercise));
+
+            if open_next {
+                let next_exercise = exercises
+                    .open_next()
+                    .expect("Failed to open the next exercise");
+                log_opened(&next_exercise);
+                print_opened_message(&next_exercise, exercises.exercises_dir_of(&next_exercise));
+            }
+            return Ok(());
+        }
+    }
+    if keep_going {
+        progress.finish(&exercises)?;
+    }
+    let metadata = configuration.metadata();
+    println!(
+        "{}\n\t{}\n",
+        success_style().paint(format!(
+            "\n\t{}",
+            catalog().no_more_tasks(metadata.title.as_deref())
+        )),
+        info_style().paint(without_emoji(messages().completed()))
+    );
+    if let Some(repository) = &metadata.repository {
+        println!(
+            "{}\n",
+            cargo_style().paint(format!("\t{}", catalog().feedback(repository)))
+        );
+    }
+    if !completed_before {
+        run_hook(configuration.on_complete(), "on_complete")?;
+    }
+    Ok(())
+}
+
+/// Show the message of a hook, then run its command, if any.
+///
+/// A failing command is reported, but it doesn't stop `wr`: learners can't do much about it.
+/// With `--output json`, the message is left out and the command prints to stderr.
+fn run_hook(hook: &wr::Hook, name: &str) -> Result<(), anyhow::Error> {
+    if !json_output() {
+        if let Some(message) = &hook.message {
+            println!(
+                "{}\n",
+                next_style().paint(textwrap::indent(message.trim_end(), "\t"))
+            );
+        }
+    }
+    if let Some(run) = &hook.run {
+        let mut cmd = run.command();
+        // The root directory is relative to the current one, and empty if they are the same.
+        cmd.current_dir(Path::new(".").join(wr::get_git_repository_root_dir()?));
+        if json_output() {
+            cmd.stdout(std::io::stderr());
+        }
+        let outcome = cmd.status();
+        let problem = match outcome {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(format!("exited with {status}")),
+            Err(e) => Some(format!("couldn't be run: {e}")),
+        };
+        if let Some(problem) = problem {
+            let problem = format!(
+                "\n\tThe `{name}` command of the workshop ({}) {problem}.",
+                run.command
+            );
+            if json_output() {
+                eprintln!("{problem}");
+            } else {
+                println!("{}", failure_style().paint(problem));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Report on the exercises that have been verified as JSON, then move on to the next exercise,
+/// like `wr` does, but without asking: the next exercise is opened only if `open_next` is set.
+fn report_progress(
+    exercises: &mut ExerciseCollection,
+    configuration: &ExercisesConfig,
+    verified: Vec<(ExerciseDefinition, TestOutcome)>,
+    keep_going: bool,
+    open_next: bool,
+    completed_before: bool,
+) -> Result<(), anyhow::Error> {
+    let mut report = JsonProgress {
+        verified: verified
+            .iter()
+            .map(|(definition, outcome)| JsonVerification::new(exercises, definition, outcome))
+            .collect::<Result<_, _>>()?,
+        opened: None,
+        next: None,
+        completed: false,
+    };
+    let stuck = |report: &JsonProgress| report.verified.iter().any(|v| !v.passed && !v.optional);
+    while !stuck(&report) {
+        let Some(next_exercise) = exercises.next()? else {
+            report.completed = true;
+            break;
+        };
+        if !keep_going && !open_next {
+            report.next = Some(next_exercise.path());
+            break;
+        }
+        let next_exercise = exercises.open_next()?;
+        log_opened(&next_exercise);
+        report.opened = Some(next_exercise.path());
+        if !keep_going {
+            break;
+        }
+        let outcome = verify(exercises, &next_exercise, configuration, false)?;
+        report
+            .verified
+            .push(JsonVerification::new(exercises, &next_exercise, &outcome)?);
+    }
+    print_json(&report)?;
+    if stuck(&report) {
+        exit(1);
+    }
+    if report.completed && !completed_before {
+        run_hook(configuration.on_complete(), "on_complete")?;
+    }
+    Ok(())
+}
+
+/// Let the user know that they can move on, even though an optional exercise failed verification.
+fn print_optional_failure_message(exercise: &ExerciseDefinition) {
+    println!(
+        "{}",
+        next_style().paint(format!(
+            "\n\t{}",
+            catalog().optional_failure(&exercise.to_string())
+        ))
+    );
+}
+
+/// Ask the user a yes/no question, until they give us a valid answer.
+fn confirm(question: &str) -> bool {
+    let catalog = catalog();
+    let answer = input::<String>()
+        .repeat_msg(format!("{question} {} ", catalog.answers()))
+        .err(catalog.invalid_answer())
+        .add_test(|s| catalog.parse_answer(s).is_some())
+        .get();
+    // We can safely unwrap here because we have already validated the input.
+    catalog.parse_answer(&answer).unwrap()
+}
+
+/// Verify the exercises that have been opened, in order.
+///
+/// It returns the exercises that were verified, alongside the outcome.
+/// If `fail_fast` is set, it stops at the first failure.
+///
+/// When rechecking, solved exercises whose sources haven't changed are skipped, unless `force` is set.
+fn seek_the_path(
+    exercises: &mut ExerciseCollection,
+    progress: &mut RunProgress,
+    recheck: bool,
+    force: bool,
+    fail_fast: bool,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+) -> Result<Vec<(ExerciseDefinition, TestOutcome)>, anyhow::Error> {
+    if show_progress() {
+        println!(
+            " \n\n{}",
+            info_style()
+                .dimmed()
+                .paint(format!("{}\n", catalog().running_tests()))
+        );
+    }
+    let mut verified = vec![];
+    for exercise in exercises.opened()? {
+        let OpenedExercise {
+            definition,
+            solved,
+            skipped,
+            ..
+        } = &exercise;
+        if !exercise
+            .definition
+            .exists(exercises.exercises_dir_of(&exercise.definition))
+        {
+            exercises.close(definition)?;
+            continue;
+        }
+        progress.enter(exercises, definition)?;
+        let skipped_because = if *skipped && !*solved {
+            Some((Marker::Skipped, catalog().not_verified_skipped()))
+        } else if *solved && !recheck {
+            Some((Marker::Unchanged, catalog().not_verified_not_rechecked()))
+        } else if *solved && !force && is_unchanged(exercises, definition, configuration)? {
+            Some((Marker::Unchanged, catalog().not_verified_unchanged()))
+        } else {
+            None
+        };
+        if let Some((icon, reason)) = skipped_because {
+            if show_progress() {
+                println!(
+                    "{}",
+                    info_style().paint(format!("\t{icon} {definition} ({reason})"))
+                );
+            }
+            continue;
+        }
+        let exercise_outcome = verify(exercises, definition, configuration, verbose)?;
+        let failed = matches!(exercise_outcome, TestOutcome::Failure { .. });
+        verified.push((exercise.definition.clone(), exercise_outcome));
+        // Optional exercises don't stand in the way of the next ones.
+        if failed && fail_fast && !exercise.optional {
+            break;
+        }
+    }
+    Ok(verified)
+}
+
+/// Groups the lines printed for each exercise during a run under a header for its chapter,
+/// followed by how many exercises of the chapter have been solved.
+/// Once the run is over, a progress bar shows how far along the whole workshop is.
+#[derive(Default)]
+struct RunProgress {
+    /// An exercise of the chapter whose lines are being printed, if any.
+    chapter: Option<ExerciseDefinition>,
+}
+
+impl RunProgress {
+    /// Print the header of the chapter of `exercise`, if it's not the current one.
+    fn enter(
+        &mut self,
+        exercises: &ExerciseCollection,
+        exercise: &ExerciseDefinition,
+    ) -> Result<(), anyhow::Error> {
+        if !show_progress()
+            || self.chapter.as_ref().map(|c| c.chapter_number()) == Some(exercise.chapter_number())
+        {
+            return Ok(());
+        }
+        self.close_chapter(exercises)?;
+        println!(
+            "\n\t{}",
+            info_style().bold().paint(format!(
+                "({:02}) {}",
+                exercise.chapter_number(),
+                exercise.chapter_name()
+            ))
+        );
+        self.chapter = Some(exercise.clone());
+        Ok(())
+    }
+
+    /// Print the subtotal of the current chapter, if any.
+    fn close_chapter(&mut self, exercises: &ExerciseCollection) -> Result<(), anyhow::Error> {
+        let Some(chapter) = self.chapter.take() else {
+            return Ok(());
+        };
+        let mut statuses = exercises.statuses()?;
+        statuses.retain(|(e, _)| e.chapter_number() == chapter.chapter_number());
+        let ((n_solved, total), optional) = n_solved(exercises, &statuses);
+        println!(
+            "{}",
+            cargo_style().paint(format!(
+                "\t   {}{}",
+                catalog().solved_in_chapter(n_solved, total),
+                catalog().optional_solved(optional.0, optional.1)
+            ))
+        );
+        Ok(())
+    }
+
+    /// Close the current chapter and print the progress bar for the whole workshop.
+    fn finish(&mut self, exercises: &ExerciseCollection) -> Result<(), anyhow::Error> {
+        if !show_progress() {
+            return Ok(());
+        }
+        self.close_chapter(exercises)?;
+        let ((n_solved, total), optional) = n_solved(exercises, &exercises.statuses()?);
+        const WIDTH: usize = 30;
+        let filled = (n_solved * WIDTH).checked_div(total).unwrap_or(0);
+        // The bar says nothing more than the numbers next to it, to screen readers.
+        let bar = if accessible() {
+            String::new()
+        } else {
+            success_style()
+                .paint(format!(
+                    "[{}{}] ",
+                    "█".repeat(filled),
+                    "░".repeat(WIDTH - filled)
+                ))
+                .to_string()
+        };
+        println!(
+            "\n\t{bar}{}",
+            info_style().bold().paint(format!(
+                "{}{}",
+                catalog().solved(n_solved, total, percentage(n_solved, total)),
+                catalog().optional_solved(optional.0, optional.1)
+            ))
+        );
+        Ok(())
+    }
+}
+
+/// Print a table listing every exercise that failed verification, and at which stage.
+fn print_failure_summary(failures: &[(ExerciseDefinition, TestOutcome)], n_verified: usize) {
+    println!(
+        "\n\t{}",
+        info_style()
+            .bold()
+            .paint(catalog().passed_verification(n_verified - failures.len(), n_verified))
+    );
+    let width = failures
+        .iter()
+        .map(|(definition, _)| definition.to_string().chars().count())
+        .max()
+        .unwrap_or_default();
+    for (definition, outcome) in failures {
+        if let TestOutcome::Failure { stage, .. } = outcome {
+            println!(
+                "{}",
+                failure_style().paint(format!(
+                    "\t{} {:<width$}  {}",
+                    Marker::Fail,
+                    definition.to_string(),
+                    catalog().failed_at_stage(stage)
+                ))
+            );
+        }
+    }
+    println!();
+}
+
+#[derive(serde::Serialize)]
+struct GradeReport {
+    /// The workshop that was graded.
+    workshop: wr::CollectionMetadata,
+    /// The percentage of points earned, between 0 and 100.
+    score: f64,
+    points: f64,
+    max_points: f64,
+    solved: usize,
+    total: usize,
+    chapters: Vec<ChapterGrade>,
+}
+
+#[derive(serde::Serialize)]
+struct ChapterGrade {
+    chapter: String,
+    points: f64,
+    max_points: f64,
+    solved: usize,
+    total: usize,
+    exercises: Vec<ExerciseGrade>,
+}
+
+#[derive(serde::Serialize)]
+struct ExerciseGrade {
+    exercise: String,
+    passed: bool,
+    weight: f64,
+}
+
+fn grade(
+    exercises: &ExerciseCollection,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+) -> Result<GradeReport, anyhow::Error> {
+    if show_progress() {
+        println!(
+            " \n\n{}",
+            info_style()
+                .dimmed()
+                .paint(format!("{}\n", catalog().running_tests()))
+        );
+    }
+    let mut chapters: Vec<ChapterGrade> = vec![];
+    for definition in exercises.iter() {
+        let weight = definition
+            .config(exercises.exercises_dir_of(definition))?
+            .map(|c| c.weight)
+            .unwrap_or(1.0);
+        if !weight.is_finite() || weight <= 0.0 {
+            anyhow::bail!(
+                "The weight of {definition} must be a positive number, not {weight}. \
+                Run `wr config validate` to check the rest of the configuration"
+            );
+        }
+        let passed = verify(exercises, definition, configuration, verbose)? == TestOutcome::Success;
+        if chapters.last().map(|c| &c.chapter) != Some(&definition.chapter()) {
+            chapters.push(ChapterGrade {
+                chapter: definition.chapter(),
+                points: 0.0,
+                max_points: 0.0,
+                solved: 0,
+                total: 0,
+                exercises: vec![],
+            });
+        }
+        let chapter = chapters.last_mut().unwrap();
+        chapter.total += 1;
+        chapter.max_points += weight;
+        if passed {
+            chapter.solved += 1;
+            chapter.points += weight;
+        }
+        chapter.exercises.push(ExerciseGrade {
+            exercise: definition.exercise(),
+            passed,
+            weight,
+        });
+    }
+
+    let points: f64 = chapters.iter().map(|c| c.points).sum();
+    let max_points: f64 = chapters.iter().map(|c| c.max_points).sum();
+    let report = GradeReport {
+        workshop: configuration.metadata(),
+        score: if max_points > 0.0 {
+            points / max_points * 100.0
+        } else {
+            0.0
+        },
+        points,
+        max_points,
+        solved: chapters.iter().map(|c| c.solved).sum(),
+        total: chapters.iter().map(|c| c.total).sum(),
+        chapters,
+    };
+    if json_output() {
+        return Ok(report);
+    }
+
+    println!();
+    for chapter in &report.chapters {
+        println!(
+            "\t{} {}",
+            info_style()
+                .bold()
+                .paint(format!("{:<30}", chapter.chapter)),
+            cargo_style().paint(catalog().chapter_score(
+                chapter.solved,
+                chapter.total,
+                chapter.points,
+                chapter.max_points
+            ))
+        );
+    }
+    println!(
+        "\n\t{}",
+        next_style().bold().paint(catalog().score(
+            report.score,
+            report.points,
+            report.max_points,
+            report.solved,
+            report.total
+        ))
+    );
+    Ok(report)
+}
+
+/// Verify every exercise in the collection (or only the ones with any of `tags`, if there are some),
+/// returning `true` if they all passed.
+fn check_all(
+    exercises: &ExerciseCollection,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+    tags: &[String],
+) -> Result<bool, anyhow::Error> {
+    let selected: Vec<_> = exercises
+        .iter()
+        .filter(|e| exercises.has_any_tag(e, tags))
+        .collect();
+    if selected.is_empty() {
+        anyhow::bail!("No exercise is tagged with {}", format_tags(tags));
+    }
+    if json_output() {
+        let mut verified = vec![];
+        for definition in selected {
+            let outcome = verify(exercises, definition, configuration, verbose)?;
+            verified.push(JsonVerification::new(exercises, definition, &outcome)?);
+        }
+        let passed = verified.iter().all(|v| v.passed);
+        print_json(&serde_json::json!({ "verified": verified }))?;
+        return Ok(passed);
+    }
+    if show_progress() {
+        println!(
+            " \n\n{}",
+            info_style()
+                .dimmed()
+                .paint(format!("{}\n", catalog().running_tests()))
+        );
+    }
+    let mut failures = vec![];
+    let mut n_checked = 0;
+    for definition in selected {
+        n_checked += 1;
+        if let TestOutcome::Failure {
+            stage,
+            command,
+            details,
+        } = verify(exercises, definition, configuration, verbose)?
+        {
+            print_failure_message(exercises, definition, &stage, &command, &details);
+            failures.push(definition);
+        }
+    }
+    println!(
+        "\n\t{}",
+        info_style()
+            .bold()
+            .paint(catalog().passed_verification(n_checked - failures.len(), n_checked))
+    );
+    // With `--quiet`, the failures have already been listed as they happened.
+    if show_progress() {
+        for failure in &failures {
+            println!(
+                "{}",
+                failure_style().paint(format!("\t{} {failure}", Marker::Fail))
+            );
+        }
+    }
+    Ok(failures.is_empty())
+}
+
+fn verify(
+    exercises: &ExerciseCollection,
+    definition: &ExerciseDefinition,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+) -> Result<TestOutcome, anyhow::Error> {
+    let steps = verification_commands(exercises, definition, configuration, verbose)?;
+    // Stages are listed one by one only if the collection gave them a name:
+    // `build` followed by `tests` is not worth mentioning.
+    let stages: Vec<_> = if steps.iter().any(|s| s.named) {
+        steps.iter().map(|s| s.name.clone()).collect()
+    } else {
+        vec![]
+    };
+    // We take the fingerprint before running anything, in case the sources change in the meantime.
+    let fingerprint = verification_fingerprint(exercises, definition, configuration)?;
+    let start = std::time::Instant::now();
+    // The hidden tests, if any, are only around while the exercise is verified.
+    let exercise_dir = definition.manifest_folder_path(exercises.exercises_dir_of(definition));
+    let hidden_tests = wr::hidden_tests::inject(&exercise_dir)
+        .with_context(|| format!("Failed to set up the hidden tests for {definition}"))?;
+    let (exercise_outcome, n_passed) = _verify(definition, steps);
+    drop(hidden_tests);
+    let failed_command = match &exercise_outcome {
+        TestOutcome::Success => None,
+        TestOutcome::Failure { command, .. } => Some(command.as_str()),
+    };
+    let duration = start.elapsed();
+    exercises.record_run(definition, duration, failed_command)?;
+    record_in_report(definition, duration, &exercise_outcome)?;
+    log(&wr::log::Event::Verification {
+        exercise: &definition.path(),
+        passed: failed_command.is_none(),
+        failed_stage: match &exercise_outcome {
+            TestOutcome::Success => None,
+            TestOutcome::Failure { stage, .. } => Some(stage),
+        },
+        duration_ms: duration.as_millis(),
+    });
+    let mut broken = false;
+    match exercise_outcome.failure() {
+        None => {
+            if show_progress() {
+                println!(
+                    "{}",
+                    success_style().paint(format!("\t{} {}", Marker::Pass, definition))
+                );
+            }
+            exercises.mark_as_solved(definition)?;
+            exercises.record_source_hash(definition, &fingerprint)?;
+        }
+        Some(failure) => {
+            if show_progress() {
+                let exercise_dir =
+                    definition.manifest_folder_path(exercises.exercises_dir_of(definition));
+                println!(
+                    "{}",
+                    failure_style().paint(format!(
+                        "\t{} {}",
+                        Marker::Fail,
+                        link_to(&exercise_dir, &definition.to_string())
+                    ))
+                );
+            } else if !json_output() {
+                // The output of the failed command is left out: this is all there is to see.
+                println!(
+                    "{}",
+                    failure_style().paint(format!(
+                        "\t{} {definition}: {}",
+                        Marker::Fail,
+                        catalog().failed_at_stage(&failure.stage)
+                    ))
+                );
+            }
+            broken = exercises.mark_as_unsolved(definition, &failure)?;
+        }
+    }
+    if !show_progress() {
+        return Ok(exercise_outcome);
+    }
+    for (i, stage) in stages.iter().enumerate() {
+        let line = match i.cmp(&n_passed) {
+            std::cmp::Ordering::Less => {
+                success_style().paint(format!("\t    {} {stage}", Marker::StagePassed))
+            }
+            std::cmp::Ordering::Equal => {
+                failure_style().paint(format!("\t    {} {stage}", Marker::StageFailed))
+            }
+            // The marker already says it, with `--accessible`.
+            std::cmp::Ordering::Greater if accessible() => {
+                cargo_style().paint(format!("\t    {} {stage}", Marker::StageSkipped))
+            }
+            std::cmp::Ordering::Greater => {
+                cargo_style().paint(format!("\t    {} {stage} (skipped)", Marker::StageSkipped))
+            }
+        };
+        println!("{line}");
+    }
+    if broken {
+        let solved_at = exercises
+            .events(Some(definition))?
+            .iter()
+            .rev()
+            .find(|e| e.kind == wr::progress::EventKind::Solved)
+            .map(|e| {
+                format!(
+                    " {} ago",
+                    format_duration(wr::unix_timestamp() - e.occurred_at)
+                )
+            })
+            .unwrap_or_default();
+        println!(
+            "{}",
+            next_style().paint(format!(
+                "\t{} You had solved {definition}{solved_at}: something you changed since then broke it.",
+                Marker::Warning
+            ))
+        );
+    }
+    Ok(exercise_outcome)
+}
+
+/// The environment variable that makes Cargo run the binaries it builds for an exercise
+/// (tests included) through `wr __limited`, so that `limits` apply to them alone.
+///
+/// The runner the exercise specifies for its target, if any, is run by `wr __limited` in turn.
+fn limited_runner_env(
+    exercise_config: Option<&ExerciseConfig>,
+    toolchain: Option<&str>,
+    limits: wr::process::ResourceLimits,
+) -> Result<(String, String), anyhow::Error> {
+    let target = match exercise_config.and_then(|c| c.target.clone()) {
+        Some(target) => target,
+        None => host_triple(toolchain)?,
+    };
+    // Cargo splits the runner on whitespace: if our path contains some, we can only hope
+    // that `wr` is on the `PATH`.
+    let wr = std::env::current_exe()
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+        .filter(|path| !path.contains(char::is_whitespace))
+        .unwrap_or_else(|| "wr".to_owned());
+    let mut runner = format!("{wr} __limited");
+    if let Some(memory_bytes) = limits.memory_bytes {
+        runner.push_str(&format!(" --memory-bytes {memory_bytes}"));
+    }
+    if let Some(cpu_time) = limits.cpu_time {
+        runner.push_str(&format!(" --cpu-time-secs {}", cpu_time.as_secs().max(1)));
+    }
+    runner.push_str(" --");
+    if let Some((_, target_runner)) = exercise_config.and_then(ExerciseConfig::target_runner_env) {
+        runner.push(' ');
+        runner.push_str(target_runner);
+    }
+    Ok((wr::runner_env_var(&target), runner))
+}
+
+/// The target triple of the host, e.g. `x86_64-unknown-linux-gnu`, according to `rustc`.
+fn host_triple(toolchain: Option<&str>) -> Result<String, anyhow::Error> {
+    static HOST: OnceLock<String> = OnceLock::new();
+    if let Some(host) = HOST.get() {
+        return Ok(host.clone());
+    }
+    let mut rustc = std::process::Command::new("rustc");
+    if let Some(toolchain) = toolchain {
+        rustc.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    let output = rustc
+        .arg("-vV")
+        .output()
+        .context("Failed to run `rustc -vV` to determine the host platform")?;
+    let host = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(str::to_owned)
+        .context("Failed to determine the host platform from the output of `rustc -vV`")?;
+    Ok(HOST.get_or_init(|| host).clone())
+}
+
+/// A `cargo` command, using `toolchain` rather than the default one, if specified.
+fn cargo(toolchain: Option<&str>) -> std::process::Command {
+    let mut cmd = std::process::Command::new("cargo");
+    if let Some(toolchain) = toolchain {
+        cmd.arg(format!("+{toolchain}"));
+    }
+    // The progress bar of `cargo build` is redrawn in place, which screen readers can't follow.
+    if accessible() {
+        cmd.env("CARGO_TERM_PROGRESS_WHEN", "never");
+    }
+    cmd
+}
+
+/// A fingerprint of everything that determines the outcome of verifying an exercise:
+/// its sources (and the ones of its path dependencies), its configuration, the one of the
+/// collection and the version of `wr`.
+fn verification_fingerprint(
+    exercises: &ExerciseCollection,
+    definition: &ExerciseDefinition,
+    configuration: &ExercisesConfig,
+) -> Result<String, anyhow::Error> {
+    use sha2::{Digest, Sha256};
+
+    let exercises_dir = exercises.exercises_dir_of(definition);
+    let exercise_dir = fs_err::canonicalize(definition.manifest_folder_path(exercises_dir))?;
+    let mut hasher = Sha256::new();
+    hasher.update(definition.source_hash(exercises_dir)?);
+    for dependency in wr::path_dependencies(&definition.manifest_path(exercises_dir))? {
+        // The ones in the exercise folder are part of its sources already.
+        if !dependency.starts_with(&exercise_dir) {
+            hasher.update(wr::hash_sources(&dependency)?);
+        }
+    }
+    // The settings as `wr` sees them, with the chapter-level configuration, the included files
+    // and the `WR_*` environment variables applied.
+    let exercise_settings = definition
+        .config(exercises_dir)?
+        .map(|c| c.verification_settings());
+    hasher.update(serde_json::to_string(&exercise_settings)?);
+    hasher.update(serde_json::to_string(
+        &configuration.verification_settings(),
+    )?);
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// `true` if nothing changed since the last time the exercise was solved, see [`verification_fingerprint`].
+fn is_unchanged(
+    exercises: &ExerciseCollection,
+    definition: &ExerciseDefinition,
+    configuration: &ExercisesConfig,
+) -> Result<bool, anyhow::Error> {
+    let Some(recorded) = exercises.source_hash(definition)? else {
+        return Ok(false);
+    };
+    Ok(recorded == verification_fingerprint(exercises, definition, configuration)?)
+}
+
+/// A command that must succeed for an exercise to be considered solved.
+struct VerificationStep {
+    /// The name of the stage, e.g. `build` or `tests`.
+    name: String,
+    /// `true` if the name was chosen by the author of the collection, rather than by `wr`.
+    named: bool,
+    command: std::process::Command,
+    /// How long the command is allowed to run, if there is a time limit.
+    timeout: Option<std::time::Duration>,
+    /// A message appended to the output of the command if it fails, to help the user fix it.
+    hint: Option<String>,
+    /// What the command must print to stdout, if it matters.
+    expected_stdout: Option<ExpectedOutput>,
+    /// The input piped into the command, if any.
+    stdin: Option<Vec<u8>>,
+    /// Set if the command is a build that must fail, rather than succeed.
+    compile_fail: Option<CompileFail>,
+    /// How many times the command is run again if it fails, before giving up.
+    retries: u32,
+    /// A pattern that stdout must match, if it matters.
+    stdout_regex: Option<regex::Regex>,
+    /// A string that stderr must contain, if it matters.
+    stderr_contains: Option<String>,
+    /// The benchmarks, reported by the command, that must run fast enough.
+    benchmarks: Vec<wr::bench::BenchmarkThreshold>,
+    /// The resources the code under test is allowed to use, if it runs in a sandbox.
+    /// Otherwise, they are enforced by `wr __limited`.
+    limits: wr::process::ResourceLimits,
+    /// The name of the container the command runs in, if it's sandboxed.
+    container: Option<String>,
+}
+
+impl VerificationStep {
+    fn new(name: impl Into<String>, command: std::process::Command) -> Self {
+        Self {
+            name: name.into(),
+            named: false,
+            command,
+            timeout: None,
+            hint: None,
+            expected_stdout: None,
+            stdin: None,
+            compile_fail: None,
+            retries: 0,
+            stdout_regex: None,
+            stderr_contains: None,
+            benchmarks: vec![],
+            limits: Default::default(),
+            container: None,
+        }
+    }
+}
+
+/// The compilation errors expected from a `compile-fail` exercise.
+struct CompileFail {
+    /// The error code the compiler must emit, e.g. `E0502`. Any error will do if `None`.
+    error_code: Option<String>,
+}
+
+impl CompileFail {
+    /// Check the output of `cargo build --message-format=json`, returning an explanation
+    /// if the build didn't fail as expected.
+    fn check(&self, output: &std::process::Output) -> Result<(), String> {
+        if output.status.success() {
+            return Err("The code compiles, but it's expected not to.".into());
+        }
+        let Some(error_code) = &self.error_code else {
+            return Ok(());
+        };
+        let mut codes = vec![];
+        let mut rendered = String::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Ok(message) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+            if message["reason"] != "compiler-message" || message["message"]["level"] != "error" {
+                continue;
+            }
+            if let Some(code) = message["message"]["code"]["code"].as_str() {
+                codes.push(code.to_owned());
+            }
+            if let Some(text) = message["message"]["rendered"].as_str() {
+                rendered.push_str(text);
+            }
+        }
+        if codes.contains(error_code) {
+            Ok(())
+        } else {
+            Err(format!(
+                "The code doesn't compile, as expected, but the compiler didn't emit `{error_code}`:\n\n{rendered}"
+            ))
+        }
+    }
+}
+
+/// The output that a verification command is expected to print.
+struct ExpectedOutput {
+    contents: String,
+    /// Ignore trailing whitespace and leading/trailing empty lines when comparing.
+    normalize: bool,
+}
+
+impl ExpectedOutput {
+    /// Compare `actual` with the expected output, returning a diff if they don't match.
+    fn diff(&self, actual: &str) -> Option<String> {
+        let (expected, actual) = if self.normalize {
+            (
+                wr::normalize_output(&self.contents),
+                wr::normalize_output(actual),
+            )
+        } else {
+            (self.contents.clone(), actual.to_owned())
+        };
+        if expected == actual {
+            return None;
+        }
+        Some(render_diff(&expected, &actual))
+    }
+}
+
+/// A line-by-line diff of `old` and `new`: removed lines start with `-`, added ones with `+`.
+///
+/// The words that changed within a line are emphasized, see [`emphasize_change`].
+fn render_diff(old: &str, new: &str) -> String {
+    let diff = similar::TextDiff::from_lines(old, new);
+    let mut rendered = String::new();
+    for op in diff.ops() {
+        for change in diff.iter_inline_changes(op) {
+            let (sign, style) = match change.tag() {
+                similar::ChangeTag::Delete => ("-", failure_style()),
+                similar::ChangeTag::Insert => ("+", success_style()),
+                similar::ChangeTag::Equal => (" ", yansi::Style::default()),
+            };
+            rendered.push_str(&style.paint(sign).to_string());
+            for (emphasized, value) in change.iter_strings_lossy() {
+                let value = value.trim_end_matches('\n');
+                if value.is_empty() {
+                    continue;
+                }
+                if emphasized {
+                    rendered.push_str(&emphasize_change(change.tag(), value));
+                } else {
+                    rendered.push_str(&style.paint(value).to_string());
+                }
+            }
+            rendered.push('\n');
+        }
+    }
+    rendered
+}
+
+/// Emphasize `value`, which was removed or added, in bold and underlined red or green.
+///
+/// Without colours, e.g. with `--color never` or `--accessible`, it's marked with text instead:
+/// `[-removed-]` or `{+added+}`.
+fn emphasize_change(tag: similar::ChangeTag, value: &str) -> String {
+    let markers = accessible() || !use_ansi_colours();
+    match tag {
+        similar::ChangeTag::Delete if markers => format!("[-{value}-]"),
+        similar::ChangeTag::Insert if markers => format!("{{+{value}+}}"),
+        similar::ChangeTag::Delete => failure_style().bold().underline().paint(value).to_string(),
+        similar::ChangeTag::Insert => success_style().bold().underline().paint(value).to_string(),
+        similar::ChangeTag::Equal => value.to_owned(),
+    }
+}
+
+/// Emphasize what differs between the two sides of the failed `assert_eq!`s in `output`.
+///
+/// Strings that span several lines are compared line by line too, see [`render_diff`].
+fn highlight_assertions(output: &str) -> String {
+    let mut assertions = wr::assertions::find(output).into_iter().peekable();
+    if assertions.peek().is_none() {
+        return output.to_owned();
+    }
+    let mut highlighted = String::new();
+    let mut lines = output.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        let Some(assertion) = assertions.next_if(|a| a.line == i) else {
+            highlighted.push_str(line);
+            highlighted.push('\n');
+            continue;
+        };
+        // Skip the `right: ` line, it's replaced too.
+        lines.next();
+        let (mut left, mut right) = (String::new(), String::new());
+        let diff = similar::TextDiff::from_words(&assertion.left, &assertion.right);
+        // Consecutive words that changed are emphasized together, e.g. `[-two words-]`.
+        for op in diff.ops() {
+            let (mut removed, mut added) = (String::new(), String::new());
+            for change in diff.iter_changes(op) {
+                let value = change.value();
+                match change.tag() {
+                    similar::ChangeTag::Delete => removed.push_str(value),
+                    similar::ChangeTag::Insert => added.push_str(value),
+                    similar::ChangeTag::Equal => {
+                        left.push_str(&failure_style().paint(value).to_string());
+                        right.push_str(&success_style().paint(value).to_string());
+                    }
+                }
+            }
+            if !removed.is_empty() {
+                left.push_str(&emphasize_change(similar::ChangeTag::Delete, &removed));
+            }
+            if !added.is_empty() {
+                right.push_str(&emphasize_change(similar::ChangeTag::Insert, &added));
+            }
+        }
+        highlighted.push_str(&format!("  left: {left}\n right: {right}\n"));
+        if let Some((left, right)) = assertion.multiline_strings() {
+            highlighted.push_str(&format!(
+                "\nDiff (- left, + right):\n{}\n",
+                render_diff(&left, &right)
+            ));
+        }
+    }
+    highlighted
+}
+
+/// The commands that must succeed for an exercise to be considered solved, in the order
+/// they should be run: `cargo build` first, then the verification commands.
+///
+/// They run inside a container if the collection asks for a sandbox.
+fn verification_commands(
+    exercises: &ExerciseCollection,
+    definition: &ExerciseDefinition,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+) -> Result<Vec<VerificationStep>, anyhow::Error> {
+    let mut steps = _verification_commands(exercises, definition, configuration, verbose)?;
+    if let Some(wr::sandbox::Sandbox::Docker) = configuration.sandbox() {
+        let root_dir = wr::get_git_repository_root_dir()?;
+        for step in &mut steps {
+            let container = wr::sandbox::container_name();
+            step.command = wr::sandbox::wrap(
+                &step.command,
+                &container,
+                &root_dir,
+                configuration.sandbox_image(),
+                configuration.sandbox_args(),
+                step.limits,
+            )?;
+            step.container = Some(container);
+        }
+        // The build logs are shown as they come in verbose mode.
+        if let Some(step) = steps.first_mut().filter(|s| verbose && s.name == "build") {
+            step.command
+                .stdout(std::process::Stdio::inherit())
+                .stderr(std::process::Stdio::inherit());
+        }
+    }
+    Ok(steps)
+}
+
+fn _verification_commands(
+    exercises: &ExerciseCollection,
+    definition: &ExerciseDefinition,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+) -> Result<Vec<VerificationStep>, anyhow::Error> {
+    let exercise_config = definition.config(exercises.exercises_dir_of(definition))?;
+    // Exercise-specific config takes precedence over the global one, if specified.
+    let verification = configuration.verification_for(exercise_config.as_ref());
+    let manifest_path = definition.manifest_path(exercises.exercises_dir_of(definition));
+    let exercise_dir = manifest_path
+        .parent()
+        .expect("Failed to get parent dir for manifest");
+
+    let toolchain = exercise_config
+        .as_ref()
+        .and_then(|c| c.toolchain.as_deref());
+    // The feature flags are passed to every `cargo` command we run on the author's behalf,
+    // and so is the target, if it isn't the host (with the exception of Miri).
+    let feature_args = exercise_config
+        .as_ref()
+        .map(ExerciseConfig::feature_args)
+        .unwrap_or_default();
+    let target_args = exercise_config
+        .as_ref()
+        .map(ExerciseConfig::target_args)
+        .unwrap_or_default();
+    let runnable = exercise_config
+        .as_ref()
+        .is_none_or(ExerciseConfig::runnable);
+    let runner_env = exercise_config
+        .as_ref()
+        .and_then(ExerciseConfig::target_runner_env);
+
+    // Warnings are turned into errors via `RUSTFLAGS`, if requested.
+    // We set it for all the `cargo` commands sharing the same build cache (`build`, `test`
+    // and `run`), otherwise they would invalidate each other's artifacts.
+    let rustflags = configuration
+        .deny_warnings(exercise_config.as_ref())
+        .then(|| {
+            let mut flags = std::env::var_os("RUSTFLAGS").unwrap_or_default();
+            if !flags.is_empty() {
+                flags.push(" ");
+            }
+            flags.push("-D warnings");
+            ("RUSTFLAGS", flags)
+        });
+
+    // Tell cargo to return colored output, unless we are on Windows and the terminal
+    // doesn't support it.
+    let color_option = if use_ansi_colours() {
+        "always"
+    } else {
+        "never"
+    };
+
+    // `cargo build` first
+    let mut build_cmd = cargo(toolchain);
+    build_cmd.arg("build");
+    build_cmd.arg("--manifest-path");
+    build_cmd.arg(&manifest_path);
+    build_cmd.arg("--all-targets");
+    build_cmd.args(&feature_args);
+    build_cmd.args(&target_args);
+    build_cmd.envs(rustflags.clone());
+    build_cmd.arg("--color");
+    build_cmd.arg(color_option);
+    if !verbose {
+        build_cmd.arg("-q");
+    }
+    // A `compile-fail` exercise is solved if the build fails, with the expected error:
+    // there is nothing else we can run.
+    if let Some(c) = exercise_config
+        .as_ref()
+        .filter(|c| c.expect == Expect::CompileFail)
+    {
+        build_cmd.arg(if use_ansi_colours() {
+            "--message-format=json-diagnostic-rendered-ansi"
+        } else {
+            "--message-format=json"
+        });
+        let mut step = VerificationStep::new("build", build_cmd);
+        step.compile_fail = Some(CompileFail {
+            error_code: c.expected_error.clone(),
+        });
+        return Ok(vec![step]);
+    }
+
+    let mut steps = vec![];
+    if !configuration.skip_build(exercise_config.as_ref()) {
+        // The collection may know better than us how to build its exercises.
+        if let Some(build) = configuration.build(exercise_config.as_ref()) {
+            build_cmd = build.command();
+            build_cmd.current_dir(exercise_dir);
+            if let Some(toolchain) = toolchain {
+                build_cmd.env("RUSTUP_TOOLCHAIN", toolchain);
+            }
+        }
+        if verbose {
+            build_cmd
+                .stdout(std::process::Stdio::inherit())
+                .stderr(std::process::Stdio::inherit());
+        }
+        // Compilation can legitimately take a while, so we don't put a time limit on it.
+        steps.push(VerificationStep::new("build", build_cmd));
+    }
+
+    // Then `cargo fmt --check`, if the collection asks for it.
+    if configuration.fmt_check(exercise_config.as_ref()) {
+        let mut cmd = cargo(toolchain);
+        cmd.args(["fmt", "--check", "--", "--color", color_option]);
+        cmd.current_dir(exercise_dir);
+        let mut step = VerificationStep::new("fmt", cmd);
+        step.named = true;
+        step.hint = Some(format!(
+            "Your code is not formatted as expected: run `cargo fmt` in {exercise_dir:?} to fix it."
+        ));
+        steps.push(step);
+    }
+
+    // Then `cargo clippy`, if the collection asks for it.
+    if configuration.clippy(exercise_config.as_ref()) {
+        let mut cmd = cargo(toolchain);
+        cmd.args(["clippy", "--all-targets", "--color", color_option]);
+        cmd.args(&feature_args);
+        cmd.args(&target_args);
+        if !verbose {
+            cmd.arg("-q");
+        }
+        cmd.args(["--", "-D", "warnings"]);
+        cmd.current_dir(exercise_dir);
+        let mut step = VerificationStep::new("clippy", cmd);
+        step.named = true;
+        steps.push(step);
+    }
+
+    // Then the verification commands.
+    let mut verification_steps = verification
+        .iter()
+        .map(|v| -> Result<_, anyhow::Error> {
+            let mut cmd = v.command();
+            // Custom commands may invoke `cargo` in all sorts of ways, so we rely on `rustup`
+            // picking the toolchain up from the environment.
+            if let Some(toolchain) = toolchain {
+                cmd.env("RUSTUP_TOOLCHAIN", toolchain);
+            }
+            let mut step = VerificationStep::new(v.stage_name(), cmd);
+            step.named = v.name.is_some();
+            step.timeout = v
+                .timeout_secs
+                .map(std::time::Duration::from_secs)
+                .or(configuration.timeout());
+            step.retries = v.retries;
+            step.stdout_regex = v
+                .expect_stdout_regex
+                .as_deref()
+                .map(regex::Regex::new)
+                .transpose()
+                .with_context(|| {
+                    format!(
+                        "Invalid `expect_stdout_regex` for the `{}` stage",
+                        step.name
+                    )
+                })?;
+            step.stderr_contains.clone_from(&v.expect_stderr_contains);
+            Ok(step)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let test_filter = exercise_config
+        .as_ref()
+        .and_then(|c| c.test_filter.as_deref());
+    // Tests can't be run for targets without a runner: building them is all we can do.
+    if verification_steps.is_empty() && runnable {
+        let doc_tests = exercise_config.as_ref().is_some_and(|c| c.doc_tests);
+        // `nextest` can't run doc tests, so we stick to `cargo test` for them.
+        let nextest = !doc_tests && configuration.test_runner() == TestRunner::Nextest;
+        let mut args: Vec<OsString> = if nextest {
+            // Exercises without tests are fine, they may be verified by other means.
+            vec!["nextest".into(), "run".into(), "--no-tests=pass".into()]
+        } else {
+            vec!["test".into()]
+        };
+        args.extend(["--color".into(), color_option.into()]);
+        if doc_tests {
+            args.push("--doc".into());
+        }
+        args.extend(feature_args.iter().chain(&target_args).map(OsString::from));
+        args.extend(test_filter.map(OsString::from));
+
+        if !verbose {
+            if nextest {
+                // Only report failing tests, without a progress bar.
+                args.extend(
+                    [
+                        "--cargo-quiet",
+                        "--hide-progress-bar",
+                        "--status-level",
+                        "fail",
+                        "--final-status-level",
+                        "fail",
+                    ]
+                    .map(OsString::from),
+                );
+            } else {
+                args.push("-q".into());
+            }
+        }
+
+        let mut cmd = cargo(toolchain);
+        cmd.args(args);
+        cmd.envs(runner_env.clone());
+        cmd.envs(rustflags.clone());
+        let mut step = VerificationStep::new(if doc_tests { "doctests" } else { "tests" }, cmd);
+        step.named = doc_tests;
+        step.timeout = configuration.timeout();
+        verification_steps.push(step);
+    }
+
+    // Then the tests again, under Miri, to catch undefined behaviour.
+    if exercise_config.as_ref().is_some_and(|c| c.miri) {
+        // Miri is only available on nightly.
+        let mut cmd = cargo(toolchain.or(Some("nightly")));
+        cmd.args(["miri", "test", "--color", color_option]);
+        cmd.args(&feature_args);
+        cmd.args(test_filter);
+        if !verbose {
+            cmd.arg("-q");
+        }
+        let mut step = VerificationStep::new("miri", cmd);
+        step.named = true;
+        step.timeout = configuration.timeout();
+        verification_steps.push(step);
+    }
+
+    // Then the benchmarks, if they are held to a threshold.
+    if let Some(c) = exercise_config
+        .as_ref()
+        .filter(|c| !c.benchmarks.is_empty())
+    {
+        let mut cmd = cargo(toolchain);
+        cmd.args(["bench", "--color", color_option]);
+        cmd.args(&feature_args);
+        if !verbose {
+            cmd.arg("-q");
+        }
+        let mut step = VerificationStep::new("bench", cmd);
+        step.named = true;
+        step.timeout = configuration.timeout();
+        step.benchmarks.clone_from(&c.benchmarks);
+        verification_steps.push(step);
+    }
+
+    // Finally, compare the output of the exercise binary with the expected one, if provided.
+    let expected_output_path = exercise_config
+        .as_ref()
+        .and_then(|c| c.expected_output.as_ref())
+        .map(|path| exercise_dir.join(path))
+        .or_else(|| Some(exercise_dir.join("expected_output.txt")).filter(|p| p.exists()));
+    if let Some(expected_output_path) = expected_output_path.filter(|_| runnable) {
+        let contents = fs_err::read_to_string(&expected_output_path)?;
+        let mut cmd = cargo(toolchain);
+        cmd.args(["run", "-q", "--color", color_option]);
+        cmd.args(&feature_args);
+        cmd.args(&target_args);
+        cmd.envs(runner_env);
+        cmd.envs(rustflags);
+        let mut step = VerificationStep::new("output", cmd);
+        step.named = true;
+        step.timeout = configuration.timeout();
+        step.expected_stdout = Some(ExpectedOutput {
+            contents,
+            normalize: exercise_config.as_ref().is_some_and(|c| c.normalize_output),
+        });
+        verification_steps.push(step);
+    }
+
+    // The input fixture, if any, is piped into every verification command.
+    let stdin = match exercise_config.as_ref() {
+        Some(c) if c.stdin.is_some() => c.stdin.clone().map(String::into_bytes),
+        Some(c) => match &c.stdin_file {
+            Some(path) => Some(fs_err::read(exercise_dir.join(path))?),
+            None => None,
+        },
+        None => None,
+    };
+    // Building is fine: it's the code under test we don't trust. Cargo runs it (tests, binaries
+    // and benchmarks alike) through `wr __limited`, which limits its resources and nothing else.
+    // In a sandbox, it's the container that is limited instead, see `verification_commands`.
+    let limits = configuration.resource_limits();
+    let limited_runner_env = (!limits.is_empty() && configuration.sandbox().is_none())
+        .then(|| limited_runner_env(exercise_config.as_ref(), toolchain, limits))
+        .transpose()?;
+    verification_steps.iter_mut().for_each(|step| {
+        // We run verification commands from the exercise's directory.
+        step.command.current_dir(exercise_dir);
+        step.stdin.clone_from(&stdin);
+        step.command.envs(limited_runner_env.clone());
+        step.limits = limits;
+    });
+    steps.extend(verification_steps);
+    Ok(steps)
+}
+
+/// Run the verification stages in order, stopping at the first failure.
+///
+/// It returns the outcome alongside the number of stages that passed.
+fn _verify(definition: &ExerciseDefinition, steps: Vec<VerificationStep>) -> (TestOutcome, usize) {
+    let n_steps = steps.len();
+    let exercise = definition.path();
+    let run_and_log = |step: &mut VerificationStep| {
+        let start = std::time::Instant::now();
+        let result = run_step(step);
+        log(&wr::log::Event::Command {
+            exercise: &exercise,
+            stage: &step.name,
+            command: &format!("{:?}", step.command),
+            passed: result.is_ok(),
+            duration_ms: start.elapsed().as_millis(),
+        });
+        result
+    };
+    for (i, mut step) in steps.into_iter().enumerate() {
+        if verbosity() >= Verbosity::Trace && !json_output() {
+            // The working directory and the environment variables we set come first.
+            println!(
+                "{}",
+                cargo_style().paint(format!("\t    $ {:?}", step.command))
+            );
+        }
+        let mut n_attempts = 1;
+        let mut result = run_and_log(&mut step);
+        // Flaky commands get a few more chances before we give up on them.
+        while result.is_err() && n_attempts <= step.retries {
+            if show_progress() {
+                println!(
+                    "{}",
+                    cargo_style().paint(format!(
+                        "\t    {} {} failed, retrying ({n_attempts}/{})",
+                        Marker::Retry,
+                        step.name,
+                        step.retries
+                    ))
+                );
+            }
+            n_attempts += 1;
+            result = run_and_log(&mut step);
+        }
+        if let Err(mut details) = result {
+            if n_attempts > 1 {
+                details.extend_from_slice(
+                    format!("\nFailed {n_attempts} times in a row.\n").as_bytes(),
+                );
+            }
+            let failure = TestOutcome::Failure {
+                stage: step.name,
+                command: format!("{:?}", step.command),
+                details,
+            };
+            return (failure, i);
+        }
+    }
+
+    (TestOutcome::Success, n_steps)
+}
+
+/// Run a single verification stage, returning what went wrong if it failed.
+fn run_step(step: &mut VerificationStep) -> Result<(), Vec<u8>> {
+    let VerificationStep {
+        command: cmd,
+        timeout,
+        hint,
+        expected_stdout,
+        stdin,
+        compile_fail,
+        stdout_regex,
+        stderr_contains,
+        benchmarks,
+        container,
+        ..
+    } = step;
+    let error_msg = format!("Failed to run: `{:?}`", cmd);
+    // The output of a `compile-fail` build is JSON, meant for us rather than for learners.
+    let stream = (stream_output() && compile_fail.is_none()).then(|| {
+        if json_output() {
+            wr::process::Stream::Stderr
+        } else {
+            wr::process::Stream::Terminal
+        }
+    });
+    let output = wr::process::output(cmd, stdin.as_deref(), *timeout, stream).expect(&error_msg);
+    if output.timed_out {
+        if let Some(container) = container {
+            wr::sandbox::kill(container);
+        }
+        let mut details = [output.output.stderr, output.output.stdout].concat();
+        details.extend_from_slice(
+            format!(
+                "\nTimed out after {}s\n",
+                timeout.unwrap_or_default().as_secs()
+            )
+            .as_bytes(),
+        );
+        return Err(details);
+    }
+    let output = output.output;
+
+    if let Some(compile_fail) = compile_fail {
+        return compile_fail.check(&output).map_err(String::into_bytes);
+    }
+
+    if !output.status.success() {
+        let mut details = [output.stderr, output.stdout].concat();
+        if let Some(hint) = hint {
+            details.extend_from_slice(format!("\n{hint}\n").as_bytes());
+        }
+        return Err(details);
+    }
+
+    if let Some(regex) = stdout_regex {
+        if !regex.is_match(&String::from_utf8_lossy(&output.stdout)) {
+            let mut details = format!("The output doesn't match `{regex}`:\n\n").into_bytes();
+            details.extend_from_slice(&output.stdout);
+            return Err(details);
+        }
+    }
+    if let Some(expected) = stderr_contains {
+        if !String::from_utf8_lossy(&output.stderr).contains(expected.as_str()) {
+            let mut details =
+                format!("The error output doesn't contain `{expected}`:\n\n").into_bytes();
+            details.extend_from_slice(&output.stderr);
+            return Err(details);
+        }
+    }
+
+    for benchmark in benchmarks.iter() {
+        benchmark
+            .check(&String::from_utf8_lossy(&output.stdout))
+            .map_err(|e| [e.as_bytes(), b"\n\n", &output.stdout].concat())?;
+    }
+
+    if let Some(expected_stdout) = expected_stdout {
+        if let Some(diff) = expected_stdout.diff(&String::from_utf8_lossy(&output.stdout)) {
+            return Err(format!(
+                "The output doesn't match the expected one (- expected, + actual):\n\n{diff}"
+            )
+            .into_bytes());
+        }
+    }
+    Ok(())
+}
+
+/// Print which exercises would be verified, in which order, and the commands that would be run
+/// for each of them, without running anything.
+///
+/// `next` is the exercise that would be opened once they pass, with `--keep-going`.
+fn print_plan(
+    exercises: &ExerciseCollection,
+    planned: &[ExerciseDefinition],
+    next: Option<&ExerciseDefinition>,
+    configuration: &ExercisesConfig,
+    verbose: bool,
+) -> Result<(), anyhow::Error> {
+    if planned.is_empty() && next.is_none() {
+        println!(
+            "{}",
+            info_style().paint("\n\tThere are no exercises to verify.")
+        );
+        return Ok(());
+    }
+    println!(
+        "{}",
+        next_style().paint("\n\tThe following exercises would be verified, in this order:")
+    );
+    for (i, definition) in planned.iter().chain(next).enumerate() {
+        let opened = match next {
+            Some(next) if next == definition && planned.is_empty() => cargo_style()
+                .paint(" (it would be opened first)")
+                .to_string(),
+            Some(next) if next == definition => cargo_style()
+                .paint(" (it would be opened first, if the exercises above pass)")
+                .to_string(),
+            _ => String::new(),
+        };
+        println!(
+            "\n\t{}. {}{opened}",
+            i + 1,
+            info_style().bold().paint(definition)
+        );
+        for step in verification_commands(exercises, definition, configuration, verbose)? {
+            let timeout = step
+                .timeout
+                .map(|t| format!(" (timeout: {}s)", t.as_secs()))
+                .unwrap_or_default();
+            let stdin = step
+                .stdin
+                .as_ref()
+                .map(|input| format!(" (stdin: {} bytes)", input.len()))
+                .unwrap_or_default();
+            let retries = if step.retries > 0 {
+                format!(" (retries: {})", step.retries)
+            } else {
+                String::new()
+            };
+            println!(
+                "\t   {} {}",
+                info_style().paint(format!("[{}]", step.name)),
+                cargo_style().paint(format!("$ {:?}{timeout}{retries}{stdin}", step.command))
+            );
+        }
+    }
+    Ok(())
+}
+
+#[derive(PartialEq)]
+enum TestOutcome {
+    Success,
+    Failure {
+        /// The name of the verification stage that failed.
+        stage: String,
+        command: String,
+        details: Vec<u8>,
+    },
+}
+
+impl TestOutcome {
+    /// What went wrong, or `None` if the verification succeeded.
+    fn failure(&self) -> Option<LastFailure> {
+        match self {
+            TestOutcome::Success => None,
+            TestOutcome::Failure {
+                stage,
+                command,
+                details,
+            } => Some(LastFailure {
+                stage: stage.clone(),
+                command: command.clone(),
+                output: String::from_utf8_lossy(details).into_owned(),
+            }),
+        }
+    }
+}
+
+fn print_opened_message(exercise: &ExerciseDefinition, exercises_dir: &Path) {
+    // The exercise stands out from the rest of the message.
+    let exercise_name = exercise.to_string();
+    let message = messages().opened(exercise);
+    let message: Vec<_> = message
+        .split(&exercise_name)
+        .map(|part| next_style().paint(part).to_string())
+        .collect();
+    let relative_path = exercise.manifest_folder_path(exercises_dir);
+    println!(
+        "\n\t{}",
+        message.join(
+            &next_style()
+                .bold()
+                .paint(link_to(&relative_path, &exercise_name))
+                .to_string()
+        )
+    );
+    let open_msg = format!(
+        "\n\t{}\n\t{}",
+        catalog().get_started(&link_to(&relative_path, &format!("{relative_path:?}"))),
+        catalog().run_again()
+    );
+    println!("{}", next_style().paint(open_msg));
+}
+
+/// Keep track of the problems found by `wr doctor`.
+#[derive(Default)]
+struct Diagnosis {
+    n_problems: usize,
+}
+
+impl Diagnosis {
+    fn ok(&self, msg: impl std::fmt::Display) {
+        println!(
+            "{}",
+            success_style().paint(format!("\t{} {msg}", Marker::Ok))
+        );
+    }
+
+    fn problem(&mut self, msg: impl std::fmt::Display, fix: impl std::fmt::Display) {
+        self.n_problems += 1;
+        println!(
+            "{}",
+            failure_style().paint(format!("\t{} {msg}", Marker::Fail))
+        );
+        println!("{}", cargo_style().paint(format!("\t   {fix}")));
+    }
+}
+
+/// Check the configuration files of the collection, returning `true` if they are all valid.
+fn config_validate() -> Result<bool, anyhow::Error> {
+    let root_dir = wr::get_git_repository_root_dir()
+        .context("Failed to determine the root path of the current `git` repository")?;
+    let diagnostics = wr::validate::validate(&root_dir)?;
+    if json_output() {
+        let problems: Vec<_> = diagnostics
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "path": d.path,
+                    "line": d.location.map(|(line, _)| line),
+                    "column": d.location.map(|(_, column)| column),
+                    "message": d.message,
+                })
+            })
+            .collect();
+        print_json(&serde_json::json!({
+            "valid": diagnostics.is_empty(),
+            "problems": problems,
+        }))?;
+        return Ok(diagnostics.is_empty());
+    }
+    println!();
+    if diagnostics.is_empty() {
+        println!(
+            "{}",
+            success_style().paint(format!(
+                "\t{} All the configuration files are valid",
+                Marker::Ok
+            ))
+        );
+        return Ok(true);
+    }
+    for diagnostic in &diagnostics {
+        println!(
+            "{}",
+            failure_style().paint(format!("\t{} {diagnostic}", Marker::Fail))
+        );
+    }
+    println!(
+        "{}",
+        cargo_style().paint(format!(
+            "\n\t{} problem(s) found in the configuration files.",
+            diagnostics.len()
+        ))
+    );
+    Ok(false)
+}
+
+/// Check the progress database, returning `true` if no problem was left unfixed.
+fn db_check(profile: Option<&str>, fix: bool) -> Result<bool, anyhow::Error> {
+    let configuration = ExercisesConfig::load()?;
+    let store = configuration.open_progress_store(profile)?;
+    let check = wr::progress::check(store.as_ref(), configuration.naming_pattern())?;
+    let mut diagnosis = Diagnosis::default();
+    println!();
+    for problem in &check.corruption {
+        diagnosis.problem(
+            format!("Your progress database is corrupted: {problem}"),
+            "Restore a backup with `wr restore-backup <path>`, or start over with `wr reset`.",
+        );
+    }
+    if check.is_ok() {
+        diagnosis.ok(format!(
+            "Your progress database ({:?}) is in good shape",
+            store.path()
+        ));
+        return Ok(true);
+    }
+    if check.invalid_exercises.is_empty() {
+        return Ok(false);
+    }
+    if !fix {
+        for (chapter, exercise) in &check.invalid_exercises {
+            diagnosis.problem(
+                format!("`{chapter}/{exercise}` is not a valid exercise"),
+                "Run `wr db check --fix` to remove it from your progress.",
+            );
+        }
+        return Ok(false);
+    }
+    let backup = wr::progress::backup(store.as_ref(), None)?;
+    let invalid: Vec<_> = check.invalid_exercises.into_iter().collect();
+    store.purge_exercises(&invalid)?;
+    for (chapter, exercise) in &invalid {
+        println!(
+            "{}",
+            cargo_style().paint(format!("\t{} {chapter}/{exercise}", Marker::Removed))
+        );
+    }
+    println!(
+        "{}",
+        info_style().paint(format!(
+            "\n\t{} invalid exercise(s) removed from your progress.\n\tThey can still be found in the backup taken beforehand, {backup:?}.",
+            invalid.len()
+        ))
+    );
+    Ok(diagnosis.n_problems == 0)
+}
+
+/// Download (and, unless `fetch_only` is set, compile) the dependencies of every exercise.
+///
+/// It returns `true` if they could all be downloaded.
+fn warm(
+    exercises: &ExerciseCollection,
+    configuration: &ExercisesConfig,
+    fetch_only: bool,
+    verbose: bool,
+) -> Result<bool, anyhow::Error> {
+    let mut n_failed = 0;
+    println!();
+    for (definition, _) in exercises.statuses()? {
+        let exercise_dir = exercises.exercises_dir_of(&definition);
+        let config = definition.config(exercise_dir)?;
+        let mut fetch = cargo(config.as_ref().and_then(|c| c.toolchain.as_deref()));
+        fetch
+            .arg("fetch")
+            .arg("--manifest-path")
+            .arg(definition.manifest_path(exercise_dir));
+        let output = fetch
+            .output()
+            .with_context(|| format!("Failed to run `{fetch:?}`"))?;
+        if !output.status.success() {
+            n_failed += 1;
+            println!(
+                "{}\n{}",
+                failure_style().paint(format!(
+                    "\t{} {definition}: its dependencies couldn't be downloaded",
+                    Marker::Fail
+                )),
+                cargo_style().paint(textwrap::indent(
+                    &String::from_utf8_lossy(&output.stderr),
+                    "\t   "
+                ))
+            );
+            continue;
+        }
+        // The build step of the verification, so that its artifacts are the ones `wr` will reuse:
+        // the same toolchain, features and flags.
+        let build = (!fetch_only)
+            .then(|| verification_commands(exercises, &definition, configuration, verbose))
+            .transpose()?
+            .and_then(|steps| steps.into_iter().find(|s| s.name == "build"));
+        let Some(mut build) = build else {
+            println!(
+                "{}",
+                success_style().paint(format!("\t{} {definition}", Marker::Pass))
+            );
+            continue;
+        };
+        // We need cargo's errors to tell who failed to compile, even in verbose mode.
+        build.command.stderr(std::process::Stdio::piped());
+        let output = build
+            .command
+            .output()
+            .with_context(|| format!("Failed to run `{:?}`", build.command))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if verbose {
+            eprint!("{stderr}");
+        }
+        if output.status.success() {
+            println!(
+                "{}",
+                success_style().paint(format!("\t{} {definition}", Marker::Pass))
+            );
+            continue;
+        }
+        // Exercises aren't expected to compile before they're solved: it's only a problem
+        // if something else, e.g. a dependency, failed to build.
+        let manifest_path = definition.manifest_path(exercise_dir);
+        if only_crate_failed(&stderr, &package_name(&manifest_path)?) {
+            println!(
+                "{}",
+                success_style().paint(format!(
+                    "\t{} {definition} (its dependencies are ready, but it doesn't compile yet)",
+                    Marker::Pass
+                ))
+            );
+            continue;
+        }
+        n_failed += 1;
+        println!(
+            "{}\n{}",
+            failure_style().paint(format!(
+                "\t{} {definition}: its dependencies couldn't be built",
+                Marker::Fail
+            )),
+            cargo_style().paint(textwrap::indent(&stderr, "\t   "))
+        );
+    }
+    if n_failed > 0 {
+        println!(
+            "{}",
+            failure_style().paint(format!(
+                "\n\tThe dependencies of {n_failed} exercise(s) couldn't be downloaded or built."
+            ))
+        );
+        return Ok(false);
+    }
+    println!(
+        "{}",
+        info_style().paint("\n\tEverything is ready: you can now work offline.")
+    );
+    Ok(true)
+}
+
+/// The name of the package defined by the manifest at `manifest_path`.
+fn package_name(manifest_path: &Path) -> Result<String, anyhow::Error> {
+    let manifest: toml::Value = toml::from_str(&fs_err::read_to_string(manifest_path)?)
+        .with_context(|| format!("Failed to parse {manifest_path:?}"))?;
+    manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(toml::Value::as_str)
+        .map(str::to_owned)
+        .ok_or_else(|| anyhow::anyhow!("{manifest_path:?} doesn't define a package name"))
+}
+
+/// Whether the failed build whose `stderr` is given only failed to compile `package`.
+///
+/// Cargo reports every crate it couldn't compile with a "could not compile `<name>`" error:
+/// if there is none, the build failed for some other reason (e.g. a missing toolchain).
+fn only_crate_failed(stderr: &str, package: &str) -> bool {
+    let failed: Vec<_> = stderr
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("error: could not compile `"))
+        .filter_map(|rest| rest.split('`').next())
+        .collect();
+    !failed.is_empty() && failed.iter().all(|name| *name == package)
+}
+
+/// Run all the environment checks, returning `true` if no problem was found.
+fn doctor(profile: Option<&str>) -> bool {
+    let mut diagnosis = Diagnosis::default();
+
+    println!("\n{}", info_style().bold().paint("Tools"));
+    for tool in ["cargo", "rustc", "git"] {
+        match tool_version(tool, &["--version"]) {
+            Ok(version) => diagnosis.ok(version),
+            Err(e) => diagnosis.problem(
+                format!("`{tool}` is not available: {e}"),
+                "Install the Rust toolchain via https://rustup.rs and make sure `git` is in your PATH.",
+            ),
+        }
+    }
+
+    println!("\n{}", info_style().bold().paint("Configuration"));
+    let configuration = match ExercisesConfig::load() {
+        Ok(c) => {
+            diagnosis.ok("The collection configuration (`.wr.toml`) is valid");
+            c
+        }
+        Err(e) => {
+            diagnosis.problem(
+                format!("{e:#}"),
+                "Run `wr` from inside the repository of the workshop and check its `.wr.toml` file.",
+            );
+            return false;
+        }
+    };
+    let exercises = match configuration
+        .open_progress_store(profile)
+        .and_then(|store| {
+            ExerciseCollection::with_store(
+                configuration.exercises_dirs().to_vec(),
+                configuration.naming_pattern().clone(),
+                store,
+            )
+        }) {
+        Ok(e) => e,
+        Err(e) => {
+            diagnosis.problem(
+                format!("{e:#}"),
+                format!(
+                    "Check that {:?} exists and that you can write to {:?}.",
+                    configuration.exercises_dir(),
+                    configuration.progress_dir()
+                ),
+            );
+            return false;
+        }
+    };
+    let mut verifications: Vec<&Verification> = configuration.verification().iter().collect();
+    let mut exercise_configs = vec![];
+    let n_problems = diagnosis.n_problems;
+    for exercise in exercises.iter() {
+        if !exercise.exists(exercises.exercises_dir_of(exercise)) {
+            diagnosis.problem(
+                format!("{exercise} doesn't have a `Cargo.toml` file"),
+                format!(
+                    "Every exercise must be a Rust project: add a manifest to {:?}.",
+                    exercise.manifest_folder_path(exercises.exercises_dir_of(exercise))
+                ),
+            );
+        }
+        match exercise.config(exercises.exercises_dir_of(exercise)) {
+            Ok(config) => exercise_configs.extend(config),
+            Err(e) => diagnosis.problem(
+                format!("{e:#}"),
+                "Fix the `.wr.toml` file in the exercise folder.",
+            ),
+        }
+    }
+    if diagnosis.n_problems == n_problems {
+        diagnosis.ok(format!(
+            "All {} exercises are valid Rust projects with a valid configuration",
+            exercises.iter().count()
+        ));
+    }
+    verifications.extend(exercise_configs.iter().flat_map(|c| c.verification.iter()));
+    verifications.extend(configuration.build(None));
+    verifications.extend(exercise_configs.iter().filter_map(|c| c.build.as_ref()));
+    // Commands meant for other platforms won't run here.
+    verifications.retain(|v| v.runs_here());
+
+    // Some verification commands rely on optional toolchain components.
+    let clippy_required = configuration.clippy(None)
+        || exercise_configs
+            .iter()
+            .any(|c| configuration.clippy(Some(c)));
+    let fmt_required = configuration.fmt_check(None)
+        || exercise_configs
+            .iter()
+            .any(|c| configuration.fmt_check(Some(c)));
+    for (subcommand, component) in [("clippy", "clippy"), ("fmt", "rustfmt")] {
+        let required = verifications.iter().any(|v| {
+            v.command == "cargo" && v.args.first().map(String::as_str) == Some(subcommand)
+        }) || (subcommand == "clippy" && clippy_required)
+            || (subcommand == "fmt" && fmt_required);
+        if !required {
+            continue;
+        }
+        match tool_version("cargo", &[subcommand, "--version"]) {
+            Ok(version) => diagnosis.ok(version),
+            Err(_) => diagnosis.problem(
+                format!("`cargo {subcommand}` is required to verify some exercises, but it's not available"),
+                format!("Install it with `rustup component add {component}`."),
+            ),
+        }
+    }
+
+    let toolchains: std::collections::BTreeSet<_> = exercise_configs
+        .iter()
+        .filter_map(|c| c.toolchain.as_deref())
+        .collect();
+    for toolchain in toolchains {
+        match tool_version("cargo", &[&format!("+{toolchain}"), "--version"]) {
+            Ok(version) => diagnosis.ok(format!("{version} (`{toolchain}` toolchain)")),
+            Err(_) => diagnosis.problem(
+                format!(
+                    "Some exercises require the `{toolchain}` toolchain, but it's not installed"
+                ),
+                format!("Install it with `rustup toolchain install {toolchain}`."),
+            ),
+        }
+    }
+
+    if configuration.sandbox().is_some() {
+        match tool_version("docker", &["--version"]) {
+            Ok(version) => diagnosis.ok(version),
+            Err(_) => diagnosis.problem(
+                "The collection is configured to verify exercises in a `docker` sandbox, but `docker` is not available",
+                "Install it from https://docs.docker.com/get-docker/.",
+            ),
+        }
+    }
+
+    if exercise_configs.iter().any(|c| c.miri) {
+        match tool_version("cargo", &["+nightly", "miri", "--version"]) {
+            Ok(version) => diagnosis.ok(version),
+            Err(_) => diagnosis.problem(
+                "Some exercises are verified with Miri, but it's not available",
+                "Install it with `rustup +nightly component add miri`.",
+            ),
+        }
+    }
+
+    let targets: std::collections::BTreeSet<_> = exercise_configs
+        .iter()
+        .filter_map(|c| c.target.as_deref())
+        .collect();
+    if !targets.is_empty() {
+        let installed =
+            tool_version("rustup", &["target", "list", "--installed"]).unwrap_or_default();
+        for target in targets {
+            if installed.lines().any(|line| line.trim() == target) {
+                diagnosis.ok(format!("The `{target}` target is installed"));
+            } else {
+                diagnosis.problem(
+                    format!(
+                        "Some exercises are built for `{target}`, but the target is not installed"
+                    ),
+                    format!("Install it with `rustup target add {target}`."),
+                );
+            }
+        }
+    }
+
+    if configuration.test_runner() == TestRunner::Nextest {
+        match tool_version("cargo", &["nextest", "--version"]) {
+            Ok(version) => diagnosis.ok(version),
+            Err(_) => diagnosis.problem(
+                "The collection is configured to run tests with `cargo nextest`, but it's not available",
+                "Install it with `cargo install cargo-nextest --locked`.",
+            ),
+        }
+    }
+
+    println!("\n{}", info_style().bold().paint("Progress"));
+    match exercises.opened() {
+        Ok(_) => {
+            diagnosis.ok("The progress database can be read");
+            for definition in exercises.stale() {
+                diagnosis.problem(
+                    format!("{definition} was opened, but it no longer exists"),
+                    "It may have been removed or renamed by an update to the workshop: run `wr prune` to forget about it.",
+                );
+            }
+        }
+        Err(e) => diagnosis.problem(
+            format!("{e:#}"),
+            "Run `wr reset` to start over with a fresh database.",
+        ),
+    }
+
+    if diagnosis.n_problems == 0 {
+        println!(
+            "\n{}",
+            success_style().paint(without_emoji(
+                "\tEverything looks good! You are ready to go. 🚀"
+            ))
+        );
+    } else {
+        println!(
+            "\n{}",
+            failure_style().paint(format!(
+                "\t{} problem(s) found. Fix them and run `wr doctor` again.",
+                diagnosis.n_problems
+            ))
+        );
+    }
+    diagnosis.n_problems == 0
+}
+
+/// Invoke a tool to retrieve its version.
+fn tool_version(tool: &str, args: &[&str]) -> Result<String, anyhow::Error> {
+    let output = std::process::Command::new(tool).args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!("`{tool} {}` failed", args.join(" "));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+fn new_exercise(
+    exercises: &ExerciseCollection,
+    chapter: String,
+    name: &str,
+) -> Result<std::path::PathBuf, anyhow::Error> {
+    let naming = exercises.naming_pattern();
+    let chapter_selector = Selector::new(chapter);
+    let in_chapter: Vec<_> = exercises
+        .iter()
+        .filter(|e| chapter_selector.matches_chapter(e))
+        .collect();
+    let (chapter_dir_name, exercise_dir_name) = match (in_chapter.last(), &chapter_selector) {
+        // Follow the naming scheme of the chapter.
+        (Some(last), _) => (
+            last.chapter(),
+            last.sibling_dir_name(last.exercise_number() + 1, name, naming),
+        ),
+        // Follow the naming scheme of the first exercise of the collection, if there is one.
+        (None, Selector::FullName(chapter)) => {
+            let exercise_dir_name = match exercises.iter().next() {
+                Some(first) => first.sibling_dir_name(first.exercise_number(), name, naming),
+                None => format!("00_{name}"),
+            };
+            (chapter.to_owned(), exercise_dir_name)
+        }
+        (None, Selector::Number(n)) => anyhow::bail!(
+            "There is no chapter numbered {n}. Pass the full name of the chapter (e.g. `{n:02}_intro`) to create a new one."
+        ),
+    };
+    // The new exercise must be picked up by `wr`, with exactly the name we gave it.
+    let definition = ExerciseDefinition::new(
+        chapter_dir_name.as_ref(),
+        exercise_dir_name.as_ref(),
+        naming,
+    )
+    .with_context(|| {
+        format!("`{chapter_dir_name}/{exercise_dir_name}` is not a valid exercise path")
+    })?;
+    if definition.exercise_name() != name {
+        anyhow::bail!(
+            "`{chapter_dir_name}/{exercise_dir_name}` is not a valid exercise path: `{name}` doesn't match the naming scheme of the collection"
+        );
+    }
+    // The chapter may live in any of the exercises directories.
+    let exercise_dir = match in_chapter.last() {
+        Some(last) => last.chapter_folder_path(exercises.exercises_dir_of(last)),
+        None => exercises.exercises_dir().join(&chapter_dir_name),
+    }
+    .join(&exercise_dir_name);
+    wr::scaffold::create_exercise(&exercise_dir, name)?;
+    Ok(exercise_dir)
+}
+
+/// The exercise the user is currently working on.
+fn current_exercise(exercises: &ExerciseCollection) -> Result<ExerciseDefinition, anyhow::Error> {
+    exercises.current()?.ok_or_else(|| {
+        anyhow::anyhow!("You haven't opened any exercise yet. Run `wr` to open the first one!")
+    })
+}
+
+/// The exercise specified as `<chapter>/<exercise>`, if any, or the current one.
+fn specified_or_current_exercise(
+    exercises: &ExerciseCollection,
+    specifier: Option<String>,
+) -> Result<ExerciseDefinition, anyhow::Error> {
+    match specifier {
+        Some(specifier) => {
+            let (chapter, exercise) = parse_exercise_specifier(&specifier)?;
+            find_exercise(exercises, &chapter, &exercise)
+        }
+        None => current_exercise(exercises),
+    }
+}
+
+fn hint(
+    exercises: &ExerciseCollection,
+    exercise: &ExerciseDefinition,
+) -> Result<(), anyhow::Error> {
+    let hints = exercise
+        .config(exercises.exercises_dir_of(exercise))?
+        .map(|c| c.hints)
+        .unwrap_or_default();
+    if hints.is_empty() {
+        println!(
+            "{}",
+            info_style().paint(format!("\n\t{}", catalog().no_hints(&exercise.to_string())))
+        );
+        return Ok(());
+    }
+
+    let hints_used = exercises.hints_used(exercise)?.min(hints.len());
+    for (i, hint) in hints.iter().enumerate().take(hints_used) {
+        println!(
+            "{}",
+            cargo_style().paint(format!("\n\t{}", catalog().hint(i + 1, hints.len(), hint)))
+        );
+    }
+    match hints.get(hints_used) {
+        Some(hint) => {
+            println!(
+                "{}",
+                next_style().paint(format!(
+                    "\n\t{}",
+                    catalog().hint(hints_used + 1, hints.len(), hint)
+                ))
+            );
+            exercises.use_hint(exercise)?;
+        }
+        None => {
+            println!(
+                "{}",
+                info_style().paint(format!("\n\t{}", catalog().no_more_hints()))
+            );
+        }
+    }
+    Ok(())
+}
+
+fn show_solution(exercise: &ExerciseDefinition, solution_dir: &Path) -> Result<(), anyhow::Error> {
+    println!(
+        "{}",
+        next_style().paint(format!("\n\tThe reference solution for {exercise}:"))
+    );
+    for file in wr::exercise_files(solution_dir)? {
+        let contents = fs_err::read(solution_dir.join(&file))?;
+        println!(
+            "\n{}\n\n{}",
+            info_style().bold().paint(file.display()),
+            String::from_utf8_lossy(&contents)
+        );
+    }
+    Ok(())
+}
+
+fn apply_solution(
+    exercise: &ExerciseDefinition,
+    solution_dir: &Path,
+    exercises_dir: &Path,
+) -> Result<(), anyhow::Error> {
+    if !confirm(&format!(
+        "The reference solution will overwrite your changes to {exercise}. Do you want to continue?"
+    )) {
+        return Ok(());
+    }
+    let exercise_dir = exercise.manifest_folder_path(exercises_dir);
+    for file in wr::exercise_files(solution_dir)? {
+        let destination = exercise_dir.join(&file);
+        if let Some(parent) = destination.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        fs_err::copy(solution_dir.join(&file), destination)?;
+    }
+    println!(
+        "{}",
+        info_style().paint(format!(
+            "\n\tThe reference solution has been copied into {exercise_dir:?}.\n\tRun `wr` to verify it."
+        ))
+    );
+    Ok(())
+}
+
+fn reset(
+    exercises: &mut ExerciseCollection,
+    chapter: Option<String>,
+    exercise: Option<String>,
+    keep_open: bool,
+) -> Result<(), anyhow::Error> {
+    let selected: Vec<ExerciseDefinition> = match (chapter, exercise) {
+        (_, Some(exercise)) => {
+            let (chapter, exercise) = parse_exercise_specifier(&exercise)?;
+            vec![find_exercise(exercises, &chapter, &exercise)?]
+        }
+        (Some(chapter), None) => {
+            let chapter = Selector::new(chapter);
+            let selected: Vec<_> = exercises
+                .iter()
+                .filter(|k| chapter.matches_chapter(k))
+                .cloned()
+                .collect();
+            if selected.is_empty() {
+                anyhow::bail!("There is no chapter matching `--chapter {chapter}`")
+            }
+            selected
+        }
+        (None, None) => {
+            if !confirm("Do you really want to reset your progress for the whole collection?") {
+                return Ok(());
+            }
+            if !keep_open {
+                // There's nothing to back up, nor to reset.
+                if !exercises.opened()?.is_empty() {
+                    backup_before_changes(exercises)?;
+                    exercises.reset()?;
+                }
+                println!(
+                    "{}",
+                    info_style().paint("\n\tYour progress has been reset. Run `wr` to start over.")
+                );
+                return Ok(());
+            }
+            exercises.iter().cloned().collect()
+        }
+    };
+
+    let opened = exercises.opened()?;
+    // Exercises that were never opened have no progress to reset.
+    let selected: Vec<_> = selected
+        .into_iter()
+        .filter(|definition| opened.iter().any(|o| &o.definition == definition))
+        .collect();
+    if !selected.is_empty() {
+        backup_before_changes(exercises)?;
+    }
+    for definition in &selected {
+        exercises.close(definition)?;
+        if keep_open {
+            exercises.open(definition)?;
+        }
+    }
+    println!(
+        "{}",
+        info_style().paint(format!(
+            "\n\tYour progress has been reset for {} exercise(s).",
+            selected.len()
+        ))
+    );
+    Ok(())
+}
+
+/// Back up the progress on the collection before an operation that can't be undone.
+fn backup_before_changes(exercises: &ExerciseCollection) -> Result<(), anyhow::Error> {
+    let path = exercises.backup(None)?;
+    println!(
+        "{}",
+        cargo_style().paint(format!(
+            "\n\tYour progress has been backed up to {path:?}.\n\tRun `wr restore-backup {}` to undo the changes below.",
+            path.display()
+        ))
+    );
+    Ok(())
+}
+
+fn print_history(
+    exercises: &ExerciseCollection,
+    exercise: Option<&ExerciseDefinition>,
+) -> Result<(), anyhow::Error> {
+    let attempts = exercises.attempts(exercise)?;
+    if json_output() {
+        let attempts: Vec<_> = attempts
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "exercise": a.definition.path(),
+                    "attempted_at": a.attempted_at,
+                    "duration_secs": a.duration.as_secs_f64(),
+                    "passed": a.failed_command.is_none(),
+                    "failed_command": a.failed_command,
+                })
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "attempts": attempts }));
+    }
+    if attempts.is_empty() {
+        println!(
+            "{}",
+            info_style()
+                .paint("\n\tThere are no attempts to show yet. Run `wr` to verify your solution!")
+        );
+        return Ok(());
+    }
+    let now = wr::unix_timestamp();
+    let name_width = attempts
+        .iter()
+        .map(|a| a.definition.to_string().chars().count())
+        .max()
+        .unwrap_or(0);
+    println!();
+    for attempt in &attempts {
+        let line = format!(
+            "\t{} {:<name_width$}  {:>8}  {:>12} ago",
+            if attempt.failed_command.is_some() {
+                Marker::Fail
+            } else {
+                Marker::Pass
+            },
+            attempt.definition.to_string(),
+            format_run_time(attempt.duration),
+            format_duration(now - attempt.attempted_at),
+        );
+        match &attempt.failed_command {
+            None => println!("{}", success_style().paint(line)),
+            Some(command) => println!(
+                "{}  {}",
+                failure_style().paint(line),
+                cargo_style().paint(format!("failed: {command}"))
+            ),
+        }
+    }
+    let n_failed = attempts
+        .iter()
+        .filter(|a| a.failed_command.is_some())
+        .count();
+    println!(
+        "{}",
+        info_style().paint(format!(
+            "\n\t{} attempt(s), {n_failed} failed.",
+            attempts.len()
+        ))
+    );
+    Ok(())
+}
 
-                let chapter_selector = Selector::new(chapter);
-                let exercise_selector = Selector::new(exercise);
+fn print_bookmarks(exercises: &ExerciseCollection) -> Result<(), anyhow::Error> {
+    let bookmarks = exercises.bookmarks()?;
+    if bookmarks.is_empty() {
+        println!(
+            "{}",
+            info_style()
+                .paint("\n\tYou haven't bookmarked any exercise yet. Use `wr bookmark add`.")
+        );
+        return Ok(());
+    }
+    println!("{}", next_style().paint("\n\tYour bookmarks:\n"));
+    for exercise in bookmarks {
+        println!(
+            "{}",
+            info_style().paint(format!(
+                "\t{} {exercise} ({}/{})",
+                Marker::Bookmark,
+                exercise.chapter(),
+                exercise.exercise()
+            ))
+        );
+    }
+    Ok(())
+}
 
-                let exercise = exercises.iter().find(|k| {
-                    chapter_selector.matches(&k.chapter(), k.chapter_number())
-                        && exercise_selector.matches(&k.exercise(), k.exercise_number())
-                }).ok_or_else(|| {
-                    anyhow::anyhow!("There is no exercise matching `--chapter {chapter_selector} -- exercise {exercise_selector}`")
-                })?.to_owned();
+fn print_notes(
+    exercises: &ExerciseCollection,
+    exercise: Option<&ExerciseDefinition>,
+) -> Result<(), anyhow::Error> {
+    let notes = exercises.notes(exercise)?;
+    if notes.is_empty() {
+        println!(
+            "{}",
+            info_style().paint("\n\tThere are no notes to show yet. Use `wr note add`.")
+        );
+        return Ok(());
+    }
+    println!("{}", next_style().paint("\n\tYour notes:"));
+    let now = wr::unix_timestamp();
+    let mut previous: Option<&ExerciseDefinition> = None;
+    for note in &notes {
+        if previous != Some(&note.definition) {
+            println!("\n\t{}", info_style().bold().paint(&note.definition));
+            previous = Some(&note.definition);
+        }
+        println!(
+            "\t  {} {} {}",
+            Marker::Note,
+            info_style().paint(textwrap::indent(&note.text, "\t     ").trim_start()),
+            cargo_style().paint(format!("({} ago)", format_duration(now - note.created_at)))
+        );
+    }
+    Ok(())
+}
 
-                exercises.open(&exercise)?;
-                print_opened_message(&exercise, exercises.exercises_dir());
-            }
-            Commands::Check => {
-                let current_dir = std::env::current_dir()?.fs_err_canonicalize()?;
-                let definition = exercises
-                    .iter()
-                    .find(|k| {
-                        let manifest_folder = k
-                            .manifest_folder_path(exercises.exercises_dir())
-                            .fs_err_canonicalize()
-                            .expect("Failed to canonicalize manifest folder path");
-                        manifest_folder == current_dir
-                    })
-                    .ok_or_else(|| anyhow::anyhow!("The current directory is not an exercise"))?;
-                verify(
-                    &exercises,
-                    &definition,
-                    configuration.verification(),
-                    verbose,
-                )?;
-            }
+/// Render notes as a Markdown document, with a section for each exercise.
+fn notes_to_markdown(notes: &[wr::Note]) -> String {
+    let mut markdown = String::from("# Notes\n");
+    let mut previous: Option<&ExerciseDefinition> = None;
+    for note in notes {
+        if previous != Some(&note.definition) {
+            markdown.push_str(&format!("\n## {}\n\n", note.definition));
+            previous = Some(&note.definition);
         }
-        return Ok(());
+        // Continuation lines are indented to stay within the list item.
+        markdown.push_str(&format!(
+            "- {}\n",
+            textwrap::indent(&note.text, "  ").trim_start()
+        ));
     }
+    markdown
+}
 
-    // If no command was specified, we verify the user's progress on the workshop-runner that have already
-    // been opened.
-    if let TestOutcome::Failure { command, details } = seek_the_path(
-        &mut exercises,
-        command.recheck,
-        configuration.verification(),
-        verbose,
-    )? {
-        print_failure_message(&command, &details);
-        std::process::exit(1);
-    };
+fn clean(exercises: &ExerciseCollection, chapter: Option<String>) -> Result<(), anyhow::Error> {
+    let chapter = chapter.map(Selector::new);
+    let selected: Vec<_> = exercises
+        .iter()
+        .filter(|e| chapter.as_ref().is_none_or(|c| c.matches_chapter(e)))
+        .collect();
+    if let Some(chapter) = &chapter {
+        if selected.is_empty() {
+            anyhow::bail!("There is no chapter matching `--chapter {chapter}`")
+        }
+    }
 
-    // If all the currently opened workshop-runner passed their checks, we open the next one (if it exists).
-    while let Some(next_exercise) = exercises.next()? {
-        if command.keep_going {
-            let next_exercise = exercises
-                .open_next()
-                .expect("Failed to open the next exercise");
-            let exercise_outcome = verify(
-                &exercises,
-                &next_exercise,
-                configuration.verification(),
-                command.verbose,
-            )?;
-            if let TestOutcome::Failure { command, details } = exercise_outcome {
-                print_failure_message(&command, &details);
-                std::process::exit(1);
-            };
+    let mut reclaimed = 0;
+    for exercise in selected {
+        let target_dir = exercise
+            .manifest_folder_path(exercises.exercises_dir_of(exercise))
+            .join("target");
+        if !target_dir.exists() {
             continue;
-        } else {
-            println!(
-                "\t{}\n",
-                info_style().paint(
-                    "Eternity lies ahead of us, and behind. Your path is not yet finished. 🍂"
-                )
-            );
-
-            let open_next = input::<String>()
-                .repeat_msg(format!(
-                    "Do you want to open the next exercise, {}? [y/n] ",
-                    next_exercise
-                ))
-                .err("Please answer either yes or no.")
-                .add_test(|s| parse_bool(s).is_some())
-                .get();
-            // We can safely unwrap here because we have already validated the input.
-            let open_next = parse_bool(&open_next).unwrap();
-
-            if open_next {
-                let next_exercise = exercises
-                    .open_next()
-                    .expect("Failed to open the next exercise");
-                print_opened_message(&next_exercise, exercises.exercises_dir());
-            }
-            return Ok(());
         }
+        let size = dir_size(&target_dir)?;
+        fs_err::remove_dir_all(&target_dir)
+            .with_context(|| format!("Failed to clean the build artifacts for {exercise}"))?;
+        println!(
+            "{}",
+            cargo_style().paint(format!(
+                "\t{} {exercise} ({})",
+                Marker::Removed,
+                format_size(size)
+            ))
+        );
+        reclaimed += size;
     }
     println!(
-        "{}\n\t{}\n",
-        success_style().paint("\n\tThere will be no more tasks."),
-        info_style().paint("What is the sound of one hand clapping (for you)? 🌟")
+        "{}",
+        info_style().paint(format!(
+            "\n\t{} of disk space reclaimed.",
+            format_size(reclaimed)
+        ))
     );
     Ok(())
 }
 
-fn parse_bool(s: &str) -> Option<bool> {
-    match s.to_ascii_lowercase().as_str() {
-        "yes" | "y" => Some(true),
-        "no" | "n" => Some(false),
-        _ => None,
+/// The total size, in bytes, of the files in `dir` and its subdirectories.
+fn dir_size(dir: &Path) -> Result<u64, anyhow::Error> {
+    let mut size = 0;
+    for entry in fs_err::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
     }
+    Ok(size)
 }
 
-fn seek_the_path(
-    exercises: &mut ExerciseCollection,
-    recheck: bool,
-    verification: &[Verification],
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.;
+    let mut unit = 0;
+    while size >= 1024. && unit < UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn serve(
+    exercises: &ExerciseCollection,
+    configuration: &ExercisesConfig,
     verbose: bool,
-) -> Result<TestOutcome, anyhow::Error> {
-    println!(" \n\n{}", info_style().dimmed().paint("Running tests...\n"));
-    for exercise in exercises.opened()? {
-        let OpenedExercise { definition, solved } = &exercise;
-        if !exercise.definition.exists(exercises.exercises_dir()) {
-            exercises.close(&definition)?;
+    port: u16,
+) -> Result<(), anyhow::Error> {
+    use std::collections::HashMap;
+    use tiny_http::{Header, Method, Response, Server};
+
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| anyhow::anyhow!("Failed to start a web server on port {port}: {e}"))?;
+    println!(
+        "{}",
+        next_style().paint(format!(
+            "\n\tYour progress is available at http://127.0.0.1:{port}\n\tPress Ctrl+C to stop the server."
+        ))
+    );
+
+    // Requests must come from the board itself: any web page could otherwise make the browser
+    // post to `/verify` (or, through DNS rebinding, read the board) behind the learner's back.
+    let same_origin = |request: &tiny_http::Request| {
+        let allowed = [format!("127.0.0.1:{port}"), format!("localhost:{port}")];
+        let header = |name: &'static str| {
+            request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv(name))
+                .map(|h| h.value.as_str().to_owned())
+        };
+        let host_ok = header("Host").is_none_or(|host| allowed.contains(&host));
+        let origin_ok = header("Origin").is_none_or(|origin| {
+            origin
+                .strip_prefix("http://")
+                .is_some_and(|o| allowed.iter().any(|a| a == o))
+        });
+        host_ok && origin_ok
+    };
+
+    let mut failures: HashMap<ExerciseDefinition, String> = HashMap::new();
+    for request in server.incoming_requests() {
+        if !same_origin(&request) {
+            let response = Response::from_string("Forbidden").with_status_code(403);
+            if let Err(e) = request.respond(response) {
+                eprintln!("Failed to respond to a request: {e}");
+            }
             continue;
         }
-        if *solved && !recheck {
-            println!(
+        let url = request.url().to_owned();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        // A failure to handle a request shouldn't take the whole board down.
+        let mut handle = || -> Result<_, anyhow::Error> {
+            let response = match (request.method(), path) {
+                (Method::Get, "/") => {
+                    let html = wr::dashboard::render(
+                        &configuration.metadata(),
+                        &exercises.statuses()?,
+                        |e| exercises.is_optional(e),
+                        &failures,
+                    );
+                    Response::from_string(html).with_header(
+                        Header::from_bytes("Content-Type", "text/html; charset=utf-8").unwrap(),
+                    )
+                }
+                (Method::Post, "/verify") => {
+                    let param = |name: &str| {
+                        query
+                            .split('&')
+                            .filter_map(|p| p.split_once('='))
+                            .find(|(k, _)| *k == name)
+                            .map(|(_, v)| wr::dashboard::percent_decode(v))
+                    };
+                    let to_verify: Vec<ExerciseDefinition> =
+                        match (param("chapter"), param("exercise")) {
+                            (Some(chapter), Some(exercise)) => exercises
+                                .iter()
+                                .filter(|e| e.chapter() == chapter && e.exercise() == exercise)
+                                .cloned()
+                                .collect(),
+                            _ => exercises
+                                .opened()?
+                                .into_iter()
+                                .map(|e| e.definition)
+                                .collect(),
+                        };
+                    for exercise in to_verify {
+                        match verify(exercises, &exercise, configuration, verbose)? {
+                            TestOutcome::Success => {
+                                failures.remove(&exercise);
+                            }
+                            TestOutcome::Failure {
+                                stage,
+                                command,
+                                details,
+                            } => {
+                                let output = format!(
+                                    "Stage `{stage}` failed.\nFailed to run: {command}\n\n{}",
+                                    wr::strip_ansi_codes(&String::from_utf8_lossy(&details))
+                                );
+                                failures.insert(exercise, output);
+                            }
+                        }
+                    }
+                    // Redirect to the board, so that refreshing the page doesn't trigger
+                    // another verification run.
+                    Response::from_string("")
+                        .with_status_code(303)
+                        .with_header(Header::from_bytes("Location", "/").unwrap())
+                }
+                _ => Response::from_string("Not found").with_status_code(404),
+            };
+            Ok(response)
+        };
+        let response = handle().unwrap_or_else(|e| {
+            eprintln!(
                 "{}",
-                info_style().paint(format!("\t⏩ {} (Not rechecked)", definition))
+                failure_style().paint(format!("Failed to handle {url}: {e:?}"))
             );
-            continue;
-        }
-        let exercise_outcome = verify(exercises, &definition, verification, verbose)?;
-        if let TestOutcome::Failure { command, details } = exercise_outcome {
-            return Ok(TestOutcome::Failure { command, details });
+            Response::from_string(format!("Something went wrong: {e}")).with_status_code(500)
+        });
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to respond to a request: {e}");
         }
     }
-    Ok(TestOutcome::Success)
+    Ok(())
 }
 
-fn verify(
+fn watch(
     exercises: &ExerciseCollection,
-    definition: &ExerciseDefinition,
-    verification: &[Verification],
+    configuration: &ExercisesConfig,
     verbose: bool,
-) -> Result<TestOutcome, anyhow::Error> {
-    let exercise_config = definition.config(exercises.exercises_dir())?;
-    // Exercise-specific config takes precedence over the global one, if specified.
-    let verification = exercise_config
-        .as_ref()
-        .map(|c| c.verification.as_slice())
-        .unwrap_or(verification);
-    let exercise_outcome = _verify(
-        &definition.manifest_path(exercises.exercises_dir()),
-        verification,
-        verbose,
-    );
-    match &exercise_outcome {
-        TestOutcome::Success => {
-            println!("{}", success_style().paint(format!("\t🚀 {}", definition)));
-            exercises.mark_as_solved(&definition)?;
+) -> Result<(), anyhow::Error> {
+    use notify::{RecursiveMode, Watcher};
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)
+        .context("Failed to start watching the filesystem for changes")?;
+    let mut watched: Option<ExerciseDefinition> = None;
+    loop {
+        let current = current_exercise(exercises)?;
+        if watched.as_ref() != Some(&current) {
+            let folder = current.manifest_folder_path(exercises.exercises_dir_of(&current));
+            if let Some(previous) = watched.take() {
+                let _ = watcher
+                    .unwatch(&previous.manifest_folder_path(exercises.exercises_dir_of(&previous)));
+            }
+            watcher
+                .watch(&folder, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch {folder:?} for changes"))?;
+            watched = Some(current.clone());
+        }
+
+        println!(
+            " \n\n{}",
+            info_style()
+                .dimmed()
+                .paint(format!("{}\n", catalog().running_tests()))
+        );
+        if let TestOutcome::Failure {
+            stage,
+            command,
+            details,
+        } = verify(exercises, &current, configuration, verbose)?
+        {
+            print_failure_message(exercises, &current, &stage, &command, &details);
+        } else {
+            println!("\n\t{}", info_style().paint(catalog().move_on()));
+        }
+        println!(
+            "\n{}",
+            cargo_style().paint(catalog().watching(&current.to_string()))
+        );
+
+        // Wait for a relevant change, then give the editor some time to finish writing
+        // before kicking off a new run.
+        // The current exercise may change in the meantime, e.g. when `wr` runs in another
+        // terminal: check every now and then, and follow it.
+        let switched = loop {
+            match receiver.recv_timeout(Duration::from_secs(1)) {
+                Ok(event) if is_relevant(&event) => break false,
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => {
+                    if current_exercise(exercises)? != current {
+                        break true;
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    anyhow::bail!("Stopped receiving filesystem notifications")
+                }
+            }
+        };
+        if switched {
+            continue;
         }
-        TestOutcome::Failure { .. } => {
-            println!("{}", failure_style().paint(format!("\t❌ {}", definition)));
-            exercises.mark_as_unsolved(&definition)?;
+        loop {
+            match receiver.recv_timeout(Duration::from_millis(300)) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    anyhow::bail!("Stopped receiving filesystem notifications")
+                }
+            }
         }
     }
-    Ok(exercise_outcome)
 }
 
-fn _verify(manifest_path: &Path, verification: &[Verification], verbose: bool) -> TestOutcome {
-    // Tell cargo to return colored output, unless we are on Windows and the terminal
-    // doesn't support it.
-    let color_option = if use_ansi_colours() {
-        "always"
-    } else {
-        "never"
+/// Changes to build artifacts (e.g. in `target/` or `Cargo.lock`) shouldn't trigger a new run,
+/// otherwise every run would trigger the next one.
+fn is_relevant(event: &notify::Result<notify::Event>) -> bool {
+    let Ok(event) = event else {
+        return false;
     };
+    if event.kind.is_access() {
+        return false;
+    }
+    event.paths.iter().any(|path| {
+        !path.components().any(|c| {
+            let c = c.as_os_str();
+            c == "target" || c == ".git" || c == "Cargo.lock"
+        }) && !wr::hidden_tests::is_injected(path)
+    })
+}
 
-    // `cargo build` first
-    {
-        let mut cmd = std::process::Command::new("cargo");
-        cmd.arg("build");
-        cmd.arg("--manifest-path");
-        cmd.arg(manifest_path);
-        cmd.arg("--all-targets");
-        cmd.arg("--color");
-        cmd.arg(color_option);
-        if !verbose {
-            cmd.arg("-q");
+/// E.g. `` `traits` or `async` ``.
+fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|t| format!("`{t}`"))
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+fn print_list(exercises: &ExerciseCollection, tags: &[String]) -> Result<(), anyhow::Error> {
+    let mut statuses = exercises.statuses()?;
+    statuses.retain(|(e, _)| exercises.has_any_tag(e, tags));
+    if statuses.is_empty() && !tags.is_empty() {
+        anyhow::bail!("No exercise is tagged with {}", format_tags(tags));
+    }
+    if json_output() {
+        let exercises: Vec<_> = statuses
+            .iter()
+            .map(|(definition, status)| JsonExercise::new(exercises, definition, *status))
+            .collect();
+        return print_json(&serde_json::json!({ "exercises": exercises }));
+    }
+    for chapter in statuses.chunk_by(|(a, _), (b, _)| a.chapter_number() == b.chapter_number()) {
+        let (first, _) = &chapter[0];
+        println!(
+            "\n{} {}",
+            info_style().bold().paint(format!(
+                "({:02}) {}",
+                first.chapter_number(),
+                first.chapter_name()
+            )),
+            cargo_style().paint({
+                let ((n_solved, total), optional) = n_solved(exercises, chapter);
+                format!(
+                    "[{n_solved}/{total} solved, {}%{}]",
+                    percentage(n_solved, total),
+                    optional_suffix(optional)
+                )
+            })
+        );
+        for (definition, status) in chapter {
+            let mut details = vec![status.to_string()];
+            if exercises.is_optional(definition) {
+                details.push("optional".into());
+            }
+            if let Some(difficulty) = exercises.difficulty(definition) {
+                details.push(difficulty.to_string());
+            }
+            let mut line = format!(
+                "({:02}) {} ({})",
+                definition.exercise_number(),
+                definition.exercise_name(),
+                details.join(", ")
+            );
+            let tags = exercises.tags(definition);
+            if !tags.is_empty() {
+                line.push_str(&format!(" [{}]", tags.join(", ")));
+            }
+            let line = match status {
+                ExerciseStatus::Solved => success_style(),
+                ExerciseStatus::Failed => failure_style(),
+                ExerciseStatus::Skipped => info_style(),
+                ExerciseStatus::Opened => next_style(),
+                ExerciseStatus::Unopened => cargo_style(),
+            }
+            .paint(format!("\t{} {line}", Marker::from(*status)));
+            println!("{line}");
         }
+    }
+    println!(
+        "\n{}",
+        info_style().paint({
+            let ((n_solved, total), optional) = n_solved(exercises, &statuses);
+            format!(
+                "{n_solved}/{total} exercises solved ({}%){}",
+                percentage(n_solved, total),
+                optional_suffix(optional)
+            )
+        })
+    );
+    Ok(())
+}
 
-        if verbose {
-            cmd.stdout(std::process::Stdio::inherit())
-                .stderr(std::process::Stdio::inherit());
+/// How many exercises have been solved, out of how many, as `(mandatory, optional)`.
+fn n_solved(
+    exercises: &ExerciseCollection,
+    statuses: &[(ExerciseDefinition, ExerciseStatus)],
+) -> ((usize, usize), (usize, usize)) {
+    let (mut mandatory, mut optional) = ((0, 0), (0, 0));
+    for (exercise, status) in statuses {
+        let counts = if exercises.is_optional(exercise) {
+            &mut optional
+        } else {
+            &mut mandatory
+        };
+        counts.1 += 1;
+        if *status == ExerciseStatus::Solved {
+            counts.0 += 1;
         }
+    }
+    (mandatory, optional)
+}
 
-        let output = cmd.output().expect("Failed to build the project");
+/// E.g. `, 1/2 optional`, if there are optional exercises. They don't count towards the
+/// completion percentage.
+fn optional_suffix((n_solved, total): (usize, usize)) -> String {
+    catalog().optional_solved(n_solved, total)
+}
 
-        if !output.status.success() {
-            return TestOutcome::Failure {
-                command: format!("{:?}", cmd),
-                details: [output.stderr, output.stdout].concat(),
-            };
+/// A verification command, as the user would type it in a shell.
+fn display_verification(v: &Verification) -> String {
+    v.env
+        .iter()
+        .map(|(name, value)| format!("{name}={}", wr::shell_quote(value)))
+        // Shell commands are scripts: they are shown as they are.
+        .chain(std::iter::once(if v.shell {
+            v.command.clone()
+        } else {
+            wr::shell_quote(&v.command)
+        }))
+        .chain(v.expanded_args().iter().map(|arg| wr::shell_quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn print_info(
+    exercises: &ExerciseCollection,
+    exercise: &ExerciseDefinition,
+    configuration: &ExercisesConfig,
+    n_lines: usize,
+) -> Result<(), anyhow::Error> {
+    let exercises_dir = exercises.exercises_dir_of(exercise);
+    let config = exercise.config(exercises_dir)?;
+    let status = exercises
+        .statuses()?
+        .into_iter()
+        .find(|(e, _)| e == exercise)
+        .map(|(_, status)| status)
+        .unwrap_or(ExerciseStatus::Unopened);
+    let verification = configuration.verification_for(config.as_ref());
+    let mut commands = vec![];
+    if !configuration.skip_build(config.as_ref()) {
+        commands.push(match configuration.build(config.as_ref()) {
+            Some(build) => display_verification(build),
+            None => "cargo build --all-targets".to_owned(),
+        });
+    }
+    if configuration.fmt_check(config.as_ref()) {
+        commands.push("cargo fmt --check".into());
+    }
+    if configuration.clippy(config.as_ref()) {
+        commands.push("cargo clippy --all-targets -- -D warnings".into());
+    }
+    let test_filter = config
+        .as_ref()
+        .and_then(|c| c.test_filter.as_deref())
+        .map(|filter| format!(" {filter}"))
+        .unwrap_or_default();
+    if verification.is_empty() {
+        if config.as_ref().is_some_and(|c| c.doc_tests) {
+            commands.push(format!("cargo test --doc{test_filter}"));
+        } else if configuration.test_runner() == TestRunner::Nextest {
+            commands.push(format!("cargo nextest run{test_filter}"));
+        } else {
+            commands.push(format!("cargo test{test_filter}"));
         }
     }
+    commands.extend(verification.into_iter().map(display_verification));
+    if config.as_ref().is_some_and(|c| c.miri) {
+        commands.push(format!("cargo +nightly miri test{test_filter}"));
+    }
+    if config.as_ref().is_some_and(|c| !c.benchmarks.is_empty()) {
+        commands.push("cargo bench".into());
+    }
+    // Nothing but the build runs for `compile-fail` exercises.
+    if let Some(c) = config.as_ref().filter(|c| c.expect == Expect::CompileFail) {
+        commands = vec![match &c.expected_error {
+            Some(code) => format!("cargo build --all-targets (must fail with {code})"),
+            None => "cargo build --all-targets (must fail)".into(),
+        }];
+    }
+    let n_hints = config.as_ref().map(|c| c.hints.len()).unwrap_or(0);
 
-    // Now we run the verification command.
-    {
-        let mut verification_commands: Vec<_> = verification
+    let label = |s: &str| info_style().bold().paint(format!("{s:<14}"));
+    println!();
+    println!("\t{}{}", label("Exercise:"), next_style().paint(exercise));
+    println!(
+        "\t{}{:?}",
+        label("Path:"),
+        exercise.manifest_folder_path(exercises_dir)
+    );
+    println!("\t{}{}", label("Status:"), status);
+    if n_hints > 0 {
+        println!(
+            "\t{}{}/{} revealed",
+            label("Hints:"),
+            exercises.hints_used(exercise)?.min(n_hints),
+            n_hints
+        );
+    }
+    println!("\t{}", label("Verification:"));
+    for command in commands {
+        println!("\t  {}", cargo_style().paint(format!("$ {command}")));
+    }
+    if let Some(readme) = exercise.readme_path(exercises_dir) {
+        let readme = fs_err::read_to_string(readme)?;
+        println!(
+            "\n{}",
+            textwrap::indent(
+                &readme.lines().take(n_lines).collect::<Vec<_>>().join("\n"),
+                "\t"
+            )
+        );
+        if readme.lines().count() > n_lines {
+            println!("\t{}", cargo_style().paint("[...]"));
+        }
+    }
+    Ok(())
+}
+
+fn print_status(
+    exercises: &ExerciseCollection,
+    configuration: &ExercisesConfig,
+) -> Result<(), anyhow::Error> {
+    let statuses = exercises.statuses()?;
+    if json_output() {
+        let ((n_solved, total), (n_optional_solved, n_optional)) = n_solved(exercises, &statuses);
+        let last_failure = statuses
             .iter()
-            .map(|v| {
-                let mut cmd = std::process::Command::new(&v.command);
-                cmd.args(&v.args);
-                cmd
-            })
-            .collect();
-        if verification_commands.is_empty() {
-            let mut args: Vec<OsString> =
-                vec!["test".into(), "--color".into(), color_option.into()];
+            .rev()
+            .find(|(_, status)| *status == ExerciseStatus::Failed)
+            .map(|(e, _)| e.path());
+        return print_json(&serde_json::json!({
+            "workshop": configuration.metadata(),
+            "track": exercises.track(),
+            "current": exercises.current()?.map(|e| e.path()),
+            "solved": n_solved,
+            "total": total,
+            "optional_solved": n_optional_solved,
+            "optional_total": n_optional,
+            "percentage": percentage(n_solved, total),
+            "last_failure": last_failure,
+            "started_at": exercises.started_at()?,
+        }));
+    }
+    let labels = catalog().status();
+    let current = exercises
+        .current()?
+        .map(|e| e.to_string())
+        .unwrap_or_else(|| labels.no_current.into());
+    let last_failure = statuses
+        .iter()
+        .rev()
+        .find(|(_, status)| *status == ExerciseStatus::Failed)
+        .map(|(e, _)| e.to_string())
+        .unwrap_or_else(|| labels.none.into());
+    let elapsed = exercises
+        .started_at()?
+        .map(|started_at| format_duration(wr::unix_timestamp() - started_at))
+        .unwrap_or_else(|| labels.unknown.into());
 
-            if !verbose {
-                args.push("-q".into());
+    // The values are aligned, whatever the length of the labels in the current language.
+    let width = [
+        labels.workshop,
+        labels.authors,
+        labels.track,
+        labels.current,
+        labels.progress,
+        labels.last_failure,
+        labels.elapsed,
+    ]
+    .iter()
+    .map(|l| l.chars().count())
+    .max()
+    .unwrap_or_default()
+        + 1;
+    let label = |s: &str| info_style().bold().paint(format!("{s:<width$}"));
+    println!();
+    let metadata = configuration.metadata();
+    if let Some(title) = &metadata.title {
+        let version = metadata
+            .version
+            .as_ref()
+            .map(|v| format!(" (v{v})"))
+            .unwrap_or_default();
+        println!("\t{}{title}{version}", label(labels.workshop));
+    }
+    if !metadata.authors.is_empty() {
+        println!("\t{}{}", label(labels.authors), metadata.authors.join(", "));
+    }
+    if let Some(track) = exercises.track() {
+        println!("\t{}{track}", label(labels.track));
+    }
+    println!("\t{}{}", label(labels.current), next_style().paint(current));
+    let ((n_solved, total), optional) = n_solved(exercises, &statuses);
+    println!(
+        "\t{}{}{}",
+        label(labels.progress),
+        catalog().solved(n_solved, total, percentage(n_solved, total)),
+        optional_suffix(optional)
+    );
+    println!("\t{}{}", label(labels.last_failure), last_failure);
+    println!("\t{}{}", label(labels.elapsed), elapsed);
+    Ok(())
+}
+
+fn progress_report(
+    exercises: &ExerciseCollection,
+    configuration: &ExercisesConfig,
+) -> Result<wr::report::ProgressReport, anyhow::Error> {
+    let mut timings = exercises.timings()?;
+    let reports = exercises
+        .statuses()?
+        .into_iter()
+        .map(|(definition, status)| {
+            let timings = timings
+                .iter()
+                .position(|t| t.definition == definition)
+                .map(|i| timings.swap_remove(i));
+            wr::report::ExerciseReport {
+                optional: exercises.is_optional(&definition),
+                definition,
+                status,
+                timings,
             }
+        })
+        .collect();
+    Ok(wr::report::ProgressReport {
+        metadata: configuration.metadata(),
+        track: exercises.track().map(ToOwned::to_owned),
+        exercises: reports,
+    })
+}
 
-            let mut cmd = std::process::Command::new("cargo");
-            cmd.args(args);
-            verification_commands.push(cmd);
-        }
-        verification_commands.iter_mut().for_each(|cmd| {
-            // We run verification commands from the exercise's directory.
-            cmd.current_dir(
-                manifest_path
-                    .parent()
-                    .expect("Failed to get parent dir for manifest"),
+fn print_stats(exercises: &ExerciseCollection) -> Result<(), anyhow::Error> {
+    let timings = exercises.timings()?;
+    if json_output() {
+        let timings: Vec<_> = timings
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "exercise": t.definition.path(),
+                    "time_to_solve_secs": t.time_to_solve.map(|t| t.as_secs()),
+                    "runs": t.runs,
+                    "run_time_secs": t.run_time.as_secs_f64(),
+                })
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "exercises": timings }));
+    }
+    if timings.is_empty() {
+        println!(
+            "{}",
+            info_style().paint("\n\tYou haven't opened any exercise yet.")
+        );
+        return Ok(());
+    }
+    let format_time_to_solve = |t: Option<std::time::Duration>| {
+        t.map(|t| format_duration(t.as_secs() as i64))
+            .unwrap_or_else(|| "-".into())
+    };
+    println!(
+        "\n\t{}",
+        cargo_style().paint(format!(
+            "{:<36}{:>14}{:>8}{:>14}",
+            "", "Time to solve", "Runs", "Run time"
+        ))
+    );
+    for chapter in
+        timings.chunk_by(|a, b| a.definition.chapter_number() == b.definition.chapter_number())
+    {
+        let first = &chapter[0].definition;
+        let time_to_solve = chapter
+            .iter()
+            .filter_map(|t| t.time_to_solve)
+            .reduce(|a, b| a + b);
+        let runs: usize = chapter.iter().map(|t| t.runs).sum();
+        let run_time: std::time::Duration = chapter.iter().map(|t| t.run_time).sum();
+        println!(
+            "\n\t{}",
+            info_style().bold().paint(format!(
+                "{:<36}{:>14}{:>8}{:>14}",
+                format!("({:02}) {}", first.chapter_number(), first.chapter_name()),
+                format_time_to_solve(time_to_solve),
+                runs,
+                format_run_time(run_time),
+            ))
+        );
+        for t in chapter {
+            println!(
+                "\t{:<36}{:>14}{:>8}{:>14}",
+                format!(
+                    "  ({:02}) {}",
+                    t.definition.exercise_number(),
+                    t.definition.exercise_name()
+                ),
+                format_time_to_solve(t.time_to_solve),
+                t.runs,
+                format_run_time(t.run_time),
             );
-        });
-        for mut verification_cmd in verification_commands {
-            let error_msg = format!("Failed to run: `{:?}`", verification_cmd);
-            let output = verification_cmd.output().expect(&error_msg);
-
-            if !output.status.success() {
-                return TestOutcome::Failure {
-                    command: format!("{:?}", verification_cmd),
-                    details: [output.stderr, output.stdout].concat(),
-                };
-            }
         }
     }
+    Ok(())
+}
 
-    TestOutcome::Success
+fn percentage(n: usize, total: usize) -> usize {
+    if total == 0 {
+        return 0;
+    }
+    n * 100 / total
 }
 
-#[derive(PartialEq)]
-enum TestOutcome {
-    Success,
-    Failure { command: String, details: Vec<u8> },
+/// Show why `exercise` failed verification.
+///
+/// Only the last lines of the output are shown, see `failure_lines` in `.wr.toml`:
+/// learners can see the rest with `wr last-failure --full`.
+fn print_failure_message(
+    exercises: &ExerciseCollection,
+    exercise: &ExerciseDefinition,
+    stage: &str,
+    command: &str,
+    details: &[u8],
+) {
+    let exercise_dir = exercise.manifest_folder_path(exercises.exercises_dir_of(exercise));
+    let output = String::from_utf8_lossy(details);
+    let max_lines = FAILURE_LINES.get().copied().unwrap_or_default();
+    let lines: Vec<_> = output.lines().collect();
+    if max_lines == 0 || lines.len() <= max_lines {
+        return _print_failure_message(&exercise_dir, stage, command, &output);
+    }
+    let n_omitted = lines.len() - max_lines;
+    let tail = format!(
+        "{}\n{}",
+        catalog().lines_omitted(n_omitted),
+        lines[n_omitted..].join("\n")
+    );
+    _print_failure_message(&exercise_dir, stage, command, &tail);
+    if show_progress() {
+        // The first error may well be among the lines that were left out.
+        print_failed_location(&exercise_dir, &output);
+        println!(
+            "{}",
+            cargo_style().paint(format!(
+                "\t{}",
+                catalog().see_everything(&format!("wr last-failure {} --full", exercise.path()))
+            ))
+        );
+    }
 }
 
-fn print_opened_message(exercise: &ExerciseDefinition, exercises_dir: &Path) {
+/// The failure message itself, with `output` in full.
+///
+/// The locations in `output` (e.g. `src/lib.rs:3:5`) are relative to `exercise_dir`.
+fn _print_failure_message(exercise_dir: &Path, stage: &str, command: &str, output: &str) {
+    if !show_progress() {
+        return;
+    }
+    let mut output = highlight_assertions(output);
+    if use_hyperlinks() {
+        output = wr::hyperlinks::link_locations(&output, exercise_dir);
+    }
     println!(
-        "{} {}",
-        next_style().paint("\n\tAhead of you lies"),
-        next_style().bold().paint(format!("{exercise}")),
-    );
-    let relative_path = exercise.manifest_folder_path(exercises_dir);
-    let open_msg = format!(
-        "\n\tOpen {:?} in your editor and get started!\n\tRun `wr` again to compile the exercise and execute its tests.",
-        relative_path
+        "\n\t{}\n\n{}\n\t{}\n{}\n{}\n",
+        info_style().paint(format!("{}\n\n", messages().failure())),
+        catalog().stage_failed(stage),
+        cargo_style().paint(&command),
+        catalog().output(),
+        cargo_style().paint(textwrap::indent(&output, "\t"))
     );
-    println!("{}", next_style().paint(open_msg));
 }
 
-fn print_failure_message(command: &str, details: &[u8]) {
+/// Point to the first location that went wrong according to `output`, if any.
+fn print_failed_location(exercise_dir: &Path, output: &str) {
+    let Some(location) = wr::hyperlinks::first_location(output) else {
+        return;
+    };
+    let location = if use_hyperlinks() {
+        wr::hyperlinks::link_location(location, exercise_dir)
+    } else {
+        location.to_owned()
+    };
     println!(
-        "\n\t{}\n\nFailed to run:\n\t{}\nOutput:\n{}\n",
-        info_style()
-            .paint("Meditate on your approach and return. Mountains are merely mountains.\n\n"),
-        cargo_style().paint(&command),
-        cargo_style().paint(textwrap::indent(
-            &String::from_utf8_lossy(details).to_string(),
-            "\t"
-        ))
+        "\t{}",
+        cargo_style().paint(catalog().first_error(&location))
     );
 }
 
+/// What `wr` did, as reported with `--output json`.
+#[derive(serde::Serialize)]
+struct JsonProgress {
+    /// The exercises that were verified, in order.
+    verified: Vec<JsonVerification>,
+    /// The exercise that was opened, if any.
+    opened: Option<String>,
+    /// The exercise that would be opened next, if it wasn't opened.
+    next: Option<String>,
+    /// `true` if every exercise has been solved.
+    completed: bool,
+}
+
+/// The outcome of verifying an exercise, as reported with `--output json`.
+#[derive(serde::Serialize)]
+struct JsonVerification {
+    /// The exercise, as `<chapter folder>/<exercise folder>`.
+    exercise: String,
+    passed: bool,
+    optional: bool,
+    /// How long the verification took.
+    duration_secs: f64,
+    /// Why the verification failed, if it did.
+    failure: Option<LastFailure>,
+}
+
+impl JsonVerification {
+    fn new(
+        exercises: &ExerciseCollection,
+        definition: &ExerciseDefinition,
+        outcome: &TestOutcome,
+    ) -> Result<Self, anyhow::Error> {
+        // Every verification is recorded as an attempt, with its duration.
+        let duration = exercises
+            .attempts(Some(definition))?
+            .last()
+            .map(|a| a.duration)
+            .unwrap_or_default();
+        let failure = outcome.failure();
+        Ok(Self {
+            exercise: definition.path(),
+            passed: failure.is_none(),
+            optional: exercises.is_optional(definition),
+            duration_secs: duration.as_secs_f64(),
+            failure,
+        })
+    }
+}
+
+/// An exercise, as listed with `--output json`.
+#[derive(serde::Serialize)]
+struct JsonExercise {
+    /// The exercise, as `<chapter folder>/<exercise folder>`.
+    exercise: String,
+    chapter_number: u16,
+    chapter_name: String,
+    exercise_number: u16,
+    exercise_name: String,
+    status: ExerciseStatus,
+    optional: bool,
+    difficulty: Option<wr::Difficulty>,
+    tags: Vec<String>,
+}
+
+impl JsonExercise {
+    fn new(
+        exercises: &ExerciseCollection,
+        definition: &ExerciseDefinition,
+        status: ExerciseStatus,
+    ) -> Self {
+        Self {
+            exercise: definition.path(),
+            chapter_number: definition.chapter_number(),
+            chapter_name: definition.chapter_name().to_owned(),
+            exercise_number: definition.exercise_number(),
+            exercise_name: definition.exercise_name().to_owned(),
+            status,
+            optional: exercises.is_optional(definition),
+            difficulty: exercises.difficulty(definition),
+            tags: exercises.tags(definition).to_vec(),
+        }
+    }
+}
+
 pub fn info_style() -> yansi::Style {
     yansi::Style::new(yansi::Color::Default)
 }
@@ -409,11 +5295,43 @@ pub fn failure_style() -> yansi::Style {
     yansi::Style::new(yansi::Color::Red)
 }
 
-/// Determine if our terminal output should leverage colors via ANSI escape codes.
+/// Determine if paths should be printed as hyperlinks, see [`wr::hyperlinks`].
+fn use_hyperlinks() -> bool {
+    use_ansi_colours() && wr::hyperlinks::supported()
+}
+
+/// `text`, as a hyperlink to `path` if the terminal supports them.
+fn link_to(path: &Path, text: &str) -> String {
+    match use_hyperlinks()
+        .then(|| wr::hyperlinks::file_url(path))
+        .flatten()
+    {
+        Some(url) => wr::hyperlinks::link(&url, text),
+        None => text.to_owned(),
+    }
+}
+
+/// Determine if our terminal output should leverage colors via ANSI escape codes, see `--color`.
 pub fn use_ansi_colours() -> bool {
-    if cfg!(target_os = "windows") {
-        Paint::enable_windows_ascii()
-    } else {
-        true
+    let supported = || {
+        if cfg!(target_os = "windows") {
+            Paint::enable_windows_ascii()
+        } else {
+            true
+        }
+    };
+    let env_is_set = |name| std::env::var_os(name).is_some_and(|v| !v.is_empty() && v != "0");
+    match COLOR.get().copied().unwrap_or(ColorChoice::Auto) {
+        ColorChoice::Always => {
+            supported();
+            true
+        }
+        ColorChoice::Never => false,
+        ColorChoice::Auto if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) => false,
+        ColorChoice::Auto if env_is_set("CLICOLOR_FORCE") => {
+            supported();
+            true
+        }
+        ColorChoice::Auto => std::io::stdout().is_terminal() && supported(),
     }
 }
@@ -0,0 +1,270 @@
+//! Keep the schema of the progress database up to date.
+//!
+//! Learners often pull a new version of `wr` in the middle of a workshop: their `progress.db`
+//! must be upgraded, without losing track of what they have already solved.
+//! The version of the schema is stored in the `schema_version` table; every migration that
+//! hasn't been applied yet is run, in order, when the database is opened.
+use anyhow::{bail, Context};
+use rusqlite::{Connection, OptionalExtension};
+
+type Migration = fn(&Connection) -> Result<(), rusqlite::Error>;
+
+/// All migrations, from the oldest to the most recent.
+///
+/// Once released, a migration must never be modified: append a new one instead.
+const MIGRATIONS: &[Migration] = &[baseline, last_failure, notes, fingerprint, events];
+
+/// Bring the database up to the latest version of the schema.
+pub(crate) fn migrate(connection: &mut Connection) -> Result<(), anyhow::Error> {
+    let err_msg = "Failed to upgrade our SQLite database to track your progress";
+    connection
+        .execute(
+            "CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)",
+            [],
+        )
+        .context(err_msg)?;
+    let version: usize = connection
+        .query_row("SELECT version FROM schema_version", [], |row| row.get(0))
+        .optional()
+        .context(err_msg)?
+        .unwrap_or(0);
+    if version > MIGRATIONS.len() {
+        bail!(
+            "Your progress database was created by a more recent version of `wr`.\n\
+            Update `wr` with `cargo install workshop-runner` to keep going."
+        );
+    }
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        // Either the whole migration is applied, or none of it is.
+        let transaction = connection.transaction().context(err_msg)?;
+        migration(&transaction).with_context(|| format!("{err_msg} (migration #{})", i + 1))?;
+        transaction
+            .execute("DELETE FROM schema_version", [])
+            .context(err_msg)?;
+        transaction
+            .execute("INSERT INTO schema_version (version) VALUES (?1)", [i + 1])
+            .context(err_msg)?;
+        transaction.commit().context(err_msg)?;
+    }
+    Ok(())
+}
+
+/// The schema as of the introduction of versioning.
+///
+/// Databases created by older versions of `wr` may be anywhere in between an empty database
+/// and this schema, so every step is idempotent.
+fn baseline(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS open_exercises (
+            chapter TEXT NOT NULL,
+            exercise TEXT NOT NULL,
+            solved INTEGER NOT NULL,
+            PRIMARY KEY (chapter, exercise)
+        )",
+        [],
+    )?;
+    for (column, definition) in [
+        ("failed", "INTEGER NOT NULL DEFAULT 0"),
+        ("skipped", "INTEGER NOT NULL DEFAULT 0"),
+        ("hints_used", "INTEGER NOT NULL DEFAULT 0"),
+        // Unix timestamp, in seconds.
+        ("opened_at", "INTEGER"),
+        // Unix timestamp, in seconds, of the first time the exercise was solved.
+        ("solved_at", "INTEGER"),
+        // How many times the exercise has been verified, i.e. the number of attempts at
+        // solving it, and for how long (in milliseconds).
+        ("runs", "INTEGER NOT NULL DEFAULT 0"),
+        ("run_time_ms", "INTEGER NOT NULL DEFAULT 0"),
+        // A hash of the exercise's sources, as of the last time it was solved.
+        ("source_hash", "TEXT"),
+    ] {
+        add_column_if_missing(connection, "open_exercises", column, definition)?;
+    }
+
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS bookmarks (
+            chapter TEXT NOT NULL,
+            exercise TEXT NOT NULL,
+            PRIMARY KEY (chapter, exercise)
+        )",
+        [],
+    )?;
+
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS attempts (
+            id INTEGER PRIMARY KEY,
+            chapter TEXT NOT NULL,
+            exercise TEXT NOT NULL,
+            -- Unix timestamp, in seconds.
+            attempted_at INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            -- NULL if the verification succeeded.
+            failed_command TEXT
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Keep the output of the last failed verification of each exercise.
+fn last_failure(connection: &Connection) -> Result<(), rusqlite::Error> {
+    for column in [
+        "last_failure_stage",
+        "last_failure_command",
+        "last_failure_output",
+    ] {
+        add_column_if_missing(connection, "open_exercises", column, "TEXT")?;
+    }
+    Ok(())
+}
+
+/// Let learners attach notes to exercises.
+fn notes(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS notes (
+            id INTEGER PRIMARY KEY,
+            chapter TEXT NOT NULL,
+            exercise TEXT NOT NULL,
+            -- Unix timestamp, in seconds.
+            created_at INTEGER NOT NULL,
+            text TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Recognise exercises that have been renamed or renumbered, see `ExerciseDefinition::fingerprint`.
+fn fingerprint(connection: &Connection) -> Result<(), rusqlite::Error> {
+    add_column_if_missing(connection, "open_exercises", "fingerprint", "TEXT")
+}
+
+/// Keep track of every change in the status of exercises.
+fn events(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS events (
+            id INTEGER PRIMARY KEY,
+            chapter TEXT NOT NULL,
+            exercise TEXT NOT NULL,
+            -- Unix timestamp, in seconds.
+            occurred_at INTEGER NOT NULL,
+            -- `opened`, `solved`, `unsolved` or `skipped`.
+            kind TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Add a column to an existing table, unless it's already there.
+///
+/// `CREATE TABLE IF NOT EXISTS` won't touch tables created by older versions of `wr`,
+/// so new columns have to be added explicitly.
+fn add_column_if_missing(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = connection.prepare(&format!("PRAGMA table_info({table})"))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|c| c == column);
+    if !exists {
+        connection.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(connection: &Connection) -> usize {
+        connection
+            .query_row("SELECT version FROM schema_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn columns(connection: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = connection
+            .prepare(&format!("PRAGMA table_info({table})"))
+            .unwrap();
+        stmt.query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn a_new_database_gets_the_latest_schema() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        assert_eq!(version(&connection), MIGRATIONS.len());
+        for table in ["open_exercises", "bookmarks", "attempts", "notes", "events"] {
+            assert!(
+                !columns(&connection, table).is_empty(),
+                "{table} is missing"
+            );
+        }
+        assert!(columns(&connection, "open_exercises").contains(&"fingerprint".to_owned()));
+    }
+
+    #[test]
+    fn migrating_again_is_a_no_op() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        migrate(&mut connection).unwrap();
+        assert_eq!(version(&connection), MIGRATIONS.len());
+        let n_versions: usize = connection
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(n_versions, 1);
+    }
+
+    #[test]
+    fn progress_recorded_before_versioning_is_kept() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE open_exercises (
+                    chapter TEXT NOT NULL,
+                    exercise TEXT NOT NULL,
+                    solved INTEGER NOT NULL,
+                    PRIMARY KEY (chapter, exercise)
+                );
+                INSERT INTO open_exercises VALUES ('01_intro', '00_welcome', 1);",
+            )
+            .unwrap();
+        migrate(&mut connection).unwrap();
+        let (solved, runs): (bool, usize) = connection
+            .query_row(
+                "SELECT solved, runs FROM open_exercises WHERE chapter = '01_intro'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(solved);
+        assert_eq!(runs, 0);
+        assert_eq!(version(&connection), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn databases_from_newer_versions_are_rejected() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        connection
+            .execute(
+                "UPDATE schema_version SET version = ?1",
+                [MIGRATIONS.len() + 1],
+            )
+            .unwrap();
+        let error = migrate(&mut connection).unwrap_err();
+        assert!(error.to_string().contains("more recent version"), "{error}");
+    }
+}
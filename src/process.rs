@@ -0,0 +1,281 @@
+//! Run external commands, e.g. to verify exercises, with a time limit, resource limits and some input.
+use anyhow::Context;
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// Limits on the resources that a command, and every process it spawns, can use.
+///
+/// They are meant for the code under test, see [`run_limited`]: the compiler may well need more.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The maximum amount of memory each process can use, in bytes.
+    ///
+    /// On Unix, it's a limit on the size of the virtual address space (`RLIMIT_AS`).
+    pub memory_bytes: Option<u64>,
+    /// The maximum amount of CPU time each process can use.
+    pub cpu_time: Option<Duration>,
+}
+
+impl ResourceLimits {
+    /// `true` if there are no limits at all.
+    pub fn is_empty(&self) -> bool {
+        self.memory_bytes.is_none() && self.cpu_time.is_none()
+    }
+}
+
+/// Run `cmd` to completion and collect its output, like [`Command::output`], with a few additions:
+///
+/// - `stdin`, if specified, is piped into the command;
+/// - if `timeout` is specified, the command (alongside any process it spawned) is killed if
+///   it's still running after that long. The returned output contains whatever it produced until then;
+/// - if `stream` is specified, what the command prints is also forwarded there as it comes,
+///   rather than only being available once the command is done.
+pub fn output(
+    cmd: &mut Command,
+    stdin: Option<&[u8]>,
+    timeout: Option<Duration>,
+    stream: Option<Stream>,
+) -> Result<TimedOutput, anyhow::Error> {
+    let err_msg = format!("Failed to run: `{cmd:?}`");
+    if stdin.is_none() && timeout.is_none() && stream.is_none() {
+        let output = cmd.output().context(err_msg.clone())?;
+        return Ok(TimedOutput {
+            output,
+            timed_out: false,
+        });
+    }
+
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+    // Put the command in its own process group, so that we can kill the processes it spawns
+    // too (e.g. the test binary spawned by `cargo test`).
+    #[cfg(unix)]
+    if timeout.is_some() {
+        std::os::unix::process::CommandExt::process_group(cmd, 0);
+    }
+    let mut child = cmd.spawn().context(err_msg)?;
+    if let (Some(mut pipe), Some(input)) = (child.stdin.take(), stdin) {
+        let input = input.to_vec();
+        // The command may exit without reading its input: we don't care if writing fails.
+        std::thread::spawn(move || {
+            let _ = pipe.write_all(&input);
+        });
+    }
+    let (stdout, stderr) = match stream {
+        None => (
+            read_in_background(child.stdout.take(), None),
+            read_in_background(child.stderr.take(), None),
+        ),
+        Some(Stream::Terminal) => (
+            read_in_background(child.stdout.take(), Some(Box::new(std::io::stdout()))),
+            read_in_background(child.stderr.take(), Some(Box::new(std::io::stderr()))),
+        ),
+        Some(Stream::Stderr) => (
+            read_in_background(child.stdout.take(), Some(Box::new(std::io::stderr()))),
+            read_in_background(child.stderr.take(), Some(Box::new(std::io::stderr()))),
+        ),
+    };
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status, false);
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break (kill(&mut child)?, true);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    Ok(TimedOutput {
+        output: Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        },
+        timed_out,
+    })
+}
+
+/// Run `cmd` to completion, with its standard streams inherited, without letting it use more
+/// resources than allowed by `limits`. It's usually killed, or sees its allocations fail,
+/// if it goes over them.
+pub fn run_limited(cmd: &mut Command, limits: ResourceLimits) -> Result<ExitStatus, anyhow::Error> {
+    let err_msg = format!("Failed to run: `{cmd:?}`");
+    #[cfg(unix)]
+    unix::set_limits(cmd, limits);
+    let mut child = cmd.spawn().context(err_msg.clone())?;
+    // Windows can only limit processes that are already running.
+    #[cfg(windows)]
+    let _job = windows::Job::new(&child, limits)?;
+    child.wait().context(err_msg)
+}
+
+/// Where [`output`] forwards what the command prints, as it comes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// Stdout to stdout, stderr to stderr.
+    Terminal,
+    /// Everything to stderr, e.g. because stdout is meant for another program.
+    Stderr,
+}
+
+/// The output of a command run with [`output`].
+pub struct TimedOutput {
+    pub output: Output,
+    /// `true` if the command was killed because it ran out of time.
+    pub timed_out: bool,
+}
+
+/// Read everything from `source`, copying it to `echo` along the way, if specified.
+fn read_in_background<R: Read + Send + 'static>(
+    source: Option<R>,
+    mut echo: Option<Box<dyn Write + Send>>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = vec![];
+        let Some(mut source) = source else {
+            return buffer;
+        };
+        let Some(echo) = echo.as_mut() else {
+            let _ = source.read_to_end(&mut buffer);
+            return buffer;
+        };
+        let mut chunk = [0; 8192];
+        loop {
+            match source.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => {
+                    buffer.extend_from_slice(&chunk[..n]);
+                    // The output is still captured if the terminal goes away.
+                    let _ = echo.write_all(&chunk[..n]).and_then(|_| echo.flush());
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        buffer
+    })
+}
+
+/// Kill a process and all the processes it spawned.
+fn kill(child: &mut Child) -> Result<ExitStatus, anyhow::Error> {
+    let pid = child.id().to_string();
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{pid}")])
+        .status();
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid])
+        .status();
+    let _ = child.kill();
+    Ok(child.wait()?)
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::ResourceLimits;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    /// Apply `limits` to the process spawned by `cmd`, right before it starts executing.
+    pub(super) fn set_limits(cmd: &mut Command, limits: ResourceLimits) {
+        let memory = limits.memory_bytes;
+        let cpu_time = limits.cpu_time.map(|cpu_time| cpu_time.as_secs().max(1));
+        // SAFETY: `setrlimit` is async-signal-safe, and we don't allocate in the closure.
+        unsafe {
+            cmd.pre_exec(move || {
+                // The type of the resource identifiers differs across platforms: we let the compiler
+                // infer it.
+                let set_limit = |resource, value: u64| {
+                    let limit = libc::rlimit {
+                        rlim_cur: value as libc::rlim_t,
+                        rlim_max: value as libc::rlim_t,
+                    };
+                    if libc::setrlimit(resource, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    Ok(())
+                };
+                if let Some(memory) = memory {
+                    set_limit(libc::RLIMIT_AS, memory)?;
+                }
+                if let Some(cpu_time) = cpu_time {
+                    set_limit(libc::RLIMIT_CPU, cpu_time)?;
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::ResourceLimits;
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+        JOB_OBJECT_LIMIT_PROCESS_TIME,
+    };
+
+    /// A Job Object that enforces resource limits on the processes assigned to it.
+    ///
+    /// They are killed when it's dropped.
+    pub(super) struct Job(HANDLE);
+
+    impl Job {
+        /// Create a job with the given `limits` and assign `child` (and its future children) to it.
+        pub(super) fn new(child: &Child, limits: ResourceLimits) -> Result<Self, anyhow::Error> {
+            // SAFETY: we pass valid pointers, and check the outcome of every call.
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return Err(std::io::Error::last_os_error().into());
+                }
+                let job = Job(handle);
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                if let Some(memory) = limits.memory_bytes {
+                    info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                    info.ProcessMemoryLimit = memory as usize;
+                }
+                if let Some(cpu_time) = limits.cpu_time {
+                    info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                    // In units of 100 nanoseconds.
+                    info.BasicLimitInformation.PerProcessUserTimeLimit =
+                        (cpu_time.as_nanos() / 100) as i64;
+                }
+                if SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const _,
+                    std::mem::size_of_val(&info) as u32,
+                ) == 0
+                    || AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) == 0
+                {
+                    return Err(std::io::Error::last_os_error().into());
+                }
+                Ok(job)
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: the handle is valid, and we own it.
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,1095 @@
+//! Where your progress on a collection of exercises is persisted.
+//!
+//! By default, progress is tracked in a SQLite database, `progress.db`, in the exercises directory.
+//! Collections can opt into a plain JSON file (`progress.json`) instead, e.g. to commit progress
+//! to `git` and review it as a human-readable diff.
+//! Either way, each profile (see [`ProgressStoreKind::open`]) has its own, independent progress.
+use crate::{
+    migrations, unix_timestamp, ExerciseDefinition, ExerciseProgress, LastFailure, NamingPattern,
+};
+use anyhow::{bail, Context};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The storage backends that can be selected in the collection configuration.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressStoreKind {
+    /// A SQLite database, `progress.db`.
+    #[default]
+    Sqlite,
+    /// A JSON file, `progress.json`.
+    Json,
+}
+
+impl ProgressStoreKind {
+    /// Open (or create) the store for the exercises in `exercises_dir`.
+    ///
+    /// Each profile gets a store of its own (e.g. `progress-alice.db`), so that several people
+    /// can work on the same checkout without stepping on each other's toes.
+    pub fn open(
+        self,
+        exercises_dir: &Path,
+        profile: Option<&str>,
+    ) -> Result<Box<dyn ProgressStore>, anyhow::Error> {
+        let stem = match profile {
+            None => "progress".to_owned(),
+            Some(profile) => {
+                if profile.is_empty()
+                    || !profile
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                {
+                    bail!(
+                        "`{profile}` is not a valid profile name: only letters, digits, `-` and `_` are allowed"
+                    );
+                }
+                format!("progress-{profile}")
+            }
+        };
+        Ok(match self {
+            ProgressStoreKind::Sqlite => Box::new(SqliteStore::open(
+                &exercises_dir.join(format!("{stem}.db")),
+            )?),
+            ProgressStoreKind::Json => {
+                Box::new(JsonStore::open(exercises_dir.join(format!("{stem}.json")))?)
+            }
+        })
+    }
+}
+
+/// A verification attempt, as persisted by a [`ProgressStore`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AttemptRecord {
+    pub chapter: String,
+    pub exercise: String,
+    /// Unix timestamp, in seconds.
+    pub attempted_at: i64,
+    pub duration_ms: u64,
+    /// `None` if the verification succeeded.
+    #[serde(default)]
+    pub failed_command: Option<String>,
+}
+
+/// A note attached to an exercise, as persisted by a [`ProgressStore`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NoteRecord {
+    pub chapter: String,
+    pub exercise: String,
+    /// Unix timestamp, in seconds.
+    pub created_at: i64,
+    pub text: String,
+}
+
+/// A change in the status of an exercise, as persisted by a [`ProgressStore`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EventRecord {
+    pub chapter: String,
+    pub exercise: String,
+    /// Unix timestamp, in seconds.
+    pub occurred_at: i64,
+    pub kind: EventKind,
+}
+
+/// The changes in the status of an exercise that are recorded as events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    /// The exercise was opened for the first time (or for the first time since it was reset).
+    Opened,
+    /// The exercise passed verification, while it wasn't solved.
+    Solved,
+    /// The exercise failed verification, while it was solved.
+    Unsolved,
+    /// The exercise was skipped.
+    Skipped,
+}
+
+impl EventKind {
+    /// How the event is stored in the `kind` column of the `events` table.
+    fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Opened => "opened",
+            EventKind::Solved => "solved",
+            EventKind::Unsolved => "unsolved",
+            EventKind::Skipped => "skipped",
+        }
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            EventKind::Opened,
+            EventKind::Solved,
+            EventKind::Unsolved,
+            EventKind::Skipped,
+        ]
+        .into_iter()
+        .find(|k| k.as_str() == s)
+        .with_context(|| format!("`{s}` is not a known kind of event"))
+    }
+}
+
+/// The operations [`ExerciseCollection`](crate::ExerciseCollection) needs to persist progress.
+///
+/// Exercises are identified by the name of their chapter and exercise folders,
+/// e.g. `01_intro` and `00_welcome`.
+pub trait ProgressStore {
+    /// The progress on every exercise that has been opened, sorted by chapter and exercise.
+    fn exercises(&self) -> Result<Vec<ExerciseProgress>, anyhow::Error>;
+
+    /// The progress on a single exercise, if it has been opened.
+    fn exercise(
+        &self,
+        chapter: &str,
+        exercise: &str,
+    ) -> Result<Option<ExerciseProgress>, anyhow::Error> {
+        Ok(self
+            .exercises()?
+            .into_iter()
+            .find(|e| e.chapter == chapter && e.exercise == exercise))
+    }
+
+    /// Insert the progress on some exercises, replacing what was stored for them, if anything.
+    ///
+    /// Either all of them are saved, or none of them is.
+    fn save_exercises(&self, exercises: &[ExerciseProgress]) -> Result<(), anyhow::Error>;
+
+    /// Record the progress on an exercise that has just been opened, returning `false` if it
+    /// had already been opened.
+    fn open_exercise(&self, progress: &ExerciseProgress) -> Result<bool, anyhow::Error>;
+
+    /// Apply a change to the progress on an exercise, if it has been opened.
+    ///
+    /// Nobody else can modify the progress on the exercise in the meantime, not even
+    /// another `wr` process.
+    fn update_exercise(
+        &self,
+        chapter: &str,
+        exercise: &str,
+        f: &mut dyn FnMut(&mut ExerciseProgress),
+    ) -> Result<(), anyhow::Error>;
+
+    /// Forget the progress on some `(chapter, exercise)` pairs.
+    ///
+    /// Either all of them are removed, or none of them is.
+    fn remove_exercises(&self, exercises: &[(String, String)]) -> Result<(), anyhow::Error>;
+
+    /// Move everything recorded for an exercise (progress, attempts, bookmark, notes and events)
+    /// to another one, e.g. because it was renamed.
+    ///
+    /// `to` must not have been opened.
+    fn rename_exercise(&self, from: (&str, &str), to: (&str, &str)) -> Result<(), anyhow::Error>;
+
+    /// Every verification attempt, from the oldest to the most recent.
+    fn attempts(&self) -> Result<Vec<AttemptRecord>, anyhow::Error>;
+
+    /// Record a verification attempt, adding it to the number of runs (and to the total
+    /// run time) of its exercise.
+    fn record_attempt(&self, attempt: &AttemptRecord) -> Result<(), anyhow::Error>;
+
+    /// The `(chapter, exercise)` pairs that have been bookmarked.
+    fn bookmarks(&self) -> Result<BTreeSet<(String, String)>, anyhow::Error>;
+
+    /// Bookmark an exercise, returning `false` if it was already bookmarked.
+    fn add_bookmark(&self, chapter: &str, exercise: &str) -> Result<bool, anyhow::Error>;
+
+    /// Remove a bookmark, returning `false` if there was none.
+    fn remove_bookmark(&self, chapter: &str, exercise: &str) -> Result<bool, anyhow::Error>;
+
+    /// Every note, from the oldest to the most recent.
+    fn notes(&self) -> Result<Vec<NoteRecord>, anyhow::Error>;
+
+    fn add_note(&self, note: &NoteRecord) -> Result<(), anyhow::Error>;
+
+    /// Every change in the status of exercises, from the oldest to the most recent.
+    fn events(&self) -> Result<Vec<EventRecord>, anyhow::Error>;
+
+    /// Append an event to the history. Events are never modified afterwards.
+    fn record_event(&self, event: &EventRecord) -> Result<(), anyhow::Error>;
+
+    /// Forget the progress on all exercises, as well as every attempt.
+    /// Bookmarks, notes and events are kept.
+    fn reset(&self) -> Result<(), anyhow::Error>;
+
+    /// Where the progress is stored.
+    fn path(&self) -> &Path;
+
+    /// Save a consistent copy of everything in the store to `to`, in the store's own format.
+    ///
+    /// `to` must not exist yet.
+    fn backup(&self, to: &Path) -> Result<(), anyhow::Error>;
+
+    /// Replace everything in the store with the contents of a copy saved by
+    /// [`ProgressStore::backup`].
+    fn restore(&self, from: &Path) -> Result<(), anyhow::Error>;
+
+    /// Look for damage to the underlying storage (e.g. corrupted database pages), returning
+    /// a description of every problem found.
+    fn integrity_check(&self) -> Result<Vec<String>, anyhow::Error>;
+
+    /// Forget everything recorded for some `(chapter, exercise)` pairs: progress, attempts,
+    /// bookmarks, notes and events.
+    ///
+    /// Either all of them are removed, or none of them is.
+    fn purge_exercises(&self, exercises: &[(String, String)]) -> Result<(), anyhow::Error>;
+}
+
+/// The problems found by [`check`].
+#[derive(Debug, Default)]
+pub struct ProgressCheck {
+    /// Damage to the underlying storage, see [`ProgressStore::integrity_check`].
+    /// `wr` can't repair it: a backup has to be restored.
+    pub corruption: Vec<String>,
+    /// The `(chapter, exercise)` pairs recorded in the store that aren't valid exercise names.
+    /// They can be removed with [`ProgressStore::purge_exercises`].
+    pub invalid_exercises: BTreeSet<(String, String)>,
+}
+
+impl ProgressCheck {
+    pub fn is_ok(&self) -> bool {
+        self.corruption.is_empty() && self.invalid_exercises.is_empty()
+    }
+}
+
+/// Check that the store is in good shape and that every exercise it refers to can be parsed
+/// by [`ExerciseDefinition::new`], according to `naming`.
+pub fn check(
+    store: &dyn ProgressStore,
+    naming: &NamingPattern,
+) -> Result<ProgressCheck, anyhow::Error> {
+    let corruption = store.integrity_check()?;
+    if !corruption.is_empty() {
+        // The contents of the store can't be trusted.
+        return Ok(ProgressCheck {
+            corruption,
+            ..Default::default()
+        });
+    }
+    let recorded = store
+        .exercises()?
+        .into_iter()
+        .map(|e| (e.chapter, e.exercise))
+        .chain(
+            store
+                .attempts()?
+                .into_iter()
+                .map(|a| (a.chapter, a.exercise)),
+        )
+        .chain(store.notes()?.into_iter().map(|n| (n.chapter, n.exercise)))
+        .chain(store.events()?.into_iter().map(|e| (e.chapter, e.exercise)))
+        .chain(store.bookmarks()?);
+    let invalid_exercises = recorded
+        .filter(|(chapter, exercise)| {
+            ExerciseDefinition::new(chapter.as_ref(), exercise.as_ref(), naming).is_err()
+        })
+        .collect();
+    Ok(ProgressCheck {
+        corruption,
+        invalid_exercises,
+    })
+}
+
+/// Save a copy of everything in the store (progress, attempts, bookmarks and notes).
+///
+/// Unless a destination is specified, the copy is saved next to your progress, in a
+/// `progress-backups` folder, with the current date and time in its name.
+/// If the destination is an existing folder, the copy is saved in it, with the same name.
+/// It returns where the copy has been saved.
+pub fn backup(store: &dyn ProgressStore, to: Option<&Path>) -> Result<PathBuf, anyhow::Error> {
+    let progress_path = store.path();
+    let dir = match to {
+        None => progress_path.with_file_name(BACKUPS_DIR),
+        Some(to) if to.is_dir() => to.to_owned(),
+        Some(to) => {
+            if to.exists() {
+                bail!("{to:?} already exists: pick another destination for your backup");
+            }
+            store.backup(to)?;
+            return Ok(to.to_owned());
+        }
+    };
+    fs_err::create_dir_all(&dir).context("Failed to create the folder for your backups")?;
+    let stem = progress_path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or("progress");
+    let extension = progress_path
+        .extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+    let timestamp = utc_date_time(unix_timestamp());
+    // Several backups may be taken within the same second, e.g. right before a restore.
+    let to = (1..)
+        .map(|i| match i {
+            1 => dir.join(format!("{stem}-{timestamp}.{extension}")),
+            i => dir.join(format!("{stem}-{timestamp}-{i}.{extension}")),
+        })
+        .find(|p| !p.exists())
+        .unwrap();
+    store.backup(&to)?;
+    Ok(to)
+}
+
+/// The folder where backups of your progress are saved, next to the progress itself.
+const BACKUPS_DIR: &str = "progress-backups";
+
+/// Format a Unix timestamp (in seconds) as a UTC date and time, e.g. `20240501-153000`.
+fn utc_date_time(timestamp: i64) -> String {
+    let (days, seconds) = (timestamp.div_euclid(86400), timestamp.rem_euclid(86400));
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// How long to wait for another process to release the progress store before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Progress stored in a SQLite database.
+pub struct SqliteStore {
+    connection: Connection,
+    path: PathBuf,
+}
+
+impl SqliteStore {
+    /// Open the database at `path` (or create it, if it doesn't exist yet), upgrading its schema
+    /// if needed.
+    pub fn open(path: &Path) -> Result<Self, anyhow::Error> {
+        let mut connection = Connection::open(path)
+            .context("Failed to create a SQLite database to track your progress")?;
+        // `wr watch` may be running in another terminal: wait for it to release the database,
+        // rather than failing with "database is locked".
+        // Write-ahead logging lets readers and a writer work concurrently in the meantime.
+        connection
+            .busy_timeout(BUSY_TIMEOUT)
+            .and_then(|_| connection.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(())))
+            .context("Failed to configure the SQLite database to track your progress")?;
+        migrations::migrate(&mut connection)?;
+        Ok(Self {
+            connection,
+            path: path.to_owned(),
+        })
+    }
+
+    /// Start a transaction that takes the write lock right away, so that what we read
+    /// can't be modified by another process before we write.
+    fn write_transaction(&self) -> Result<Transaction<'_>, rusqlite::Error> {
+        Transaction::new_unchecked(&self.connection, TransactionBehavior::Immediate)
+    }
+
+    /// Replace the contents of every table with those of the (up to date) database at `path`.
+    fn restore_from(&self, path: &Path) -> Result<(), anyhow::Error> {
+        self.connection.execute(
+            "ATTACH DATABASE ?1 AS backup",
+            [path
+                .to_str()
+                .context("The path of the backup must be valid UTF-8")?],
+        )?;
+        let result = (|| {
+            let transaction = self.write_transaction()?;
+            for table in ["open_exercises", "bookmarks", "attempts", "notes", "events"] {
+                let columns = transaction
+                    .prepare(&format!("PRAGMA main.table_info({table})"))?
+                    .query_map([], |row| row.get::<_, String>(1))?
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ");
+                transaction.execute(&format!("DELETE FROM main.{table}"), [])?;
+                transaction.execute(
+                    &format!(
+                        "INSERT INTO main.{table} ({columns}) SELECT {columns} FROM backup.{table}"
+                    ),
+                    [],
+                )?;
+            }
+            transaction.commit()
+        })();
+        self.connection.execute("DETACH DATABASE backup", [])?;
+        Ok(result?)
+    }
+
+    fn save_exercises_in(
+        &self,
+        transaction: &Transaction,
+        exercises: &[ExerciseProgress],
+    ) -> Result<(), rusqlite::Error> {
+        for e in exercises {
+            transaction.execute(
+                "INSERT OR REPLACE INTO open_exercises (chapter, exercise, solved, failed, skipped, hints_used, opened_at, solved_at, runs, run_time_ms, source_hash, last_failure_stage, last_failure_command, last_failure_output, fingerprint) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                params![
+                    e.chapter,
+                    e.exercise,
+                    e.solved,
+                    e.failed,
+                    e.skipped,
+                    e.hints_used,
+                    e.opened_at,
+                    e.solved_at,
+                    e.runs,
+                    e.run_time_ms,
+                    e.source_hash,
+                    e.last_failure.as_ref().map(|f| &f.stage),
+                    e.last_failure.as_ref().map(|f| &f.command),
+                    e.last_failure.as_ref().map(|f| &f.output),
+                    e.fingerprint
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ProgressStore for SqliteStore {
+    fn exercises(&self) -> Result<Vec<ExerciseProgress>, anyhow::Error> {
+        let err_msg = "Failed to retrieve the list of exercises that you have already started";
+        let mut stmt = self
+            .connection
+            .prepare(
+                "SELECT chapter, exercise, solved, failed, skipped, hints_used, opened_at, solved_at, runs, run_time_ms, source_hash, last_failure_stage, last_failure_command, last_failure_output, fingerprint FROM open_exercises ORDER BY chapter, exercise",
+            )
+            .context(err_msg)?;
+        let exercises = stmt
+            .query_map([], exercise_from_row)
+            .context(err_msg)?
+            .collect::<Result<Vec<_>, _>>()
+            .context(err_msg)?;
+        Ok(exercises)
+    }
+
+    fn exercise(
+        &self,
+        chapter: &str,
+        exercise: &str,
+    ) -> Result<Option<ExerciseProgress>, anyhow::Error> {
+        self.connection
+            .query_row(
+                "SELECT chapter, exercise, solved, failed, skipped, hints_used, opened_at, solved_at, runs, run_time_ms, source_hash, last_failure_stage, last_failure_command, last_failure_output, fingerprint FROM open_exercises WHERE chapter = ?1 AND exercise = ?2",
+                params![chapter, exercise],
+                exercise_from_row,
+            )
+            .optional()
+            .context("Failed to retrieve your progress on the exercise")
+    }
+
+    fn save_exercises(&self, exercises: &[ExerciseProgress]) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to save your progress";
+        let transaction = self.write_transaction().context(err_msg)?;
+        self.save_exercises_in(&transaction, exercises)
+            .context(err_msg)?;
+        transaction.commit().context(err_msg)
+    }
+
+    fn open_exercise(&self, progress: &ExerciseProgress) -> Result<bool, anyhow::Error> {
+        let inserted = self
+            .connection
+            .execute(
+                "INSERT OR IGNORE INTO open_exercises (chapter, exercise, solved, opened_at, fingerprint) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    progress.chapter,
+                    progress.exercise,
+                    progress.solved,
+                    progress.opened_at,
+                    progress.fingerprint
+                ],
+            )
+            .context("Failed to open the exercise")?;
+        Ok(inserted > 0)
+    }
+
+    fn update_exercise(
+        &self,
+        chapter: &str,
+        exercise: &str,
+        f: &mut dyn FnMut(&mut ExerciseProgress),
+    ) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to save your progress";
+        let transaction = self.write_transaction().context(err_msg)?;
+        if let Some(mut progress) = self.exercise(chapter, exercise)? {
+            f(&mut progress);
+            self.save_exercises_in(&transaction, &[progress])
+                .context(err_msg)?;
+        }
+        transaction.commit().context(err_msg)
+    }
+
+    fn remove_exercises(&self, exercises: &[(String, String)]) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to remove an exercise from your progress";
+        let transaction = self.write_transaction().context(err_msg)?;
+        for (chapter, exercise) in exercises {
+            transaction
+                .execute(
+                    "DELETE FROM open_exercises WHERE chapter = ?1 AND exercise = ?2",
+                    params![chapter, exercise],
+                )
+                .context(err_msg)?;
+        }
+        transaction.commit().context(err_msg)
+    }
+
+    fn rename_exercise(&self, from: (&str, &str), to: (&str, &str)) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to carry over your progress on a renamed exercise";
+        let transaction = self.write_transaction().context(err_msg)?;
+        for table in ["open_exercises", "attempts", "notes", "events"] {
+            transaction
+                .execute(
+                    &format!("UPDATE {table} SET chapter = ?3, exercise = ?4 WHERE chapter = ?1 AND exercise = ?2"),
+                    params![from.0, from.1, to.0, to.1],
+                )
+                .context(err_msg)?;
+        }
+        // The new exercise may have been bookmarked already.
+        transaction
+            .execute(
+                "UPDATE OR IGNORE bookmarks SET chapter = ?3, exercise = ?4 WHERE chapter = ?1 AND exercise = ?2",
+                params![from.0, from.1, to.0, to.1],
+            )
+            .context(err_msg)?;
+        transaction
+            .execute(
+                "DELETE FROM bookmarks WHERE chapter = ?1 AND exercise = ?2",
+                params![from.0, from.1],
+            )
+            .context(err_msg)?;
+        transaction.commit().context(err_msg)
+    }
+
+    fn attempts(&self) -> Result<Vec<AttemptRecord>, anyhow::Error> {
+        let err_msg = "Failed to retrieve the history of your attempts";
+        let mut stmt = self
+            .connection
+            .prepare(
+                "SELECT chapter, exercise, attempted_at, duration_ms, failed_command FROM attempts ORDER BY id",
+            )
+            .context(err_msg)?;
+        let attempts = stmt
+            .query_map([], |row| {
+                Ok(AttemptRecord {
+                    chapter: row.get(0)?,
+                    exercise: row.get(1)?,
+                    attempted_at: row.get(2)?,
+                    duration_ms: row.get(3)?,
+                    failed_command: row.get(4)?,
+                })
+            })
+            .context(err_msg)?
+            .collect::<Result<Vec<_>, _>>()
+            .context(err_msg)?;
+        Ok(attempts)
+    }
+
+    fn record_attempt(&self, attempt: &AttemptRecord) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to record your attempt";
+        let transaction = self.write_transaction().context(err_msg)?;
+        transaction
+            .execute(
+                "UPDATE open_exercises SET runs = runs + 1, run_time_ms = run_time_ms + ?3 WHERE chapter = ?1 AND exercise = ?2",
+                params![attempt.chapter, attempt.exercise, attempt.duration_ms],
+            )
+            .context(err_msg)?;
+        transaction
+            .execute(
+                "INSERT INTO attempts (chapter, exercise, attempted_at, duration_ms, failed_command) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    attempt.chapter,
+                    attempt.exercise,
+                    attempt.attempted_at,
+                    attempt.duration_ms,
+                    attempt.failed_command
+                ],
+            )
+            .context(err_msg)?;
+        transaction.commit().context(err_msg)
+    }
+
+    fn bookmarks(&self) -> Result<BTreeSet<(String, String)>, anyhow::Error> {
+        let err_msg = "Failed to retrieve your bookmarks";
+        let mut stmt = self
+            .connection
+            .prepare("SELECT chapter, exercise FROM bookmarks")
+            .context(err_msg)?;
+        let bookmarks = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .context(err_msg)?
+            .collect::<Result<_, _>>()
+            .context(err_msg)?;
+        Ok(bookmarks)
+    }
+
+    fn add_bookmark(&self, chapter: &str, exercise: &str) -> Result<bool, anyhow::Error> {
+        let n_inserted = self
+            .connection
+            .execute(
+                "INSERT OR IGNORE INTO bookmarks (chapter, exercise) VALUES (?1, ?2)",
+                params![chapter, exercise],
+            )
+            .context("Failed to bookmark the exercise")?;
+        Ok(n_inserted > 0)
+    }
+
+    fn remove_bookmark(&self, chapter: &str, exercise: &str) -> Result<bool, anyhow::Error> {
+        let n_deleted = self
+            .connection
+            .execute(
+                "DELETE FROM bookmarks WHERE chapter = ?1 AND exercise = ?2",
+                params![chapter, exercise],
+            )
+            .context("Failed to remove the bookmark for the exercise")?;
+        Ok(n_deleted > 0)
+    }
+
+    fn notes(&self) -> Result<Vec<NoteRecord>, anyhow::Error> {
+        let err_msg = "Failed to retrieve your notes";
+        let mut stmt = self
+            .connection
+            .prepare("SELECT chapter, exercise, created_at, text FROM notes ORDER BY id")
+            .context(err_msg)?;
+        let notes = stmt
+            .query_map([], |row| {
+                Ok(NoteRecord {
+                    chapter: row.get(0)?,
+                    exercise: row.get(1)?,
+                    created_at: row.get(2)?,
+                    text: row.get(3)?,
+                })
+            })
+            .context(err_msg)?
+            .collect::<Result<Vec<_>, _>>()
+            .context(err_msg)?;
+        Ok(notes)
+    }
+
+    fn add_note(&self, note: &NoteRecord) -> Result<(), anyhow::Error> {
+        self.connection
+            .execute(
+                "INSERT INTO notes (chapter, exercise, created_at, text) VALUES (?1, ?2, ?3, ?4)",
+                params![note.chapter, note.exercise, note.created_at, note.text],
+            )
+            .context("Failed to save your note")?;
+        Ok(())
+    }
+
+    fn events(&self) -> Result<Vec<EventRecord>, anyhow::Error> {
+        let err_msg = "Failed to retrieve the history of your exercises";
+        let mut stmt = self
+            .connection
+            .prepare("SELECT chapter, exercise, occurred_at, kind FROM events ORDER BY id")
+            .context(err_msg)?;
+        let events = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .context(err_msg)?
+            .collect::<Result<Vec<_>, _>>()
+            .context(err_msg)?;
+        events
+            .into_iter()
+            .map(|(chapter, exercise, occurred_at, kind)| {
+                Ok(EventRecord {
+                    chapter,
+                    exercise,
+                    occurred_at,
+                    kind: kind.parse().context(err_msg)?,
+                })
+            })
+            .collect()
+    }
+
+    fn record_event(&self, event: &EventRecord) -> Result<(), anyhow::Error> {
+        self.connection
+            .execute(
+                "INSERT INTO events (chapter, exercise, occurred_at, kind) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    event.chapter,
+                    event.exercise,
+                    event.occurred_at,
+                    event.kind.as_str()
+                ],
+            )
+            .context("Failed to record a change in the status of the exercise")?;
+        Ok(())
+    }
+
+    fn reset(&self) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to reset your progress";
+        let transaction = self.write_transaction().context(err_msg)?;
+        transaction
+            .execute("DELETE FROM open_exercises", [])
+            .context(err_msg)?;
+        transaction
+            .execute("DELETE FROM attempts", [])
+            .context(err_msg)?;
+        transaction.commit().context(err_msg)
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn backup(&self, to: &Path) -> Result<(), anyhow::Error> {
+        let to = to
+            .to_str()
+            .context("The path of the backup must be valid UTF-8")?;
+        self.connection
+            .execute("VACUUM INTO ?1", [to])
+            .context("Failed to back up your progress")?;
+        Ok(())
+    }
+
+    fn restore(&self, from: &Path) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to restore your progress from the backup";
+        // The backup may have been taken by an older version of `wr`: upgrade a copy of it,
+        // leaving the original untouched, so that its tables match ours.
+        let copy = std::env::temp_dir().join(format!("wr-restore-{}.db", std::process::id()));
+        fs_err::copy(from, &copy).context(err_msg)?;
+        let result = SqliteStore::open(&copy)
+            .with_context(|| format!("{from:?} is not a backup of your progress"))
+            .and_then(|backup| {
+                drop(backup);
+                self.restore_from(&copy).context(err_msg)
+            });
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs_err::remove_file(format!("{}{suffix}", copy.display()));
+        }
+        result
+    }
+
+    fn integrity_check(&self) -> Result<Vec<String>, anyhow::Error> {
+        let problems = self
+            .connection
+            .prepare("PRAGMA integrity_check")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to check the integrity of your progress database")?;
+        Ok(problems.into_iter().filter(|p| p != "ok").collect())
+    }
+
+    fn purge_exercises(&self, exercises: &[(String, String)]) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to remove invalid exercises from your progress";
+        let transaction = self.write_transaction().context(err_msg)?;
+        for (chapter, exercise) in exercises {
+            for table in ["open_exercises", "attempts", "bookmarks", "notes", "events"] {
+                transaction
+                    .execute(
+                        &format!("DELETE FROM {table} WHERE chapter = ?1 AND exercise = ?2"),
+                        params![chapter, exercise],
+                    )
+                    .context(err_msg)?;
+            }
+        }
+        transaction.commit().context(err_msg)
+    }
+}
+
+fn exercise_from_row(row: &rusqlite::Row) -> Result<ExerciseProgress, rusqlite::Error> {
+    Ok(ExerciseProgress {
+        chapter: row.get(0)?,
+        exercise: row.get(1)?,
+        solved: row.get(2)?,
+        failed: row.get(3)?,
+        skipped: row.get(4)?,
+        hints_used: row.get(5)?,
+        opened_at: row.get(6)?,
+        solved_at: row.get(7)?,
+        runs: row.get(8)?,
+        run_time_ms: row.get(9)?,
+        source_hash: row.get(10)?,
+        last_failure: match (row.get(11)?, row.get(12)?, row.get(13)?) {
+            (Some(stage), Some(command), Some(output)) => Some(LastFailure {
+                stage,
+                command,
+                output,
+            }),
+            _ => None,
+        },
+        fingerprint: row.get(14)?,
+    })
+}
+
+/// Progress stored in a JSON file, rewritten in full on every change.
+///
+/// The file is read again every time progress is queried or modified: another `wr` process
+/// may have changed it in the meantime.
+pub struct JsonStore {
+    path: PathBuf,
+}
+
+/// The contents of the file behind a [`JsonStore`].
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct ProgressFile {
+    #[serde(default)]
+    exercises: Vec<ExerciseProgress>,
+    #[serde(default)]
+    bookmarks: BTreeSet<(String, String)>,
+    #[serde(default)]
+    attempts: Vec<AttemptRecord>,
+    #[serde(default)]
+    notes: Vec<NoteRecord>,
+    #[serde(default)]
+    events: Vec<EventRecord>,
+}
+
+impl JsonStore {
+    /// Track progress in the file at `path`. The file is created on the first change, if it
+    /// doesn't exist yet.
+    pub fn open(path: PathBuf) -> Result<Self, anyhow::Error> {
+        let store = Self { path };
+        // Fail early if the file is corrupted.
+        store.load()?;
+        Ok(store)
+    }
+
+    fn load(&self) -> Result<ProgressFile, anyhow::Error> {
+        if !self.path.exists() {
+            return Ok(ProgressFile::default());
+        }
+        let contents = fs_err::read_to_string(&self.path)
+            .context("Failed to read the file that tracks your progress")?;
+        serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "`{}` doesn't contain a valid record of your progress",
+                self.path.display()
+            )
+        })
+    }
+
+    /// Prevent other `wr` processes from modifying the file until the returned lock file is closed.
+    fn lock(&self) -> Result<fs_err::File, std::io::Error> {
+        let lock = fs_err::File::create(self.path.with_extension("json.lock"))?;
+        lock.file().lock()?;
+        Ok(lock)
+    }
+
+    /// Apply a change to the stored progress and write it back to disk.
+    ///
+    /// Other `wr` processes have to wait for the change to be written before making
+    /// their own.
+    fn update<T>(&self, f: impl FnOnce(&mut ProgressFile) -> T) -> Result<T, anyhow::Error> {
+        let err_msg = "Failed to save your progress";
+        let _lock = self.lock().context(err_msg)?;
+
+        let mut data = self.load()?;
+        let output = f(&mut data);
+        data.exercises
+            .sort_by(|a, b| (&a.chapter, &a.exercise).cmp(&(&b.chapter, &b.exercise)));
+        let contents = serde_json::to_string_pretty(&data).context(err_msg)?;
+        // Write to a temporary file first, so that a crash (or a concurrent reader) can't
+        // see a truncated file.
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs_err::write(&tmp_path, contents + "\n").context(err_msg)?;
+        fs_err::rename(&tmp_path, &self.path).context(err_msg)?;
+        // The lock is released when the lock file is closed.
+        Ok(output)
+    }
+}
+
+impl ProgressStore for JsonStore {
+    fn exercises(&self) -> Result<Vec<ExerciseProgress>, anyhow::Error> {
+        Ok(self.load()?.exercises)
+    }
+
+    fn save_exercises(&self, exercises: &[ExerciseProgress]) -> Result<(), anyhow::Error> {
+        self.update(|data| {
+            for e in exercises {
+                data.exercises
+                    .retain(|o| o.chapter != e.chapter || o.exercise != e.exercise);
+                data.exercises.push(e.clone());
+            }
+        })
+    }
+
+    fn open_exercise(&self, progress: &ExerciseProgress) -> Result<bool, anyhow::Error> {
+        self.update(|data| {
+            let opened = data
+                .exercises
+                .iter()
+                .any(|e| e.chapter == progress.chapter && e.exercise == progress.exercise);
+            if !opened {
+                data.exercises.push(progress.clone());
+            }
+            !opened
+        })
+    }
+
+    fn update_exercise(
+        &self,
+        chapter: &str,
+        exercise: &str,
+        f: &mut dyn FnMut(&mut ExerciseProgress),
+    ) -> Result<(), anyhow::Error> {
+        self.update(|data| {
+            if let Some(progress) = data
+                .exercises
+                .iter_mut()
+                .find(|e| e.chapter == chapter && e.exercise == exercise)
+            {
+                f(progress);
+            }
+        })
+    }
+
+    fn remove_exercises(&self, exercises: &[(String, String)]) -> Result<(), anyhow::Error> {
+        self.update(|data| {
+            data.exercises.retain(|e| {
+                !exercises
+                    .iter()
+                    .any(|(chapter, exercise)| &e.chapter == chapter && &e.exercise == exercise)
+            })
+        })
+    }
+
+    fn rename_exercise(&self, from: (&str, &str), to: (&str, &str)) -> Result<(), anyhow::Error> {
+        let is_from = |chapter: &str, exercise: &str| (chapter, exercise) == from;
+        self.update(|data| {
+            for e in &mut data.exercises {
+                if is_from(&e.chapter, &e.exercise) {
+                    (e.chapter, e.exercise) = (to.0.to_owned(), to.1.to_owned());
+                }
+            }
+            for a in &mut data.attempts {
+                if is_from(&a.chapter, &a.exercise) {
+                    (a.chapter, a.exercise) = (to.0.to_owned(), to.1.to_owned());
+                }
+            }
+            for n in &mut data.notes {
+                if is_from(&n.chapter, &n.exercise) {
+                    (n.chapter, n.exercise) = (to.0.to_owned(), to.1.to_owned());
+                }
+            }
+            for e in &mut data.events {
+                if is_from(&e.chapter, &e.exercise) {
+                    (e.chapter, e.exercise) = (to.0.to_owned(), to.1.to_owned());
+                }
+            }
+            if data
+                .bookmarks
+                .remove(&(from.0.to_owned(), from.1.to_owned()))
+            {
+                data.bookmarks.insert((to.0.to_owned(), to.1.to_owned()));
+            }
+        })
+    }
+
+    fn attempts(&self) -> Result<Vec<AttemptRecord>, anyhow::Error> {
+        Ok(self.load()?.attempts)
+    }
+
+    fn record_attempt(&self, attempt: &AttemptRecord) -> Result<(), anyhow::Error> {
+        self.update(|data| {
+            if let Some(progress) = data
+                .exercises
+                .iter_mut()
+                .find(|e| e.chapter == attempt.chapter && e.exercise == attempt.exercise)
+            {
+                progress.runs += 1;
+                progress.run_time_ms += attempt.duration_ms;
+            }
+            data.attempts.push(attempt.clone());
+        })
+    }
+
+    fn bookmarks(&self) -> Result<BTreeSet<(String, String)>, anyhow::Error> {
+        Ok(self.load()?.bookmarks)
+    }
+
+    fn add_bookmark(&self, chapter: &str, exercise: &str) -> Result<bool, anyhow::Error> {
+        self.update(|data| {
+            data.bookmarks
+                .insert((chapter.to_owned(), exercise.to_owned()))
+        })
+    }
+
+    fn remove_bookmark(&self, chapter: &str, exercise: &str) -> Result<bool, anyhow::Error> {
+        self.update(|data| {
+            data.bookmarks
+                .remove(&(chapter.to_owned(), exercise.to_owned()))
+        })
+    }
+
+    fn notes(&self) -> Result<Vec<NoteRecord>, anyhow::Error> {
+        Ok(self.load()?.notes)
+    }
+
+    fn add_note(&self, note: &NoteRecord) -> Result<(), anyhow::Error> {
+        self.update(|data| data.notes.push(note.clone()))
+    }
+
+    fn events(&self) -> Result<Vec<EventRecord>, anyhow::Error> {
+        Ok(self.load()?.events)
+    }
+
+    fn record_event(&self, event: &EventRecord) -> Result<(), anyhow::Error> {
+        self.update(|data| data.events.push(event.clone()))
+    }
+
+    fn reset(&self) -> Result<(), anyhow::Error> {
+        self.update(|data| {
+            data.exercises.clear();
+            data.attempts.clear();
+        })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn backup(&self, to: &Path) -> Result<(), anyhow::Error> {
+        let err_msg = "Failed to back up your progress";
+        let _lock = self.lock().context(err_msg)?;
+        let contents = serde_json::to_string_pretty(&self.load()?).context(err_msg)?;
+        fs_err::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(to)
+            .and_then(|mut f| std::io::Write::write_all(&mut f, (contents + "\n").as_bytes()))
+            .context(err_msg)
+    }
+
+    fn restore(&self, from: &Path) -> Result<(), anyhow::Error> {
+        let contents =
+            fs_err::read(from).context("Failed to restore your progress from the backup")?;
+        let backup: ProgressFile = serde_json::from_slice(&contents)
+            .with_context(|| format!("{from:?} is not a backup of your progress"))?;
+        self.update(|data| *data = backup)
+    }
+
+    fn integrity_check(&self) -> Result<Vec<String>, anyhow::Error> {
+        // The file has been parsed successfully when the store was opened.
+        Ok(vec![])
+    }
+
+    fn purge_exercises(&self, exercises: &[(String, String)]) -> Result<(), anyhow::Error> {
+        self.update(|data| {
+            let purged = |chapter: &String, exercise: &String| {
+                exercises.iter().any(|(c, e)| c == chapter && e == exercise)
+            };
+            data.exercises.retain(|e| !purged(&e.chapter, &e.exercise));
+            data.attempts.retain(|a| !purged(&a.chapter, &a.exercise));
+            data.notes.retain(|n| !purged(&n.chapter, &n.exercise));
+            data.events.retain(|e| !purged(&e.chapter, &e.exercise));
+            data.bookmarks.retain(|(c, e)| !purged(c, e));
+        })
+    }
+}
@@ -0,0 +1,233 @@
+//! Summarize the progress on a collection as a Markdown or HTML document, for `wr report`.
+//!
+//! Learners can attach it when asking for help, and instructors can collect it at the end
+//! of a workshop.
+use crate::dashboard::escape;
+use crate::{
+    format_duration, format_run_time, CollectionMetadata, ExerciseDefinition, ExerciseStatus,
+    ExerciseTimings,
+};
+use std::fmt::Write;
+
+/// The progress on a collection, as of now.
+pub struct ProgressReport {
+    pub metadata: CollectionMetadata,
+    /// The track being followed, if any.
+    pub track: Option<String>,
+    /// Every exercise in the collection (or in the track), in order.
+    pub exercises: Vec<ExerciseReport>,
+}
+
+/// The progress on a single exercise.
+pub struct ExerciseReport {
+    pub definition: ExerciseDefinition,
+    pub status: ExerciseStatus,
+    pub optional: bool,
+    /// How much time was spent on the exercise, or `None` if it hasn't been opened yet.
+    pub timings: Option<ExerciseTimings>,
+}
+
+impl ProgressReport {
+    /// Render the report as Markdown, with a table for each chapter.
+    ///
+    /// Only the exercises that have been opened are listed, but every exercise counts towards
+    /// the totals.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let title = self
+            .metadata
+            .title
+            .as_deref()
+            .unwrap_or("Workshop progress");
+        writeln!(md, "# {title}\n").unwrap();
+        for (label, value) in self.details() {
+            writeln!(md, "- **{label}:** {value}").unwrap();
+        }
+        for chapter in self.chapters() {
+            let first = &chapter[0].definition;
+            writeln!(
+                md,
+                "\n## ({:02}) {} ({})\n",
+                first.chapter_number(),
+                first.chapter_name(),
+                summary(chapter)
+            )
+            .unwrap();
+            let opened: Vec<_> = chapter
+                .iter()
+                .filter(|e| e.status != ExerciseStatus::Unopened)
+                .collect();
+            if opened.is_empty() {
+                writeln!(md, "No exercise opened yet.").unwrap();
+                continue;
+            }
+            writeln!(
+                md,
+                "| Exercise | Status | Time to solve | Attempts | Run time |\n|---|---|---|---:|---:|"
+            )
+            .unwrap();
+            for exercise in opened {
+                let [name, status, time_to_solve, attempts, run_time] = exercise.cells();
+                writeln!(
+                    md,
+                    "| {name} | {status} | {time_to_solve} | {attempts} | {run_time} |"
+                )
+                .unwrap();
+            }
+        }
+        md
+    }
+
+    /// Render the report as a standalone HTML page, with the same content as
+    /// [`ProgressReport::to_markdown`].
+    pub fn to_html(&self) -> String {
+        let title = escape(
+            self.metadata
+                .title
+                .as_deref()
+                .unwrap_or("Workshop progress"),
+        );
+        let mut body = String::from("<ul>\n");
+        for (label, value) in self.details() {
+            writeln!(
+                body,
+                "<li><strong>{}:</strong> {}</li>",
+                escape(label),
+                escape(&value)
+            )
+            .unwrap();
+        }
+        body.push_str("</ul>\n");
+        for chapter in self.chapters() {
+            let first = &chapter[0].definition;
+            writeln!(
+                body,
+                "<h2>({:02}) {} ({})</h2>",
+                first.chapter_number(),
+                escape(first.chapter_name()),
+                summary(chapter)
+            )
+            .unwrap();
+            let opened: Vec<_> = chapter
+                .iter()
+                .filter(|e| e.status != ExerciseStatus::Unopened)
+                .collect();
+            if opened.is_empty() {
+                writeln!(body, "<p>No exercise opened yet.</p>").unwrap();
+                continue;
+            }
+            writeln!(
+                body,
+                "<table>\n<tr><th>Exercise</th><th>Status</th><th>Time to solve</th><th>Attempts</th><th>Run time</th></tr>"
+            )
+            .unwrap();
+            for exercise in opened {
+                let cells: Vec<_> = exercise
+                    .cells()
+                    .iter()
+                    .map(|c| format!("<td>{}</td>", escape(c)))
+                    .collect();
+                writeln!(
+                    body,
+                    r#"<tr class="{}">{}</tr>"#,
+                    exercise.status,
+                    cells.concat()
+                )
+                .unwrap();
+            }
+            writeln!(body, "</table>").unwrap();
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title} - wr</title>
+<style>
+body {{ font-family: sans-serif; max-width: 60em; margin: 2em auto; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th {{ text-align: left; }}
+td, th {{ padding: 0.3em 0.6em; border-bottom: 1px solid #ddd; }}
+tr.solved td {{ color: #1a7f37; }}
+tr.failed td {{ color: #cf222e; }}
+tr.opened td {{ color: #9a6700; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}
+</body>
+</html>
+"#
+        )
+    }
+
+    /// The lines at the top of the report, as `(label, value)` pairs.
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = vec![];
+        if let Some(version) = &self.metadata.version {
+            details.push(("Version", version.clone()));
+        }
+        if let Some(track) = &self.track {
+            details.push(("Track", track.clone()));
+        }
+        details.push(("Progress", summary(&self.exercises)));
+        let runs: usize = self
+            .exercises
+            .iter()
+            .filter_map(|e| e.timings.as_ref())
+            .map(|t| t.runs)
+            .sum();
+        details.push(("Attempts", runs.to_string()));
+        details
+    }
+
+    fn chapters(&self) -> impl Iterator<Item = &[ExerciseReport]> {
+        self.exercises
+            .chunk_by(|a, b| a.definition.chapter_number() == b.definition.chapter_number())
+    }
+}
+
+impl ExerciseReport {
+    /// The cells of the exercise's row: name, status, time to solve, attempts and run time.
+    fn cells(&self) -> [String; 5] {
+        let name = format!(
+            "({:02}) {}{}",
+            self.definition.exercise_number(),
+            self.definition.exercise_name(),
+            if self.optional { " (optional)" } else { "" }
+        );
+        let timings = self.timings.as_ref();
+        [
+            name,
+            self.status.to_string(),
+            timings
+                .and_then(|t| t.time_to_solve)
+                .map(|t| format_duration(t.as_secs() as i64))
+                .unwrap_or_else(|| "-".into()),
+            timings.map(|t| t.runs).unwrap_or_default().to_string(),
+            format_run_time(timings.map(|t| t.run_time).unwrap_or_default()),
+        ]
+    }
+}
+
+/// E.g. `3/5 solved, 60%, 1/2 optional`. Optional exercises don't count towards the percentage.
+fn summary(exercises: &[ExerciseReport]) -> String {
+    let count = |optional: bool| {
+        let matching = exercises.iter().filter(|e| e.optional == optional);
+        let n_solved = matching
+            .clone()
+            .filter(|e| e.status == ExerciseStatus::Solved)
+            .count();
+        (n_solved, matching.count())
+    };
+    let (n_solved, total) = count(false);
+    let percentage = (n_solved * 100).checked_div(total).unwrap_or(0);
+    let mut summary = format!("{n_solved}/{total} solved, {percentage}%");
+    let (n_optional_solved, n_optional) = count(true);
+    if n_optional > 0 {
+        write!(summary, ", {n_optional_solved}/{n_optional} optional").unwrap();
+    }
+    summary
+}
@@ -0,0 +1,71 @@
+//! Import progress from [rustlings](https://github.com/rust-lang/rustlings), so that learners
+//! moving from a rustlings-style course to `wr` don't have to start over.
+use crate::{ExerciseCollection, ExerciseDefinition};
+use anyhow::bail;
+
+/// The first line of a rustlings state file.
+const HEADER: &str = "DON'T EDIT THIS FILE!";
+
+/// The outcome of [`import`].
+pub struct Import {
+    /// The exercises that have been marked as solved.
+    pub solved: Vec<ExerciseDefinition>,
+    /// The exercises done in rustlings that don't match exactly one exercise in the collection.
+    pub unmatched: Vec<String>,
+}
+
+/// The names of the exercises recorded as done in a rustlings state file (`.rustlings-state.txt`).
+///
+/// Since rustlings 6, the file looks like this:
+///
+/// ```text
+/// DON'T EDIT THIS FILE!
+///
+/// <the current exercise>
+///
+/// <a done exercise>
+/// <another done exercise>
+/// ```
+pub fn done_exercises(state: &str) -> Result<Vec<String>, anyhow::Error> {
+    let mut lines = state.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        bail!("A rustlings state file must start with `{HEADER}`");
+    }
+    let done = lines
+        .map(str::trim)
+        // An empty line, then the current exercise, then another empty line.
+        .skip(3)
+        .filter(|l| !l.is_empty())
+        .map(ToOwned::to_owned)
+        .collect();
+    Ok(done)
+}
+
+/// Mark as solved the exercises that rustlings recorded as done, see [`done_exercises`].
+///
+/// Exercises are matched by name (e.g. `variables1` matches `01_variables1`), ignoring
+/// chapter and exercise numbers. Exercises that haven't been opened yet are opened first.
+pub fn import(
+    exercises: &mut ExerciseCollection,
+    done: &[String],
+) -> Result<Import, anyhow::Error> {
+    let mut import = Import {
+        solved: vec![],
+        unmatched: vec![],
+    };
+    for name in done {
+        let candidates: Vec<_> = exercises
+            .iter()
+            .filter(|e| e.exercise_name() == name.as_str())
+            .cloned()
+            .collect();
+        let [exercise] = candidates.as_slice() else {
+            import.unmatched.push(name.clone());
+            continue;
+        };
+        exercises.open(exercise)?;
+        exercises.mark_as_solved(exercise)?;
+        import.solved.push(exercise.clone());
+    }
+    Ok(import)
+}
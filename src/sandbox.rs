@@ -0,0 +1,133 @@
+//! Run verification commands inside a container, to isolate the machine from the code under test.
+use crate::process::ResourceLimits;
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Where verification commands run.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Sandbox {
+    /// In a throwaway `docker` container.
+    Docker,
+}
+
+/// The image used for sandboxed verification when the collection doesn't specify one.
+pub const DEFAULT_IMAGE: &str = "rust:latest";
+
+/// Turn `cmd` into an equivalent command that runs inside a container, based on `image`.
+///
+/// `root_dir`, the root of the workshop repository, is mounted at the same path inside the container:
+/// paths in the command's arguments, relative or absolute, keep working. So are the dependencies
+/// downloaded by Cargo, so that they are downloaded once rather than on every run.
+/// The command runs as the current user, who must be able to `cargo clean` what it builds.
+///
+/// The command's environment variables and working directory are carried over, while `extra_args`
+/// are passed to `docker run` as they are, e.g. to cut off network access.
+/// The container can't use more resources than `limits` allow. It's called `name`, see [`kill`].
+pub fn wrap(
+    cmd: &Command,
+    name: &str,
+    root_dir: &Path,
+    image: &str,
+    extra_args: &[String],
+    limits: ResourceLimits,
+) -> Result<Command, anyhow::Error> {
+    let current_dir =
+        std::env::current_dir().context("Failed to determine the current directory")?;
+    let working_dir =
+        fs_err::canonicalize(current_dir.join(cmd.get_current_dir().unwrap_or(Path::new(""))))?;
+    let root_dir = fs_err::canonicalize(current_dir.join(root_dir))?;
+    let root_dir = root_dir.display();
+    let mut docker = Command::new("docker");
+    docker
+        .args(["run", "--rm", "--interactive", "--name", name])
+        .args(["--volume", &format!("{root_dir}:{root_dir}")])
+        .arg("--workdir")
+        .arg(&working_dir);
+    #[cfg(unix)]
+    {
+        // SAFETY: neither call can fail.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        // There's no such user in the image: it needs a home to write to, all the same.
+        docker
+            .arg(format!("--user={uid}:{gid}"))
+            .args(["--env", "HOME=/tmp"]);
+    }
+    if let Some(cargo_home) = cargo_home() {
+        // Only the downloads are shared: the rest of Cargo's home (e.g. its configuration) is
+        // meant for this machine, not for the container.
+        docker
+            .args([
+                "--mount",
+                &format!("type=tmpfs,destination={CARGO_HOME},tmpfs-mode=1777"),
+            ])
+            .args(["--env", &format!("CARGO_HOME={CARGO_HOME}")]);
+        for dir in ["registry", "git"] {
+            let path = cargo_home.join(dir);
+            if path.is_dir() {
+                docker.args([
+                    "--volume",
+                    &format!("{}:{CARGO_HOME}/{dir}", path.display()),
+                ]);
+            }
+        }
+    }
+    for (name, value) in cmd.get_envs() {
+        if let Some(value) = value {
+            let mut variable = name.to_owned();
+            variable.push("=");
+            variable.push(value);
+            docker.arg("--env").arg(variable);
+        }
+    }
+    if let Some(memory_bytes) = limits.memory_bytes {
+        // Without swap, on top of it.
+        docker.arg(format!("--memory={memory_bytes}b"));
+        docker.arg(format!("--memory-swap={memory_bytes}b"));
+    }
+    if let Some(cpu_time) = limits.cpu_time {
+        let secs = cpu_time.as_secs().max(1);
+        docker.arg(format!("--ulimit=cpu={secs}:{secs}"));
+    }
+    docker
+        .args(extra_args)
+        .arg(image)
+        .arg(cmd.get_program())
+        .args(cmd.get_args());
+    Ok(docker)
+}
+
+/// A name for a new container, unique to this run of `wr`.
+pub fn container_name() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    format!(
+        "wr-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Kill and remove the container called `name`, if it's still around.
+///
+/// Killing `docker run` (e.g. when a command times out) leaves its container running.
+pub fn kill(name: &str) {
+    // Best effort: the container may well be gone already.
+    let _ = Command::new("docker")
+        .args(["rm", "--force", name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Where Cargo's home is, inside the container.
+const CARGO_HOME: &str = "/wr-cargo-home";
+
+/// Where Cargo keeps the registry index and the sources of the dependencies, on this machine.
+fn cargo_home() -> Option<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))?;
+    fs_err::canonicalize(cargo_home).ok()
+}
@@ -0,0 +1,106 @@
+//! Create new collections and exercises from a template.
+use anyhow::{bail, Context};
+use std::path::Path;
+
+/// Create a new collection of exercises in `root_dir`, with a first exercise to get started.
+///
+/// It creates:
+///
+/// - a `.wr.toml` configuration file;
+/// - an `exercises` directory, with a `01_intro/00_welcome` exercise;
+/// - a `.gitignore` entry for the database used to track progress.
+pub fn init(root_dir: &Path) -> Result<(), anyhow::Error> {
+    let config_path = root_dir.join(".wr.toml");
+    if config_path.exists() {
+        bail!(
+            "There is already a collection of exercises in {:?}",
+            root_dir
+        );
+    }
+    fs_err::write(&config_path, "exercises_dir = \"exercises\"\n")
+        .context("Failed to create the configuration for the collection")?;
+    create_exercise(
+        &root_dir
+            .join("exercises")
+            .join("01_intro")
+            .join("00_welcome"),
+        "welcome",
+    )?;
+
+    let gitignore_path = root_dir.join(".gitignore");
+    let mut gitignore = if gitignore_path.exists() {
+        fs_err::read_to_string(&gitignore_path)?
+    } else {
+        String::new()
+    };
+    // One progress database per profile (see `wr --profile`), along with the files SQLite
+    // (or the JSON store) creates next to it while in use, and backups (see `wr backup`).
+    let missing: Vec<_> = [
+        "progress.db",
+        "progress-*.db",
+        "progress*.db-*",
+        "progress*.json.lock",
+        "progress-backups/",
+    ]
+    .into_iter()
+    .filter(|pattern| !gitignore.lines().any(|l| l.trim() == *pattern))
+    .collect();
+    if !missing.is_empty() {
+        if !gitignore.is_empty() && !gitignore.ends_with('\n') {
+            gitignore.push('\n');
+        }
+        for pattern in missing {
+            gitignore.push_str(pattern);
+            gitignore.push('\n');
+        }
+        fs_err::write(&gitignore_path, gitignore).context("Failed to update `.gitignore`")?;
+    }
+    Ok(())
+}
+
+/// Create a new exercise in `exercise_dir`: a library crate with a stub for learners
+/// to fill in and a (failing) test to verify their solution.
+pub fn create_exercise(exercise_dir: &Path, package_name: &str) -> Result<(), anyhow::Error> {
+    if exercise_dir.exists() {
+        bail!("{:?} already exists", exercise_dir);
+    }
+    let err_msg = || format!("Failed to create a new exercise in {:?}", exercise_dir);
+    fs_err::create_dir_all(exercise_dir.join("src")).with_context(err_msg)?;
+    fs_err::create_dir_all(exercise_dir.join("tests")).with_context(err_msg)?;
+    fs_err::write(
+        exercise_dir.join("Cargo.toml"),
+        format!(
+            r#"[package]
+name = "{package_name}"
+version = "0.1.0"
+edition = "2021"
+"#
+        ),
+    )
+    .with_context(err_msg)?;
+    fs_err::write(
+        exercise_dir.join("src").join("lib.rs"),
+        r#"// TODO: implement this function to make the tests pass.
+pub fn answer() -> u32 {
+    todo!()
+}
+"#,
+    )
+    .with_context(err_msg)?;
+    fs_err::write(
+        exercise_dir
+            .join("tests")
+            .join(format!("{package_name}.rs")),
+        format!(
+            r#"use {package_name}::answer;
+
+#[test]
+fn the_answer_is_right() {{
+    assert_eq!(answer(), 42);
+}}
+"#
+        ),
+    )
+    .with_context(err_msg)?;
+    Ok(())
+}
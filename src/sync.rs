@@ -0,0 +1,66 @@
+//! Keep your progress in sync across machines, via a remote HTTP endpoint.
+//!
+//! The protocol is deliberately minimal, so that it can be implemented by any web server:
+//!
+//! - `GET <sync_url>` returns the latest [`ProgressSnapshot`], as JSON, or `404` if there is none yet;
+//! - `PUT <sync_url>` replaces it with the snapshot in the request body.
+//!
+//! Both requests are authenticated with an `Authorization: Bearer <token>` header.
+use crate::ProgressSnapshot;
+use anyhow::{anyhow, Context};
+use std::time::Duration;
+
+/// How long we wait for the sync endpoint before giving up.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fetch the snapshot stored at `url`, if there is one.
+pub fn pull(url: &str, token: &str) -> Result<Option<ProgressSnapshot>, anyhow::Error> {
+    let response = match agent()
+        .get(url)
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+    {
+        Ok(response) => response,
+        Err(ureq::Error::Status(404, _)) => return Ok(None),
+        Err(e) => return Err(request_error(e)).context("Failed to pull your progress"),
+    };
+    let body = response
+        .into_string()
+        .context("Failed to pull your progress")?;
+    let snapshot = serde_json::from_str(&body)
+        .with_context(|| format!("{url} didn't return a valid progress snapshot"))?;
+    Ok(Some(snapshot))
+}
+
+/// Replace the snapshot stored at `url` with `snapshot`.
+pub fn push(url: &str, token: &str, snapshot: &ProgressSnapshot) -> Result<(), anyhow::Error> {
+    agent()
+        .put(url)
+        .set("Authorization", &format!("Bearer {token}"))
+        .set("Content-Type", "application/json")
+        .send_string(&serde_json::to_string(snapshot)?)
+        .map_err(request_error)
+        .context("Failed to push your progress")?;
+    Ok(())
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(TIMEOUT).build()
+}
+
+/// Include the response body, if any: it usually explains why the request was rejected.
+fn request_error(e: ureq::Error) -> anyhow::Error {
+    match e {
+        ureq::Error::Status(401 | 403, _) => {
+            anyhow!("The sync endpoint rejected your token. Double-check it and try again")
+        }
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+            anyhow!(
+                "The sync endpoint responded with status {status}: {}",
+                body.trim()
+            )
+        }
+        ureq::Error::Transport(e) => anyhow!("Failed to reach the sync endpoint: {e}"),
+    }
+}
@@ -0,0 +1,310 @@
+//! An interactive terminal UI to browse a collection, for `wr tui`.
+//!
+//! The exercises are listed on the left, grouped by chapter. The pane on the right shows
+//! the README of the selected exercise and the output of its last failed verification, if any.
+use crate::{strip_ansi_codes, ExerciseCollection, ExerciseDefinition, ExerciseStatus};
+use anyhow::Context;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+/// How many lines the details pane scrolls by at once.
+const SCROLL_STEP: u16 = 10;
+
+/// Run the UI until the learner quits.
+///
+/// Exercises are verified with `check`, which returns `true` if the exercise passed.
+/// The UI is suspended while it runs, so that it can print to the terminal as usual.
+pub fn run<F>(exercises: &mut ExerciseCollection, mut check: F) -> Result<(), anyhow::Error>
+where
+    F: FnMut(&ExerciseCollection, &ExerciseDefinition) -> Result<bool, anyhow::Error>,
+{
+    let mut app = App::new(exercises)?;
+    let mut terminal = ratatui::try_init().context("Failed to set up the terminal")?;
+    let outcome = app.run(&mut terminal, exercises, &mut check);
+    ratatui::restore();
+    outcome
+}
+
+/// A line of the exercise tree.
+enum Row {
+    Chapter(String),
+    Exercise(ExerciseDefinition, ExerciseStatus),
+}
+
+struct App {
+    rows: Vec<Row>,
+    list: ListState,
+    /// How far down the details pane has been scrolled.
+    scroll: u16,
+    /// The outcome of the last action, shown at the bottom.
+    message: String,
+    /// The details of the selected exercise, see [`details_text`]. They're computed when it's
+    /// selected, rather than on every draw, and again after every action.
+    details: Option<(ExerciseDefinition, Text<'static>)>,
+}
+
+impl App {
+    fn new(exercises: &ExerciseCollection) -> Result<Self, anyhow::Error> {
+        let mut app = Self {
+            rows: vec![],
+            list: ListState::default(),
+            scroll: 0,
+            message: String::new(),
+            details: None,
+        };
+        app.refresh(exercises)?;
+        // Start from the exercise the learner is working on.
+        let current = exercises.current()?;
+        let selected = app
+            .rows
+            .iter()
+            .position(|r| matches!(r, Row::Exercise(e, _) if Some(e) == current.as_ref()))
+            .or_else(|| app.rows.iter().position(|r| matches!(r, Row::Exercise(..))));
+        app.list.select(selected);
+        Ok(app)
+    }
+
+    /// Reload the status of every exercise, e.g. after it has been verified.
+    fn refresh(&mut self, exercises: &ExerciseCollection) -> Result<(), anyhow::Error> {
+        self.rows.clear();
+        self.details = None;
+        for (definition, status) in exercises.statuses()? {
+            let chapter = format!(
+                "({:02}) {}",
+                definition.chapter_number(),
+                definition.chapter_name()
+            );
+            let same_chapter = matches!(
+                self.rows.last(),
+                Some(Row::Exercise(e, _)) if e.chapter_number() == definition.chapter_number()
+            );
+            if !same_chapter {
+                self.rows.push(Row::Chapter(chapter));
+            }
+            self.rows.push(Row::Exercise(definition, status));
+        }
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&ExerciseDefinition> {
+        match self.rows.get(self.list.selected()?) {
+            Some(Row::Exercise(definition, _)) => Some(definition),
+            _ => None,
+        }
+    }
+
+    /// Move the selection by `delta` exercises, skipping chapter headers.
+    fn select_next(&mut self, delta: isize) {
+        let Some(mut i) = self.list.selected() else {
+            return;
+        };
+        let mut remaining = delta.unsigned_abs();
+        while remaining > 0 {
+            let next = if delta > 0 {
+                i.checked_add(1).filter(|&n| n < self.rows.len())
+            } else {
+                i.checked_sub(1)
+            };
+            let Some(next) = next else {
+                break;
+            };
+            i = next;
+            if matches!(self.rows[i], Row::Exercise(..)) {
+                remaining -= 1;
+                self.list.select(Some(i));
+                self.scroll = 0;
+            }
+        }
+    }
+
+    fn run<F>(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        exercises: &mut ExerciseCollection,
+        check: &mut F,
+    ) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(&ExerciseCollection, &ExerciseDefinition) -> Result<bool, anyhow::Error>,
+    {
+        loop {
+            terminal.draw(|frame| self.draw(frame, exercises))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.select_next(1),
+                KeyCode::Up | KeyCode::Char('k') => self.select_next(-1),
+                KeyCode::Home | KeyCode::Char('g') => self.select_next(isize::MIN),
+                KeyCode::End | KeyCode::Char('G') => self.select_next(isize::MAX),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(SCROLL_STEP),
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(SCROLL_STEP),
+                KeyCode::Char(c @ ('o' | 'c' | 's')) => {
+                    let Some(exercise) = self.selected().cloned() else {
+                        continue;
+                    };
+                    self.message = match c {
+                        'o' => open(exercises, &exercise),
+                        's' => exercises
+                            .mark_as_skipped(&exercise)
+                            .map(|_| format!("{exercise} has been skipped.")),
+                        _ => {
+                            ratatui::restore();
+                            let passed = check(exercises, &exercise);
+                            println!("\n\tPress Enter to go back.");
+                            let _ = std::io::stdin().read_line(&mut String::new());
+                            *terminal =
+                                ratatui::try_init().context("Failed to set up the terminal")?;
+                            passed.map(|passed| {
+                                let verdict = if passed { "passed" } else { "failed" };
+                                format!("{exercise} {verdict} verification.")
+                            })
+                        }
+                    }
+                    .unwrap_or_else(|e| format!("{e:#}"));
+                    self.refresh(exercises)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, exercises: &ExerciseCollection) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(frame.area());
+        let [tree, details] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+
+        let items: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Chapter(name) => ListItem::new(name.as_str())
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+                Row::Exercise(definition, status) => ListItem::new(format!(
+                    "  {} ({:02}) {}",
+                    icon(*status),
+                    definition.exercise_number(),
+                    definition.exercise_name()
+                ))
+                .style(Style::default().fg(color(*status))),
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Exercises "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, tree, &mut self.list);
+
+        let (title, text) = match self.selected().cloned() {
+            Some(exercise) => {
+                let cached = self.details.take().filter(|(e, _)| e == &exercise);
+                let (_, text) = self.details.insert(
+                    cached
+                        .unwrap_or_else(|| (exercise.clone(), details_text(exercises, &exercise))),
+                );
+                (format!(" {exercise} "), text.clone())
+            }
+            None => (String::new(), Text::default()),
+        };
+        let details_pane = Paragraph::new(text)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(details_pane, details);
+
+        let keys = Line::from("↑/↓ select · o open · c check · s skip · PgUp/PgDn scroll · q quit")
+            .style(Style::default().add_modifier(Modifier::DIM));
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(self.message.as_str()), keys]),
+            footer,
+        );
+    }
+}
+
+/// Open `exercise`, unless it has prerequisites that haven't been solved yet.
+fn open(
+    exercises: &mut ExerciseCollection,
+    exercise: &ExerciseDefinition,
+) -> Result<String, anyhow::Error> {
+    let unmet = exercises.unmet_prerequisites(exercise)?;
+    if !unmet.is_empty() {
+        let unmet: Vec<_> = unmet.iter().map(|e| e.to_string()).collect();
+        anyhow::bail!(
+            "{exercise} requires you to solve these exercises first: {}",
+            unmet.join(", ")
+        );
+    }
+    exercises.open(exercise)?;
+    Ok(format!("{exercise} has been opened."))
+}
+
+/// The status, README and last failure of `exercise`.
+fn details_text(exercises: &ExerciseCollection, exercise: &ExerciseDefinition) -> Text<'static> {
+    let heading = |s: &str| {
+        Line::from(Span::styled(
+            s.to_owned(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    };
+    let mut lines = vec![];
+    let mut details = vec![];
+    if exercises.is_optional(exercise) {
+        details.push("optional".to_owned());
+    }
+    if let Some(difficulty) = exercises.difficulty(exercise) {
+        details.push(difficulty.to_string());
+    }
+    details.extend(exercises.tags(exercise).iter().map(|t| format!("#{t}")));
+    if !details.is_empty() {
+        lines.push(Line::from(details.join(" · ")));
+        lines.push(Line::default());
+    }
+    if let Ok(Some(failure)) = exercises.last_failure(exercise) {
+        lines.push(heading(&format!(
+            "Last failure: stage `{}` ({})",
+            failure.stage, failure.command
+        )));
+        lines.extend(
+            strip_ansi_codes(&failure.output)
+                .lines()
+                .map(|l| Line::styled(l.to_owned(), Style::default().fg(Color::Red))),
+        );
+        lines.push(Line::default());
+    }
+    match exercise
+        .readme_path(exercises.exercises_dir_of(exercise))
+        .and_then(|p| fs_err::read_to_string(p).ok())
+    {
+        Some(readme) => lines.extend(readme.lines().map(|l| Line::from(l.to_owned()))),
+        None => lines.push(Line::from("This exercise doesn't have a README.")),
+    }
+    Text::from(lines)
+}
+
+fn icon(status: ExerciseStatus) -> &'static str {
+    match status {
+        ExerciseStatus::Solved => "🚀",
+        ExerciseStatus::Failed => "❌",
+        ExerciseStatus::Skipped => "⏭ ",
+        ExerciseStatus::Opened => "📖",
+        ExerciseStatus::Unopened => "🔒",
+    }
+}
+
+fn color(status: ExerciseStatus) -> Color {
+    match status {
+        ExerciseStatus::Solved => Color::Green,
+        ExerciseStatus::Failed => Color::Red,
+        ExerciseStatus::Skipped => Color::Blue,
+        ExerciseStatus::Opened => Color::Yellow,
+        ExerciseStatus::Unopened => Color::DarkGray,
+    }
+}
@@ -0,0 +1,530 @@
+//! Check every configuration file of a collection (its `.wr.toml`, as well as the ones of its
+//! chapters and exercises), pointing at the exact location of each problem.
+use crate::{
+    default_exercise_dir, discover_exercises, read_layered_config, ExerciseConfig,
+    ExerciseDefinition, ExercisesConfig, Expect, NamingPattern, Verification,
+};
+use anyhow::Context;
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{Item, TableLike, Value};
+
+/// A problem in a configuration file.
+pub struct Diagnostic {
+    /// The configuration file, relative to the root of the collection.
+    pub path: PathBuf,
+    /// The line and column (both starting from 1) the problem refers to, if it can be pinpointed.
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "{}:{line}:{column}", self.path.display())?,
+            None => write!(f, "{}", self.path.display())?,
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Check the configuration of the collection rooted at `root_dir`, as well as the configuration
+/// of all its chapters and exercises.
+///
+/// Unknown keys, values of the wrong type and invalid values are all reported, rather than
+/// stopping at the first problem.
+pub fn validate(root_dir: &Path) -> Result<Vec<Diagnostic>, anyhow::Error> {
+    let mut diagnostics = vec![];
+
+    let path = root_dir.join(".wr.toml");
+    let source = fs_err::read_to_string(&path).context(
+        "Failed to read the configuration for the current collection of workshop-runner",
+    )?;
+    let mut file = ConfigFile::new(path.clone(), &source);
+    let collection = file.parse::<ExercisesConfig>();
+    let mut included_diagnostics = vec![];
+    let collection = match collection {
+        Some(collection) if !collection.include.is_empty() => {
+            let mut including = vec![fs_err::canonicalize(&path)?];
+            let layered = check_includes(
+                &mut file,
+                &collection.include,
+                &mut including,
+                &mut included_diagnostics,
+            )
+            .then(|| {
+                let layered = read_layered_config(&path).ok()?;
+                toml::Value::Table(layered).try_into().ok()
+            })
+            .flatten();
+            // Settings can come from the included files: check the configuration they add up to.
+            Some(layered.unwrap_or(collection))
+        }
+        collection => collection,
+    };
+    // Chapters and exercises are discovered according to the naming pattern.
+    let mut naming = NamingPattern::default();
+    let exercises_dirs = match &collection {
+        Some(collection) => {
+            if let Some(pattern) = &collection.naming_pattern {
+                match NamingPattern::new(pattern) {
+                    Ok(pattern) => naming = pattern,
+                    Err(e) => file.report("naming_pattern", format!("{e:#}")),
+                }
+            }
+            check_collection(&mut file, collection, root_dir);
+            exercises_dirs(&mut file, collection, root_dir)
+        }
+        None => vec![root_dir.join(default_exercise_dir())],
+    };
+    let exercises = match discover_exercises(&exercises_dirs, &naming) {
+        Ok(exercises) => exercises,
+        Err(e) => {
+            file.report("exercises_dir", e.to_string());
+            BTreeMap::new()
+        }
+    };
+    if let Some(collection) = &collection {
+        check_tracks(&mut file, collection, &exercises);
+    }
+    diagnostics.extend(file.into_diagnostics());
+    diagnostics.extend(included_diagnostics);
+
+    let chapters: BTreeSet<_> = exercises
+        .iter()
+        .map(|(e, exercises_dir)| e.chapter_folder_path(exercises_dir))
+        .collect();
+    for chapter in chapters {
+        let path = chapter.join(".wr.toml");
+        if !path.exists() {
+            continue;
+        }
+        let source = fs_err::read_to_string(&path)?;
+        let mut file = ConfigFile::new(path, &source);
+        if let Some(config) = file.parse::<ExerciseConfig>() {
+            if config.id.is_some() {
+                file.report(
+                    "id",
+                    "`id` must be unique to each exercise: it can't be set for a whole chapter",
+                );
+            }
+            check_exercise(&mut file, &config, None);
+            check_requires(&mut file, &config, &exercises, None);
+        }
+        diagnostics.extend(file.into_diagnostics());
+    }
+    for (exercise, exercises_dir) in &exercises {
+        let folder = exercise.manifest_folder_path(exercises_dir);
+        let path = folder.join(".wr.toml");
+        if !path.exists() {
+            continue;
+        }
+        let source = fs_err::read_to_string(&path)?;
+        let mut file = ConfigFile::new(path, &source);
+        if let Some(config) = file.parse::<ExerciseConfig>() {
+            check_exercise(&mut file, &config, Some(&folder));
+            check_requires(&mut file, &config, &exercises, Some(exercise));
+        }
+        diagnostics.extend(file.into_diagnostics());
+    }
+    for diagnostic in &mut diagnostics {
+        if let Ok(path) = diagnostic.path.strip_prefix(root_dir) {
+            diagnostic.path = path.to_path_buf();
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Check the files included by `file`, and the ones they include in turn.
+///
+/// It returns `false` if they can't be merged into a single configuration, e.g. because one
+/// of them is missing or can't be parsed.
+fn check_includes(
+    file: &mut ConfigFile,
+    includes: &[PathBuf],
+    including: &mut Vec<PathBuf>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> bool {
+    let parent = file.path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let mut ok = true;
+    for (i, include) in includes.iter().enumerate() {
+        let key = format!("include.{i}");
+        let path = parent.join(include);
+        let (Ok(canonical), Ok(source)) =
+            (fs_err::canonicalize(&path), fs_err::read_to_string(&path))
+        else {
+            file.report(&key, format!("{include:?} doesn't exist"));
+            ok = false;
+            continue;
+        };
+        if including.contains(&canonical) {
+            file.report(&key, format!("{include:?} includes this file back"));
+            ok = false;
+            continue;
+        }
+        let mut included = ConfigFile::new(path, &source);
+        match included.parse::<ExercisesConfig>() {
+            Some(config) => {
+                including.push(canonical);
+                ok &= check_includes(&mut included, &config.include, including, diagnostics);
+                including.pop();
+            }
+            None => ok = false,
+        }
+        diagnostics.extend(included.into_diagnostics());
+    }
+    ok
+}
+
+/// The exercises directories of the collection that exist.
+fn exercises_dirs(
+    file: &mut ConfigFile,
+    config: &ExercisesConfig,
+    root_dir: &Path,
+) -> Vec<PathBuf> {
+    let dirs = match config.exercises_dir.resolve(root_dir) {
+        Ok(dirs) => dirs,
+        Err(e) => {
+            file.report("exercises_dir", e.to_string());
+            return vec![];
+        }
+    };
+    dirs.into_iter()
+        .filter(|dir| {
+            let exists = dir.is_dir();
+            if !exists {
+                let dir = dir.strip_prefix(root_dir).unwrap_or(dir);
+                file.report("exercises_dir", format!("{dir:?} is not a directory"));
+            }
+            exists
+        })
+        .collect()
+}
+
+fn check_collection(file: &mut ConfigFile, config: &ExercisesConfig, root_dir: &Path) {
+    if let Some(solutions_dir) = &config.solutions_dir {
+        if !root_dir.join(solutions_dir).is_dir() {
+            file.report(
+                "solutions_dir",
+                format!("{solutions_dir:?} is not a directory"),
+            );
+        }
+    }
+    if config.progress_path.is_some() && config.progress_in_data_dir {
+        file.report(
+            "progress_in_data_dir",
+            "`progress_path` and `progress_in_data_dir` can't be used together",
+        );
+    }
+    if let Some(locale) = &config.locale {
+        if crate::i18n::Locale::parse(locale).is_none() {
+            let supported: Vec<_> = crate::i18n::Locale::ALL.iter().map(|(c, _)| *c).collect();
+            file.report(
+                "locale",
+                format!(
+                    "`{locale}` is not supported, use one of: {}",
+                    supported.join(", ")
+                ),
+            );
+        }
+    }
+    if config.timeout_secs == Some(0) {
+        file.report("timeout_secs", "`timeout_secs` must be greater than 0");
+    }
+    if config.sandbox.is_none() {
+        if config.sandbox_image.is_some() {
+            file.report(
+                "sandbox_image",
+                "`sandbox_image` has no effect unless `sandbox` is set",
+            );
+        }
+        if !config.sandbox_args.is_empty() {
+            file.report(
+                "sandbox_args",
+                "`sandbox_args` has no effect unless `sandbox` is set",
+            );
+        }
+    }
+    if let Some(url) = &config.sync_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            file.report(
+                "sync_url",
+                "`sync_url` must be an `http://` or `https://` URL",
+            );
+        }
+    }
+    check_verifications(file, &config.verification, config.build.as_ref());
+    for (key, hook) in [
+        ("on_start", &config.on_start),
+        ("on_complete", &config.on_complete),
+    ] {
+        if hook
+            .run
+            .as_ref()
+            .is_some_and(|r| r.command.trim().is_empty())
+        {
+            file.report(&format!("{key}.run.command"), "The command can't be empty");
+        }
+    }
+}
+
+/// Check that tracks only list chapters and exercises of the collection.
+fn check_tracks(
+    file: &mut ConfigFile,
+    config: &ExercisesConfig,
+    exercises: &BTreeMap<ExerciseDefinition, PathBuf>,
+) {
+    for (name, entries) in &config.tracks {
+        if entries.is_empty() {
+            file.report(&format!("tracks.{name}"), "A track can't be empty");
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            if !exercises
+                .keys()
+                .any(|e| e.chapter() == *entry || e.path() == *entry)
+            {
+                file.report(
+                    &format!("tracks.{name}.{i}"),
+                    format!("`{entry}` is neither a chapter nor an exercise, e.g. `01_intro` or `01_intro/00_welcome`"),
+                );
+            }
+        }
+    }
+}
+
+/// Check the configuration of an exercise or, if `folder` is `None`, of a chapter.
+fn check_exercise(file: &mut ConfigFile, config: &ExerciseConfig, folder: Option<&Path>) {
+    if !config.weight.is_finite() || config.weight <= 0.0 {
+        file.report("weight", "`weight` must be a positive number");
+    }
+    if let Some(code) = &config.expected_error {
+        if config.expect != Expect::CompileFail {
+            file.report(
+                "expected_error",
+                "`expected_error` has no effect unless `expect = \"compile-fail\"`",
+            );
+        }
+        let error_code = Regex::new(r"^E\d{4}$").unwrap();
+        if !error_code.is_match(code) {
+            file.report(
+                "expected_error",
+                format!("`{code}` is not a compiler error code, e.g. `E0502`"),
+            );
+        }
+    }
+    if config.target_runner.is_some() && config.target.is_none() {
+        file.report(
+            "target_runner",
+            "`target_runner` has no effect unless `target` is set",
+        );
+    }
+    if let Some(folder) = folder {
+        for (key, path) in [
+            ("stdin_file", &config.stdin_file),
+            ("expected_output", &config.expected_output),
+        ] {
+            if let Some(path) = path {
+                if !folder.join(path).is_file() {
+                    file.report(
+                        key,
+                        format!("{path:?} doesn't exist in the exercise folder"),
+                    );
+                }
+            }
+        }
+    }
+    check_verifications(file, &config.verification, config.build.as_ref());
+}
+
+/// Check that the prerequisites of `exercise` (or, if `None`, of all the exercises of a chapter)
+/// exist.
+fn check_requires(
+    file: &mut ConfigFile,
+    config: &ExerciseConfig,
+    exercises: &BTreeMap<ExerciseDefinition, PathBuf>,
+    exercise: Option<&ExerciseDefinition>,
+) {
+    for (i, required) in config.requires.iter().enumerate() {
+        let key = format!("requires.{i}");
+        if exercise.is_some_and(|e| e.path() == *required) {
+            file.report(&key, "An exercise can't require itself");
+        } else if !exercises.keys().any(|e| e.path() == *required) {
+            file.report(
+                &key,
+                format!("`{required}` is not an exercise, e.g. `01_intro/00_welcome`"),
+            );
+        }
+    }
+}
+
+fn check_verifications(
+    file: &mut ConfigFile,
+    verification: &[Verification],
+    build: Option<&Verification>,
+) {
+    let commands = verification
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (format!("verification.{i}"), v))
+        .chain(build.map(|b| ("build".to_owned(), b)));
+    for (key, command) in commands {
+        if command.command.trim().is_empty() {
+            file.report(&format!("{key}.command"), "The command can't be empty");
+        }
+        if let Some(regex) = &command.expect_stdout_regex {
+            if let Err(e) = Regex::new(regex) {
+                file.report(
+                    &format!("{key}.expect_stdout_regex"),
+                    // The last line of the error is the explanation, the others point at the culprit.
+                    format!(
+                        "Invalid regular expression: {}",
+                        e.to_string()
+                            .lines()
+                            .last()
+                            .unwrap_or_default()
+                            .trim_start_matches("error: ")
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// A configuration file being checked, alongside the problems found so far.
+struct ConfigFile<'a> {
+    path: PathBuf,
+    source: &'a str,
+    /// The syntax tree of the file, to locate the keys problems refer to.
+    document: Option<toml_edit::Document<&'a str>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> ConfigFile<'a> {
+    fn new(path: PathBuf, source: &'a str) -> Self {
+        Self {
+            path,
+            source,
+            document: toml_edit::Document::parse(source).ok(),
+            diagnostics: vec![],
+        }
+    }
+
+    /// Deserialize the file, reporting unknown keys along the way.
+    fn parse<T: DeserializeOwned>(&mut self) -> Option<T> {
+        let mut unknown_keys = vec![];
+        let config = serde_ignored::deserialize(toml::Deserializer::new(self.source), |key| {
+            unknown_keys.push(dotted(&key))
+        });
+        for key in unknown_keys {
+            let message = format!("Unknown key `{key}`");
+            self.report(&key, message);
+        }
+        match config {
+            Ok(config) => Some(config),
+            Err(e) => {
+                let location = e.span().map(|span| self.location(span.start));
+                self.diagnostics.push(Diagnostic {
+                    path: self.path.clone(),
+                    location,
+                    message: e.message().trim().lines().collect::<Vec<_>>().join(", "),
+                });
+                None
+            }
+        }
+    }
+
+    /// Record a problem with the value of `key`, a dotted path such as `verification.0.command`.
+    fn report(&mut self, key: &str, message: impl Into<String>) {
+        let location = self
+            .document
+            .as_ref()
+            .and_then(|document| {
+                let key: Vec<_> = key.split('.').collect();
+                table_span(document.as_table(), &key)
+            })
+            .map(|span| self.location(span.start));
+        self.diagnostics.push(Diagnostic {
+            path: self.path.clone(),
+            location,
+            message: message.into(),
+        });
+    }
+
+    /// The problems found, in the order they appear in the file.
+    fn into_diagnostics(mut self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .sort_by_key(|d| d.location.unwrap_or((usize::MAX, 0)));
+        self.diagnostics
+    }
+
+    /// The line and column of the byte at `offset`.
+    fn location(&self, offset: usize) -> (usize, usize) {
+        let before = &self.source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+}
+
+/// `key` as a dotted path, e.g. `verification.0.command`.
+fn dotted(key: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    match key {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => join(dotted(parent), &index.to_string()),
+        Path::Map { parent, key } => join(dotted(parent), key),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => dotted(parent),
+    }
+}
+
+fn join(parent: String, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+/// The span of the innermost element of `table` along `key` that can be found.
+fn table_span(table: &dyn TableLike, key: &[&str]) -> Option<Range<usize>> {
+    let (first, rest) = key.split_first()?;
+    let (key, item) = table.get_key_value(first)?;
+    if rest.is_empty() {
+        return key.span();
+    }
+    item_span(item, rest).or_else(|| key.span())
+}
+
+fn item_span(item: &Item, key: &[&str]) -> Option<Range<usize>> {
+    match item {
+        Item::Value(value) => value_span(value, key),
+        Item::ArrayOfTables(tables) => {
+            let (index, rest) = key.split_first()?;
+            let table = tables.get(index.parse().ok()?)?;
+            table_span(table, rest).or_else(|| table.span())
+        }
+        _ => table_span(item.as_table_like()?, key),
+    }
+}
+
+fn value_span(value: &Value, key: &[&str]) -> Option<Range<usize>> {
+    match value {
+        Value::Array(values) => {
+            let (index, rest) = key.split_first()?;
+            let value = values.get(index.parse().ok()?)?;
+            if rest.is_empty() {
+                return value.span();
+            }
+            value_span(value, rest).or_else(|| value.span())
+        }
+        Value::InlineTable(table) => table_span(table, key),
+        _ => None,
+    }
+}